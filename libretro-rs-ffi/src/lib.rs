@@ -27,6 +27,73 @@ pub type non_null_retro_hw_get_current_framebuffer_t = unsafe extern "C" fn() ->
 pub type non_null_retro_hw_get_proc_address_t = unsafe extern "C" fn(sym: *const c_char) -> retro_proc_address_t;
 pub type non_null_retro_hw_context_reset_t = unsafe extern "C" fn();
 
+/// Environment commands reserved for RetroArch-specific extensions, which aren't part of
+/// `libretro.h`.
+pub const RETRO_ENVIRONMENT_RETROARCH_START_BLOCK: u32 = 0x800000;
+
+/// `retro_environment_t *` --
+/// Returns a callback that releases the frontend's threads from any waits, so threaded cores
+/// can tear down without deadlocking. Calling it with a non-zero value clears the waits, and
+/// calling it with zero restores them.
+pub const RETRO_ENVIRONMENT_GET_CLEAR_ALL_THREAD_WAITS_CB: u32 =
+  3 | RETRO_ENVIRONMENT_RETROARCH_START_BLOCK;
+
+/// `struct retro_microphone_interface *` --
+/// Returns an interface for reading audio from the frontend's microphones. The core sets
+/// `interface_version` to [`RETRO_MICROPHONE_INTERFACE_VERSION`], and the frontend fills in
+/// the rest. This command is newer than the bundled `libretro.h`.
+pub const RETRO_ENVIRONMENT_GET_MICROPHONE_INTERFACE: u32 = 75 | RETRO_ENVIRONMENT_EXPERIMENTAL;
+
+pub const RETRO_MICROPHONE_INTERFACE_VERSION: c_uint = 1;
+
+/// An opaque handle to a microphone opened by the frontend.
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct retro_microphone {
+  _unused: [u8; 0],
+}
+pub type retro_microphone_t = retro_microphone;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct retro_microphone_params {
+  pub rate: c_uint,
+}
+pub type retro_microphone_params_t = retro_microphone_params;
+
+pub type retro_open_mic_t =
+  Option<unsafe extern "C" fn(params: *const retro_microphone_params_t) -> *mut retro_microphone_t>;
+pub type retro_close_mic_t = Option<unsafe extern "C" fn(microphone: *mut retro_microphone_t)>;
+pub type retro_get_mic_params_t = Option<
+  unsafe extern "C" fn(
+    microphone: *const retro_microphone_t,
+    params: *mut retro_microphone_params_t,
+  ) -> bool,
+>;
+pub type retro_set_mic_state_t =
+  Option<unsafe extern "C" fn(microphone: *mut retro_microphone_t, state: bool) -> bool>;
+pub type retro_get_mic_state_t =
+  Option<unsafe extern "C" fn(microphone: *const retro_microphone_t) -> bool>;
+pub type retro_read_mic_t = Option<
+  unsafe extern "C" fn(
+    microphone: *mut retro_microphone_t,
+    samples: *mut i16,
+    num_samples: usize,
+  ) -> c_int,
+>;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct retro_microphone_interface {
+  pub interface_version: c_uint,
+  pub open_mic: retro_open_mic_t,
+  pub close_mic: retro_close_mic_t,
+  pub get_params: retro_get_mic_params_t,
+  pub set_mic_state: retro_set_mic_state_t,
+  pub get_mic_state: retro_get_mic_state_t,
+  pub read_mic: retro_read_mic_t,
+}
+
 pub const RETRO_HW_FRAME_BUFFER_VALID: *const c_void = sptr::invalid(usize::MAX);
 
 #[cfg(test)]
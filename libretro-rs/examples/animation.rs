@@ -0,0 +1,101 @@
+use libretro_rs::prelude::*;
+
+pub const WIDTH: u16 = 320;
+pub const HEIGHT: u16 = 240;
+pub const RECT_SIZE: u16 = 32;
+pub const RECT_COLOR: u32 = 0x00FFFFFF;
+pub const TEXT_COLOR: u32 = 0x00FFFF00;
+
+/// Renders a scrolling gradient with a bouncing rectangle and a frame counter
+/// on top, using a [Vec]-backed [SliceFrameBuffer].
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  format: ActiveFormat<XRGB8888>,
+  frame_buffer: SliceFrameBuffer<Vec<XRGB8888>>,
+  frame: u32,
+}
+
+impl Core {
+  fn draw(&mut self) {
+    let frame = self.frame;
+    // Blue scrolls horizontally over time, green fades in from top to bottom.
+    for (y, row) in self
+      .frame_buffer
+      .chunks_exact_mut(WIDTH as usize)
+      .enumerate()
+    {
+      let green = (y * 255 / HEIGHT as usize) as u32;
+      for (x, pixel) in row.iter_mut().enumerate() {
+        let blue = (x as u32).wrapping_add(frame) % 256;
+        *pixel = XRGB8888::new_with_raw_value(green << 8 | blue);
+      }
+    }
+
+    let x = bounce(frame * 3, (WIDTH - RECT_SIZE) as u32);
+    let y = bounce(frame * 2, (HEIGHT - RECT_SIZE) as u32);
+    let rect_color = XRGB8888::new_with_raw_value(RECT_COLOR);
+    self
+      .frame_buffer
+      .fill_rect(x, y, RECT_SIZE, RECT_SIZE, rect_color);
+
+    let text_color = XRGB8888::new_with_raw_value(TEXT_COLOR);
+    self
+      .frame_buffer
+      .draw_text(4, 4, &format!("FRAME {frame}"), text_color);
+  }
+}
+
+/// Maps `t` onto a triangle wave between 0 and `max`.
+fn bounce(t: u32, max: u32) -> i32 {
+  let t = t % (2 * max);
+  (if t <= max { t } else { 2 * max - t }) as i32
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(
+      c_utf8!("libretro-rs animation example"),
+      library_version!(env!("CARGO_PKG_VERSION")),
+      ext![],
+    )
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {
+    ()
+  }
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let format = env.set_pixel_format_xrgb8888(pixel_format)?;
+    let pixels = vec![XRGB8888::default(); WIDTH as usize * HEIGHT as usize];
+    let frame_buffer = SliceFrameBuffer::with_width(pixels, WIDTH)?;
+    Ok(Core { rendering_mode, format, frame_buffer, frame: 0 })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(WIDTH, HEIGHT))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    self.draw();
+    self.frame = self.frame.wrapping_add(1);
+    callbacks.upload_video_frame(&self.rendering_mode, &self.format, &self.frame_buffer);
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {
+    self.frame = 0;
+  }
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {
+    ()
+  }
+}
+
+libretro_core!(crate::Core);
@@ -72,7 +72,7 @@ impl<'a> Core<'a> for LibretroCore {
   fn get_system_info() -> SystemInfo {
     SystemInfo::new(
       c_utf8!("chip8.rs"),
-      c_utf8!(env!("CARGO_PKG_VERSION")),
+      library_version!(env!("CARGO_PKG_VERSION")),
       ext!["png"],
     )
   }
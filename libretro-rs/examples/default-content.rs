@@ -0,0 +1,64 @@
+use libretro_rs::prelude::*;
+
+pub const WIDTH: u16 = 320;
+pub const HEIGHT: u16 = 240;
+pub const TEXT_COLOR: u32 = 0x00FFFFFF;
+
+/// Shows a text file, or a built-in message if the core is started without
+/// content. The built-in message is loaded through [Core::load_game] like any
+/// other content, so the core only has one loading path.
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  format: ActiveFormat<XRGB8888>,
+  frame_buffer: SliceFrameBuffer<Vec<XRGB8888>>,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  // Also makes SUPPORTS_NO_GAME default to true.
+  const DEFAULT_CONTENT: Option<&'static [u8]> = Some(b"No content loaded.\nShowing the demo.");
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(
+      c_utf8!("libretro-rs default content example"),
+      library_version!(env!("CARGO_PKG_VERSION")),
+      ext!["txt"],
+    )
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_game<E: env::LoadGame>(
+    game: &GameInfo,
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let format = env.set_pixel_format_xrgb8888(pixel_format)?;
+    let text = match game.as_ref() {
+      GameInfoKind::Data(data) => String::from_utf8_lossy(data.data()).into_owned(),
+      GameInfoKind::Path(path) => std::fs::read_to_string(path.path().as_str())?,
+    };
+    let pixels = vec![XRGB8888::DEFAULT; WIDTH as usize * HEIGHT as usize];
+    let mut frame_buffer = SliceFrameBuffer::with_width(pixels, WIDTH)?;
+    let color = XRGB8888::new_with_raw_value(TEXT_COLOR);
+    frame_buffer.draw_text(8, 8, &text, color);
+    Ok(Core { rendering_mode, format, frame_buffer })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(WIDTH, HEIGHT))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let polled = callbacks.poll_inputs();
+    callbacks.upload_video_frame(&self.rendering_mode, &self.format, &self.frame_buffer);
+    polled
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+libretro_core!(crate::Core);
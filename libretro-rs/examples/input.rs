@@ -0,0 +1,67 @@
+use libretro_rs::prelude::*;
+
+/// The number of users assumed when the frontend doesn't say how many it
+/// supports.
+pub const DEFAULT_MAX_USERS: usize = 2;
+
+/// Reads the gamepad of every user the frontend supports, and fills the screen
+/// with a brighter shade of gray for each user holding a button.
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  format: ActiveFormat<XRGB8888>,
+  /// One gamepad per user, sized when the game is loaded.
+  gamepads: Vec<Gamepad>,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(
+      c_utf8!("libretro-rs input example"),
+      library_version!(env!("CARGO_PKG_VERSION")),
+      ext![],
+    )
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let format = env.set_pixel_format_xrgb8888(pixel_format)?;
+    let max_users = env
+      .get_input_max_users()
+      .map_or(DEFAULT_MAX_USERS, |max_users| max_users as usize);
+    Ok(Core {
+      rendering_mode,
+      format,
+      gamepads: vec![Gamepad::default(); max_users],
+    })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(16, 16))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let polled = callbacks.poll_inputs();
+    for (port, gamepad) in (0..).zip(&mut self.gamepads) {
+      *gamepad = callbacks.read_gamepad(DevicePort::new(port));
+    }
+    let held = self.gamepads.iter().filter(|gamepad| gamepad.buttons != 0);
+    let shade = (held.count() * 0xFF / self.gamepads.len().max(1)) as u32;
+    let color = XRGB8888::new_with_raw_value(shade << 16 | shade << 8 | shade);
+    callbacks.upload_solid_frame(&self.rendering_mode, &self.format, 16, 16, color);
+    polled
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+libretro_core!(crate::Core);
@@ -0,0 +1,65 @@
+use libretro_rs::prelude::*;
+
+pub const SAMPLE_RATE: u32 = 44_100;
+pub const FPS: u32 = 60;
+pub const SAMPLES_PER_FRAME: usize = (SAMPLE_RATE / FPS) as usize;
+
+/// Plays back what the microphone records, like an emulated system with a
+/// microphone peripheral would read it. Without a microphone, it reads (and
+/// plays) silence.
+struct Core {
+  microphone: Option<Microphone>,
+  samples: [i16; SAMPLES_PER_FRAME],
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(
+      c_utf8!("libretro-rs microphone example"),
+      library_version!(env!("CARGO_PKG_VERSION")),
+      ext![],
+    )
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, .. } = args;
+    let microphone = env
+      .get_microphone_interface()
+      .and_then(|iface| iface.open_mic(Some(SAMPLE_RATE)).ok())
+      .and_then(|mut microphone| microphone.set_active(true).map(|_| microphone).ok());
+    Ok(Core { microphone, samples: [0; SAMPLES_PER_FRAME] })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    let timing = SystemTiming::new(FPS.into(), SAMPLE_RATE.into());
+    SystemAVInfo::new(GameGeometry::fixed(1, 1), timing)
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let polled = callbacks.poll_inputs();
+    let read = match &mut self.microphone {
+      Some(microphone) => microphone.read(&mut self.samples).unwrap_or(0),
+      None => 0,
+    };
+    // Whatever wasn't recorded is silence.
+    self.samples[read..].fill(0);
+    for &sample in &self.samples {
+      callbacks.frame_audio().push_frame(sample, sample);
+    }
+    polled
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+libretro_core!(crate::Core);
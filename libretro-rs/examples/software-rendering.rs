@@ -7,24 +7,23 @@ pub const FRAMEBUFFER_SIZE: usize = FRAMEBUFFER_WIDTH as usize * FRAMEBUFFER_HEI
 struct Core {
   rendering_mode: SoftwareRenderEnabled,
   format: ActiveFormat<XRGB8888>,
-  frame_buffer: ArrayFrameBuffer<XRGB8888, FRAMEBUFFER_SIZE, FRAMEBUFFER_WIDTH>,
+  can_dupe: bool,
+  frame_buffer: DirtyTracked<ArrayFrameBuffer<XRGB8888, FRAMEBUFFER_SIZE, FRAMEBUFFER_WIDTH>>,
 }
 
 impl<'a> retro::Core<'a> for Core {
   type Init = ();
 
+  const SUPPORTS_NO_GAME: bool = true;
+
   fn get_system_info() -> SystemInfo {
     SystemInfo::new(
       c_utf8!("libretro-rs software rendering example"),
-      c_utf8!(env!("CARGO_PKG_VERSION")),
+      library_version!(env!("CARGO_PKG_VERSION")),
       ext![],
     )
   }
 
-  fn set_environment(env: &mut impl env::SetEnvironment) {
-    env.set_support_no_game(true).ok();
-  }
-
   fn init(_env: &mut impl env::Init) -> Self::Init {
     ()
   }
@@ -36,6 +35,7 @@ impl<'a> retro::Core<'a> for Core {
     let mut logger = FallbackLogger::new(env.get_log_interface().ok());
     logger.info(c_utf8!("Loading game."));
     let format = env.set_pixel_format_xrgb8888(pixel_format)?;
+    let can_dupe = env.get_can_dupe().unwrap_or(false);
     logger.info(c_utf8!("Game loaded."));
     let framebuffer = ArrayFrameBuffer::new([
       XRGB8888::new_with_raw_value(0x000000FF),
@@ -43,7 +43,12 @@ impl<'a> retro::Core<'a> for Core {
       XRGB8888::new_with_raw_value(0x00FF0000),
       XRGB8888::new_with_raw_value(0x00FFFFFF),
     ]);
-    Ok(Core { rendering_mode, format, frame_buffer: framebuffer })
+    Ok(Core {
+      rendering_mode,
+      format,
+      can_dupe,
+      frame_buffer: DirtyTracked::new(framebuffer),
+    })
   }
 
   fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
@@ -51,8 +56,19 @@ impl<'a> retro::Core<'a> for Core {
   }
 
   fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
-    callbacks.upload_video_frame(&self.rendering_mode, &self.format, &self.frame_buffer);
-    callbacks.poll_inputs()
+    let polled = callbacks.poll_inputs();
+    // Rotate the pixels while A is held.
+    if callbacks.is_joypad_button_pressed(DevicePort::new(0), JoypadButton::A) {
+      self.frame_buffer.rotate_right(1);
+    }
+    // Only upload the frame if it changed; otherwise let the frontend reuse
+    // the previous one, if it can.
+    if self.frame_buffer.take_dirty() || !self.can_dupe {
+      callbacks.upload_video_frame(&self.rendering_mode, &self.format, &*self.frame_buffer);
+    } else {
+      callbacks.repeat_video_frame();
+    }
+    polled
   }
 
   fn reset(&mut self, _env: &mut impl env::Reset) {}
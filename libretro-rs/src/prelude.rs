@@ -1,17 +1,24 @@
 pub use crate::c_utf8::c_utf8;
 pub use crate::retro;
+pub use crate::retro::audio::*;
 pub use crate::retro::av::*;
+pub use crate::retro::core_options::*;
 pub use crate::retro::device::*;
 pub use crate::retro::env::Environment;
 pub use crate::retro::error::*;
 pub use crate::retro::fs::*;
 pub use crate::retro::game::*;
 pub use crate::retro::hw_render::*;
+pub use crate::retro::input::*;
 pub use crate::retro::log::*;
 pub use crate::retro::mem::*;
+pub use crate::retro::mic::*;
 pub use crate::retro::pixel::format::*;
+pub use crate::retro::pixel::ConvertPixel;
+pub use crate::retro::state::*;
 pub use crate::retro::str::*;
+pub use crate::retro::vfs::*;
 pub use crate::retro::video::*;
 pub use crate::retro::*;
 pub use crate::retro::{env, pixel};
-pub use crate::{ext, libretro_core};
+pub use crate::{enum_option, ext, library_version, libretro_core};
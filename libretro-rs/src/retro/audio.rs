@@ -0,0 +1,178 @@
+use std::ops::{Deref, DerefMut};
+
+/// A buffer of interleaved stereo samples (left, right, left, right, ...) in
+/// the signed 16-bit format expected by the libretro audio callbacks.
+/// Automatically dereferences to a slice of samples, so it can be passed
+/// directly to [`Callbacks::upload_audio_frame`](crate::retro::Callbacks::upload_audio_frame).
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let mut buffer = AudioBuffer::new();
+/// buffer.push_frame(i16::MIN, i16::MAX);
+/// assert_eq!(buffer.frames(), 1);
+/// assert_eq!(&buffer[..], &[i16::MIN, i16::MAX]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AudioBuffer(Vec<i16>);
+
+impl AudioBuffer {
+  /// Creates an empty buffer.
+  pub const fn new() -> Self {
+    Self(Vec::new())
+  }
+
+  /// Creates an empty buffer with room for `frames` stereo frames.
+  pub fn with_capacity(frames: usize) -> Self {
+    Self(Vec::with_capacity(frames * 2))
+  }
+
+  /// Creates a buffer from `f32` samples in the range `-1.0..=1.0`, as used by
+  /// most DSP code. Samples are scaled by 32768 and clamped to the range of
+  /// [`i16`], so out of range values clip instead of wrapping around.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let buffer = AudioBuffer::from_f32(&[-1.0, 1.0, 0.5, 0.0, -2.0, 2.0]);
+  /// assert_eq!(&buffer[..], &[i16::MIN, i16::MAX, 16384, 0, i16::MIN, i16::MAX]);
+  /// ```
+  pub fn from_f32(samples: &[f32]) -> Self {
+    Self(samples.iter().map(|&sample| f32_to_i16(sample)).collect())
+  }
+
+  /// Converts the samples to `f32`, scaling them by 1/32768 so that the result
+  /// is in the range `-1.0..1.0`.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let buffer = AudioBuffer::from(vec![i16::MIN, i16::MAX, 16384, 0]);
+  /// assert_eq!(buffer.to_f32(), [-1.0, 32767.0 / 32768.0, 0.5, 0.0]);
+  /// ```
+  pub fn to_f32(&self) -> Vec<f32> {
+    self.0.iter().map(|&sample| i16_to_f32(sample)).collect()
+  }
+
+  /// Appends a single stereo frame.
+  pub fn push_frame(&mut self, left: i16, right: i16) {
+    self.0.push(left);
+    self.0.push(right);
+  }
+
+  /// Appends interleaved stereo samples.
+  pub fn push_samples(&mut self, samples: &[i16]) {
+    self.0.extend_from_slice(samples)
+  }
+
+  /// Returns the number of complete stereo frames in the buffer.
+  pub fn frames(&self) -> usize {
+    self.0.len() / 2
+  }
+
+  /// Removes all samples, keeping the allocated capacity.
+  pub fn clear(&mut self) {
+    self.0.clear()
+  }
+
+  /// Consumes this buffer and returns the underlying samples.
+  pub fn into_inner(self) -> Vec<i16> {
+    self.0
+  }
+}
+
+impl Deref for AudioBuffer {
+  type Target = [i16];
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl DerefMut for AudioBuffer {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.0
+  }
+}
+
+impl AsRef<[i16]> for AudioBuffer {
+  fn as_ref(&self) -> &[i16] {
+    &self.0
+  }
+}
+
+impl AsMut<[i16]> for AudioBuffer {
+  fn as_mut(&mut self) -> &mut [i16] {
+    &mut self.0
+  }
+}
+
+impl From<Vec<i16>> for AudioBuffer {
+  fn from(samples: Vec<i16>) -> Self {
+    Self(samples)
+  }
+}
+
+impl From<AudioBuffer> for Vec<i16> {
+  fn from(buffer: AudioBuffer) -> Self {
+    buffer.into_inner()
+  }
+}
+
+/// Audio accumulated over a single call to [`Core::run`](crate::retro::Core::run).
+///
+/// Cores that push their samples here, via
+/// [`Callbacks::frame_audio`](crate::retro::Callbacks::frame_audio), don't
+/// need to upload audio themselves: once `run` returns, everything pushed
+/// during the frame is sent to the frontend in a single batch, and the
+/// accumulator is emptied for the next frame. Cores that never push anything
+/// are unaffected, so uploading audio manually still works.
+///
+/// Dereferences to the [AudioBuffer] that accumulates the samples.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let mut audio = FrameAudio::new();
+/// audio.push_frame(1, 2);
+/// audio.push_samples(&[3, 4, 5, 6]);
+/// assert_eq!(audio.frames(), 3);
+/// assert_eq!(audio.samples(), &[1, 2, 3, 4, 5, 6]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FrameAudio(AudioBuffer);
+
+impl FrameAudio {
+  /// Creates an empty accumulator.
+  pub const fn new() -> Self {
+    Self(AudioBuffer::new())
+  }
+
+  /// Returns the interleaved samples accumulated so far.
+  pub fn samples(&self) -> &[i16] {
+    &self.0
+  }
+}
+
+impl Deref for FrameAudio {
+  type Target = AudioBuffer;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl DerefMut for FrameAudio {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.0
+  }
+}
+
+fn i16_to_f32(sample: i16) -> f32 {
+  sample as f32 / 32768.0
+}
+
+fn f32_to_i16(sample: f32) -> i16 {
+  // NaN is converted to 0 by the cast.
+  (sample * 32768.0).clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
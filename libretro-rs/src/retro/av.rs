@@ -1,4 +1,7 @@
 use crate::ffi::*;
+use crate::retro::env::Environment;
+use crate::retro::error::CoreError;
+use crate::retro::log::RetroLogLevel;
 use core::ffi::*;
 use core::ops::*;
 use std::convert::Into;
@@ -38,6 +41,32 @@ impl SystemAVInfo {
     Self::new(geometry, SystemTiming::default())
   }
 
+  /// Returns a [`SystemAVInfo`] whose frame rate matches the refresh rate the
+  /// frontend is targeting (see [`Environment::get_target_refresh_rate`]), so
+  /// the core can run natively on e.g. 120Hz displays. Falls back to the
+  /// default frame rate if the frontend doesn't report a valid refresh rate.
+  ///
+  /// Only use this if the core can actually run at an arbitrary frame rate;
+  /// most emulators are tied to the timing of the system they emulate.
+  pub fn matching_refresh_rate(
+    env: &impl Environment,
+    geometry: GameGeometry,
+    sample_rate: f64,
+  ) -> Self {
+    let fps = env
+      .get_target_refresh_rate()
+      .ok()
+      .filter(|rate| rate.is_finite() && *rate > 0.0)
+      .map_or(SystemTiming::default().fps(), f64::from);
+    Self::new(geometry, SystemTiming::new(fps, sample_rate))
+  }
+
+  /// Returns a [SystemAVInfoBuilder] for `geometry`, which validates the
+  /// timing and geometry before building the [SystemAVInfo].
+  pub fn builder(geometry: GameGeometry) -> SystemAVInfoBuilder {
+    SystemAVInfoBuilder::new(geometry)
+  }
+
   pub fn geometry(&self) -> GameGeometry {
     GameGeometry(self.0.geometry)
   }
@@ -63,6 +92,104 @@ impl From<SystemAVInfo> for retro_system_av_info {
   }
 }
 
+/// Builds a [SystemAVInfo], checking that the timing and geometry are usable
+/// by the frontend. A zero frame rate or sample rate in particular makes
+/// frontends divide by zero or silently fail to output anything, which is easy
+/// to miss while bringing up a core.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let av_info = SystemAVInfo::builder(GameGeometry::fixed(320, 240))
+///   .fps(59.94)
+///   .sample_rate(48_000.0)
+///   .build()
+///   .unwrap();
+/// assert_eq!(av_info.timing().fps(), 59.94);
+///
+/// let err = SystemAVInfo::builder(GameGeometry::fixed(320, 240)).fps(0.0).build();
+/// assert_eq!(err.unwrap_err(), SystemAVInfoError::InvalidFps(0.0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SystemAVInfoBuilder {
+  geometry: GameGeometry,
+  timing: SystemTiming,
+}
+
+impl SystemAVInfoBuilder {
+  /// Creates a builder for `geometry`, with the default [SystemTiming].
+  pub fn new(geometry: GameGeometry) -> Self {
+    Self { geometry, timing: SystemTiming::default() }
+  }
+
+  /// Sets the frame rate, in frames per second.
+  pub fn fps(mut self, fps: f64) -> Self {
+    self.timing.0.fps = fps;
+    self
+  }
+
+  /// Sets the audio sample rate, in Hz.
+  pub fn sample_rate(mut self, sample_rate: f64) -> Self {
+    self.timing.0.sample_rate = sample_rate;
+    self
+  }
+
+  /// Sets both the frame rate and the sample rate.
+  pub fn timing(mut self, timing: SystemTiming) -> Self {
+    self.timing = timing;
+    self
+  }
+
+  /// Returns the [SystemAVInfo], or the first problem found with it.
+  pub fn build(self) -> Result<SystemAVInfo, SystemAVInfoError> {
+    let (fps, sample_rate) = (self.timing.fps(), self.timing.sample_rate());
+    if !(fps.is_finite() && fps > 0.0) {
+      return Err(SystemAVInfoError::InvalidFps(fps));
+    }
+    if !(sample_rate.is_finite() && sample_rate > 0.0) {
+      return Err(SystemAVInfoError::InvalidSampleRate(sample_rate));
+    }
+    let geometry = &self.geometry;
+    if geometry.base_width() == 0 || geometry.base_height() == 0 {
+      return Err(SystemAVInfoError::EmptyGeometry);
+    }
+    if geometry.base_width() > geometry.max_width()
+      || geometry.base_height() > geometry.max_height()
+    {
+      return Err(SystemAVInfoError::BaseExceedsMax);
+    }
+    // Non-positive aspect ratios are valid, and mean "derive from the base size".
+    if !geometry.aspect_ratio().is_finite() {
+      return Err(SystemAVInfoError::InvalidAspectRatio(
+        geometry.aspect_ratio(),
+      ));
+    }
+    Ok(SystemAVInfo::new(self.geometry, self.timing))
+  }
+}
+
+/// A problem found by [SystemAVInfoBuilder::build].
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, thiserror::Error)]
+pub enum SystemAVInfoError {
+  #[error("the frame rate must be positive and finite, but it was {0}")]
+  InvalidFps(f64),
+  #[error("the sample rate must be positive and finite, but it was {0}")]
+  InvalidSampleRate(f64),
+  #[error("the base width and height must be non-zero")]
+  EmptyGeometry,
+  #[error("the base width and height must not exceed the max width and height")]
+  BaseExceedsMax,
+  #[error("the aspect ratio must be finite, but it was {0}")]
+  InvalidAspectRatio(f32),
+}
+
+impl From<SystemAVInfoError> for CoreError {
+  fn from(value: SystemAVInfoError) -> Self {
+    CoreError::with_source(value)
+  }
+}
+
 /// Rust interface for [`retro_game_geometry`].
 #[repr(transparent)]
 #[derive(Clone, Debug)]
@@ -87,7 +214,9 @@ impl GameGeometry {
     Self::new(width, height, 0.0)
   }
 
-  /// Main constructor.
+  /// Main constructor. `width` and `height` range from the base size to the
+  /// max size. A non-positive `aspect_ratio` asks the frontend to derive it
+  /// from the base size.
   pub fn new(width: RangeInclusive<u16>, height: RangeInclusive<u16>, aspect_ratio: f32) -> Self {
     Self(retro_game_geometry {
       base_width: c_uint::from(*width.start()),
@@ -118,6 +247,41 @@ impl GameGeometry {
     self.0.aspect_ratio
   }
 
+  /// Returns the aspect ratio the frontend displays frames at: the aspect
+  /// ratio if it's positive, or else `base_width / base_height`, as
+  /// `libretro.h` specifies.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// assert_eq!(GameGeometry::fixed(320, 240).effective_aspect_ratio(), 4.0 / 3.0);
+  /// let geometry = GameGeometry::fixed(320, 224).with_aspect_ratio(4.0 / 3.0);
+  /// assert_eq!(geometry.effective_aspect_ratio(), 4.0 / 3.0);
+  /// ```
+  pub fn effective_aspect_ratio(&self) -> f32 {
+    match self.0.aspect_ratio > 0.0 {
+      true => self.0.aspect_ratio,
+      false => self.0.base_width as f32 / self.0.base_height as f32,
+    }
+  }
+
+  /// Sets the aspect ratio frames are displayed at, e.g. 4:3 for a 320x224
+  /// frame with non-square pixels. A non-positive value asks the frontend to
+  /// derive it from the base size.
+  pub fn with_aspect_ratio(mut self, aspect_ratio: f32) -> Self {
+    self.0.aspect_ratio = aspect_ratio;
+    self
+  }
+
+  /// Sets the max size of frames, for cores that change their resolution
+  /// with [`Run::set_geometry`](crate::retro::env::Run::set_geometry). The
+  /// base size is unchanged.
+  pub fn with_max_size(mut self, max_width: u16, max_height: u16) -> Self {
+    self.0.max_width = max_width.into();
+    self.0.max_height = max_height.into();
+    self
+  }
+
   pub fn into_inner(self) -> retro_game_geometry {
     self.0
   }
@@ -243,3 +407,141 @@ impl From<Message> for retro_message {
     value.0
   }
 }
+
+/// Rust interface for [`retro_message_ext`]: a [Message] with a severity and
+/// priority, whose duration is in milliseconds instead of frames. See
+/// [`Environment::set_message_ext`].
+#[repr(transparent)]
+#[derive(Clone, Debug)]
+pub struct MessageExt(retro_message_ext);
+
+impl MessageExt {
+  /// Creates a notification displayed for `duration` milliseconds, both on
+  /// screen and in the frontend's log, at the [RetroLogLevel::Info] level.
+  pub fn new<'a>(msg: impl Into<&'a CStr>, duration: c_uint) -> Self {
+    Self(retro_message_ext {
+      msg: msg.into().as_ptr(),
+      duration,
+      priority: 0,
+      level: retro_log_level::RETRO_LOG_INFO,
+      target: retro_message_target::RETRO_MESSAGE_TARGET_ALL,
+      type_: retro_message_type::RETRO_MESSAGE_TYPE_NOTIFICATION,
+      progress: -1,
+    })
+  }
+
+  /// Sets the severity of the message.
+  pub fn with_level(mut self, level: RetroLogLevel) -> Self {
+    self.0.level = level.into();
+    self
+  }
+
+  /// Sets the priority of the message; messages with a higher priority replace
+  /// the ones being displayed.
+  pub fn with_priority(mut self, priority: c_uint) -> Self {
+    self.0.priority = priority;
+    self
+  }
+
+  pub fn msg(&self) -> &CStr {
+    unsafe { CStr::from_ptr(self.0.msg) }
+  }
+
+  pub fn duration(&self) -> c_uint {
+    self.0.duration
+  }
+
+  pub fn priority(&self) -> c_uint {
+    self.0.priority
+  }
+
+  pub fn level(&self) -> retro_log_level {
+    self.0.level
+  }
+}
+
+impl From<MessageExt> for retro_message_ext {
+  fn from(value: MessageExt) -> Self {
+    value.0
+  }
+}
+
+/// Rust interface for [`retro_fastforwarding_override`]. Lets a core take
+/// control of the frontend's fast-forwarding mode, via
+/// [`Environment::set_fastforwarding_override`].
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// // Fast-forward at up to 4x speed, without the frontend's notification.
+/// let ff = FastForwardingOverride::new(true)
+///   .set_ratio(4.0)
+///   .set_notification(false);
+/// assert!(ff.fastforward());
+/// assert_eq!(ff.ratio(), 4.0);
+/// assert!(!ff.inhibit_toggle());
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+pub struct FastForwardingOverride(retro_fastforwarding_override);
+
+impl FastForwardingOverride {
+  /// Enables or disables fast-forwarding at the frontend's default speed, with
+  /// a notification, while still letting the user toggle it.
+  pub fn new(fastforward: bool) -> Self {
+    Self(retro_fastforwarding_override {
+      ratio: -1.0,
+      fastforward,
+      notification: true,
+      inhibit_toggle: false,
+    })
+  }
+
+  /// Sets the maximum speed multiplier while fast-forwarding. Values between
+  /// 0.0 and 1.0 uncap the speed; negative values let the frontend decide.
+  pub fn set_ratio(mut self, ratio: f32) -> Self {
+    self.0.ratio = ratio;
+    self
+  }
+
+  /// Sets whether fast-forwarding is enabled.
+  pub fn set_fastforward(mut self, fastforward: bool) -> Self {
+    self.0.fastforward = fastforward;
+    self
+  }
+
+  /// Sets whether the frontend displays a notification while fast-forwarding.
+  pub fn set_notification(mut self, notification: bool) -> Self {
+    self.0.notification = notification;
+    self
+  }
+
+  /// If true, the frontend can't toggle fast-forwarding until this is reset
+  /// to false or the core is unloaded.
+  pub fn set_inhibit_toggle(mut self, inhibit_toggle: bool) -> Self {
+    self.0.inhibit_toggle = inhibit_toggle;
+    self
+  }
+
+  pub fn ratio(&self) -> f32 {
+    self.0.ratio
+  }
+
+  pub fn fastforward(&self) -> bool {
+    self.0.fastforward
+  }
+
+  pub fn notification(&self) -> bool {
+    self.0.notification
+  }
+
+  pub fn inhibit_toggle(&self) -> bool {
+    self.0.inhibit_toggle
+  }
+}
+
+impl From<FastForwardingOverride> for retro_fastforwarding_override {
+  fn from(value: FastForwardingOverride) -> Self {
+    value.0
+  }
+}
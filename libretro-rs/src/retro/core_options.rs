@@ -0,0 +1,593 @@
+//! Helpers for declaring core options (a.k.a. variables) and reading them back.
+
+use crate::ffi::*;
+use crate::retro::av::Message;
+use crate::retro::env::Environment;
+#[cfg(doc)]
+use crate::retro::env::SetEnvironment;
+use c_utf8::CUtf8;
+use core::ffi::{c_char, CStr};
+use std::ffi::CString;
+
+/// The most values libretro allows for a core option.
+const MAX_VALUES: usize = 128;
+
+/// An integer core option, whose values range from `min` to `max` (inclusive)
+/// in increments of `step`.
+///
+/// libretro options are strings, so this generates the list of values the
+/// frontend shows (e.g. `"0 ms|10 ms|20 ms"`) and parses the selected value
+/// back into an integer with [Environment::get_int_option].
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let latency = IntOption::new("mycore_latency", "Audio latency", 0, 30)
+///   .step(10)
+///   .default(20)
+///   .units("ms");
+/// assert_eq!(latency.values(), ["20 ms", "0 ms", "10 ms", "30 ms"]);
+/// assert_eq!(latency.parse(c_utf8!("10 ms").as_ref()), 10);
+/// assert_eq!(latency.parse(c_utf8!("99").as_ref()), 30);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntOption {
+  key: CString,
+  description: String,
+  min: i64,
+  max: i64,
+  step: i64,
+  default: i64,
+  units: Option<String>,
+}
+
+impl IntOption {
+  /// Creates an option ranging from `min` to `max` in steps of 1, defaulting to
+  /// `min`. `description` must not contain `;`, since it delimits the
+  /// description from the values.
+  ///
+  /// # Panics
+  /// Panics if `key` contains a NUL byte.
+  pub fn new(key: &str, description: &str, min: i64, max: i64) -> Self {
+    Self {
+      key: CString::new(key).expect("option keys must not contain NUL bytes"),
+      description: description.into(),
+      min: min.min(max),
+      max: max.max(min),
+      step: 1,
+      default: min.min(max),
+      units: None,
+    }
+  }
+
+  /// Sets the increment between values. Non-positive steps are treated as 1.
+  pub fn step(mut self, step: i64) -> Self {
+    self.step = step.max(1);
+    self
+  }
+
+  /// Sets the default value, which is clamped to the option's range.
+  pub fn default(mut self, default: i64) -> Self {
+    self.default = default.clamp(self.min, self.max);
+    self
+  }
+
+  /// Sets a label shown after each value, e.g. `"ms"` or `"%"`.
+  pub fn units(mut self, units: &str) -> Self {
+    self.units = Some(units.into());
+    self
+  }
+
+  pub fn key(&self) -> &CStr {
+    &self.key
+  }
+
+  pub fn min(&self) -> i64 {
+    self.min
+  }
+
+  pub fn max(&self) -> i64 {
+    self.max
+  }
+
+  pub fn default_value(&self) -> i64 {
+    self.default
+  }
+
+  /// Returns the values shown by the frontend, with the default value first
+  /// (as the frontend treats the first value as the default). `max` is always
+  /// included, even if it isn't a multiple of `step` away from `min`.
+  ///
+  /// libretro allows at most 128 values, so the step is widened for ranges
+  /// that would have more.
+  pub fn values(&self) -> Vec<String> {
+    // At most `limit + 1` steps, leaving room for `max` and the default, which
+    // may be off the steps.
+    let span = self.max as i128 - self.min as i128;
+    let limit = MAX_VALUES as i128 - 3;
+    let step = (self.step as i128).max((span + limit - 1) / limit);
+    let steps = (0..=span / step).map(|i| (self.min as i128 + i * step) as i64);
+    let mut values: Vec<i64> = steps.filter(|&n| n != self.default).collect();
+    if values.last() != Some(&self.max) && self.max != self.default {
+      values.push(self.max);
+    }
+    values.insert(0, self.default);
+    values.into_iter().map(|n| self.format(n)).collect()
+  }
+
+  /// Returns the string passed to the frontend as the option's value, i.e. the
+  /// description followed by the list of [values](IntOption::values).
+  pub fn to_value_string(&self) -> CString {
+    let value = format!("{}; {}", self.description, self.values().join("|"));
+    CString::new(value).unwrap_or_default()
+  }
+
+  /// Parses the integer at the start of `value`, ignoring the units, and clamps
+  /// it to the option's range. Returns the default value if there's no number.
+  pub fn parse(&self, value: &CStr) -> i64 {
+    let value = value.to_str().unwrap_or_default().trim_start();
+    let len = value
+      .char_indices()
+      .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && (c == '-' || c == '+'))))
+      .map_or(value.len(), |(i, _)| i);
+    match value[..len].parse::<i64>() {
+      Ok(n) => n.clamp(self.min, self.max),
+      Err(_) => self.default,
+    }
+  }
+
+  fn format(&self, n: i64) -> String {
+    match &self.units {
+      Some(units) => format!("{n} {units}"),
+      None => n.to_string(),
+    }
+  }
+}
+
+/// A core option whose values are the variants of an enum, declared with
+/// [enum_option!](crate::enum_option). Each variant is stored by the frontend
+/// as its name, and shown with its label.
+pub trait EnumOption: Copy + Eq + 'static {
+  /// The option's key, e.g. `"mycore_region"`.
+  const KEY: &'static CUtf8;
+  /// The description shown by the frontend, e.g. `"Console region"`.
+  const DESCRIPTION: &'static CUtf8;
+  /// Every variant, in declaration order.
+  const VARIANTS: &'static [Self];
+
+  /// Returns the variant marked as `default`, or the first variant if there
+  /// isn't one.
+  fn default_variant() -> Self;
+
+  /// Returns the string the frontend stores for this variant.
+  fn value(&self) -> &'static CUtf8;
+
+  /// Returns the string the frontend shows for this variant.
+  fn label(&self) -> &'static CUtf8;
+
+  /// Returns the stored value of each variant, in declaration order.
+  fn values() -> Vec<&'static CUtf8> {
+    Self::VARIANTS.iter().map(Self::value).collect()
+  }
+
+  /// Returns the variant stored as `value`, or the default variant if none
+  /// matches.
+  fn parse(value: &CStr) -> Self {
+    let variant = Self::VARIANTS
+      .iter()
+      .find(|variant| variant.value().as_c_str() == value);
+    variant.copied().unwrap_or_else(Self::default_variant)
+  }
+
+  /// Returns the option's `RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2` definition.
+  /// All of its strings are static. libretro allows at most 128 values, and
+  /// any variants beyond that are left out.
+  fn to_v2_definition() -> retro_core_option_v2_definition {
+    let mut definition = retro_core_option_v2_definition {
+      key: Self::KEY.as_ptr(),
+      desc: Self::DESCRIPTION.as_ptr(),
+      default_value: Self::default_variant().value().as_ptr(),
+      ..Default::default()
+    };
+    // The last entry is left null to terminate the list.
+    let slots = definition.values.len() - 1;
+    for (slot, variant) in definition.values[..slots].iter_mut().zip(Self::VARIANTS) {
+      *slot = retro_core_option_value {
+        value: variant.value().as_ptr(),
+        label: variant.label().as_ptr(),
+      };
+    }
+    definition
+  }
+}
+
+/// The core options and option categories registered with
+/// [SetEnvironment::set_core_options_v2], built from ordinary string slices.
+///
+/// The strings are copied, and the raw `retro_core_options_v2` returned by
+/// [CoreOptions::as_raw] points into them, so it's valid for as long as the
+/// [CoreOptions] isn't dropped.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let options = CoreOptions::new()
+///   .category("system", "System", "Emulated hardware settings.")
+///   .option(
+///     CoreOption::new("mycore_overclock", "System > CPU overclock")
+///       .category("system", "CPU overclock")
+///       .info("Speeds up the emulated CPU, which reduces slowdown in some games.")
+///       .value("1x")
+///       .value("2x")
+///       .labeled_value("4x", "4x (unstable)")
+///       .default("1x"),
+///   )
+///   .option(
+///     CoreOption::new("mycore_palette", "Palette")
+///       .labeled_value("grey", "Greyscale")
+///       .labeled_value("green", "Original"),
+///   );
+/// assert_eq!(options.len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct CoreOptions {
+  /// Owns the strings the raw categories and definitions point to.
+  strings: Vec<CString>,
+  /// Terminated by a null category.
+  categories: Vec<retro_core_option_v2_category>,
+  /// Terminated by a null definition.
+  definitions: Vec<retro_core_option_v2_definition>,
+  /// The key and description of each option marked with
+  /// [CoreOption::restart_required].
+  restart_required: Vec<(CString, CString)>,
+}
+
+impl Default for CoreOptions {
+  fn default() -> Self {
+    Self {
+      strings: Vec::new(),
+      categories: vec![Default::default()],
+      definitions: vec![Default::default()],
+      restart_required: Vec::new(),
+    }
+  }
+}
+
+impl CoreOptions {
+  /// Creates an empty set of options, without categories.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a category, which options join with [CoreOption::category]. `desc`
+  /// is its label, and `info` an optional (possibly empty) sublabel.
+  pub fn category(mut self, key: &str, desc: &str, info: &str) -> Self {
+    let category = retro_core_option_v2_category {
+      key: self.intern(key),
+      desc: self.intern(desc),
+      info: self.intern(info),
+    };
+    self.categories.insert(self.categories.len() - 1, category);
+    self
+  }
+
+  /// Adds an option. libretro allows at most 128 values, and any values beyond
+  /// that are left out.
+  pub fn option(mut self, option: CoreOption<'_>) -> Self {
+    let suffix = match option.restart_required {
+      true => RESTART_SUFFIX,
+      false => "",
+    };
+    let desc_categorized = option
+      .desc_categorized
+      .map(|desc| format!("{desc}{suffix}"));
+    let mut definition = retro_core_option_v2_definition {
+      key: self.intern(option.key),
+      desc: self.intern(&format!("{}{suffix}", option.desc)),
+      desc_categorized: self.intern_opt(desc_categorized.as_deref()),
+      info: self.intern_opt(option.info),
+      category_key: self.intern_opt(option.category),
+      default_value: self.intern_opt(option.default),
+      ..Default::default()
+    };
+    // The last entry is left null to terminate the list.
+    let slots = definition.values.len() - 1;
+    for (slot, &(value, label)) in definition.values[..slots].iter_mut().zip(&option.values) {
+      *slot = retro_core_option_value {
+        value: self.intern(value),
+        label: self.intern_opt(label),
+      };
+    }
+    if option.restart_required {
+      self.restart_required.push((
+        CString::new(option.key).unwrap_or_default(),
+        CString::new(option.desc).unwrap_or_default(),
+      ));
+    }
+    self.push_definition(definition)
+  }
+
+  /// Adds the option declared by `T`. See [EnumOption].
+  pub fn enum_option<T: EnumOption>(self) -> Self {
+    self.push_definition(T::to_v2_definition())
+  }
+
+  /// Returns the number of options.
+  pub fn len(&self) -> usize {
+    self.definitions.len() - 1
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Returns the keys of the options, in the order they were added.
+  pub fn keys(&self) -> impl Iterator<Item = &CStr> {
+    let definitions = &self.definitions[..self.len()];
+    definitions
+      .iter()
+      .map(|definition| unsafe { CStr::from_ptr(definition.key) })
+  }
+
+  /// Returns the keys of the options marked with
+  /// [CoreOption::restart_required], in the order they were added.
+  pub fn restart_required_keys(&self) -> impl Iterator<Item = &CStr> {
+    self.restart_required.iter().map(|(key, _)| key.as_c_str())
+  }
+
+  /// Returns the options as passed to the frontend. The pointers are valid
+  /// until `self` is dropped.
+  pub fn as_raw(&self) -> retro_core_options_v2 {
+    retro_core_options_v2 {
+      categories: self.categories.as_ptr() as *mut _,
+      definitions: self.definitions.as_ptr() as *mut _,
+    }
+  }
+
+  fn push_definition(mut self, definition: retro_core_option_v2_definition) -> Self {
+    self
+      .definitions
+      .insert(self.definitions.len() - 1, definition);
+    self
+  }
+
+  /// Copies `string` into `self`, returning a pointer that's valid for as
+  /// long as `self` is. Strings with NUL bytes are replaced by empty strings.
+  fn intern(&mut self, string: &str) -> *const c_char {
+    let string = CString::new(string).unwrap_or_default();
+    // Moving the CString doesn't move its buffer.
+    let ptr = string.as_ptr();
+    self.strings.push(string);
+    ptr
+  }
+
+  fn intern_opt(&mut self, string: Option<&str>) -> *const c_char {
+    match string {
+      Some(string) => self.intern(string),
+      None => core::ptr::null(),
+    }
+  }
+}
+
+/// An option added to [CoreOptions], whose values are strings.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoreOption<'a> {
+  key: &'a str,
+  desc: &'a str,
+  desc_categorized: Option<&'a str>,
+  info: Option<&'a str>,
+  category: Option<&'a str>,
+  values: Vec<(&'a str, Option<&'a str>)>,
+  default: Option<&'a str>,
+  restart_required: bool,
+}
+
+impl<'a> CoreOption<'a> {
+  /// Creates an option without values. `key` is what the core passes to
+  /// [Environment::get_variable], and `desc` the label shown by the frontend.
+  pub fn new(key: &'a str, desc: &'a str) -> Self {
+    Self { key, desc, ..Default::default() }
+  }
+
+  /// Sets the sublabel shown by the frontend.
+  pub fn info(mut self, info: &'a str) -> Self {
+    self.info = Some(info);
+    self
+  }
+
+  /// Puts the option in the category with key `category`. Frontends that
+  /// support categories show the option as `desc` instead of the description
+  /// passed to [CoreOption::new], which usually includes the category.
+  pub fn category(mut self, category: &'a str, desc: &'a str) -> Self {
+    self.category = Some(category);
+    self.desc_categorized = Some(desc);
+    self
+  }
+
+  /// Adds a value, shown as is.
+  pub fn value(mut self, value: &'a str) -> Self {
+    self.values.push((value, None));
+    self
+  }
+
+  /// Adds a value, shown as `label`.
+  pub fn labeled_value(mut self, value: &'a str, label: &'a str) -> Self {
+    self.values.push((value, Some(label)));
+    self
+  }
+
+  /// Sets the default value, which must be one of the option's values. The
+  /// first value is the default otherwise.
+  pub fn default(mut self, value: &'a str) -> Self {
+    self.default = Some(value);
+    self
+  }
+
+  /// Marks the option as only taking effect once the game is restarted.
+  /// libretro has no flag for this, so as is customary, the descriptions
+  /// shown by the frontend get a ` (Restart)` suffix. Use a [RestartTracker]
+  /// to tell the user when such an option changes.
+  pub fn restart_required(mut self) -> Self {
+    self.restart_required = true;
+    self
+  }
+}
+
+/// The suffix added to the descriptions of options that require a restart.
+const RESTART_SUFFIX: &str = " (Restart)";
+
+/// Detects changes to the options marked with [CoreOption::restart_required],
+/// by comparing their values with the ones they had when the tracker was
+/// created, i.e. the ones the game was loaded with.
+///
+/// Create it in `load_game`, after reading the options, and call
+/// [RestartTracker::notify] whenever the options change (see
+/// [Environment::variable_update]).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestartTracker {
+  options: Vec<TrackedOption>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TrackedOption {
+  key: CString,
+  desc: CString,
+  /// The value the game was loaded with.
+  applied: Option<CString>,
+  /// The value the user was last told about, to only notify them once.
+  notified: Option<CString>,
+}
+
+impl RestartTracker {
+  /// Records the current values of the options in `options` that require a
+  /// restart.
+  pub fn new(options: &CoreOptions, env: &impl Environment) -> Self {
+    let options = options.restart_required.iter().map(|(key, desc)| {
+      let applied = current_value(env, key);
+      TrackedOption {
+        key: key.clone(),
+        desc: desc.clone(),
+        notified: applied.clone(),
+        applied,
+      }
+    });
+    Self { options: options.collect() }
+  }
+
+  /// Returns the keys of the options whose value differs from the one the
+  /// game was loaded with.
+  pub fn changed(&self, env: &impl Environment) -> Vec<&CStr> {
+    let options = self.options.iter();
+    let changed = options.filter(|option| current_value(env, &option.key) != option.applied);
+    changed.map(|option| option.key.as_c_str()).collect()
+  }
+
+  /// Shows a message asking the user to restart the game if an option that
+  /// requires it changed since the previous call. Returns true if a message
+  /// was shown.
+  ///
+  /// Each new value is only reported once, and setting an option back to the
+  /// value the game was loaded with doesn't show a message.
+  pub fn notify(&mut self, env: &mut impl Environment) -> bool {
+    let mut descs = Vec::new();
+    for option in &mut self.options {
+      let value = current_value(env, &option.key);
+      if value != option.notified {
+        if value != option.applied {
+          descs.push(option.desc.to_string_lossy().into_owned());
+        }
+        option.notified = value;
+      }
+    }
+    if descs.is_empty() {
+      return false;
+    }
+    let text = format!("Restart the game to apply: {}", descs.join(", "));
+    let text = CString::new(text).unwrap_or_default();
+    env.set_message(&Message::new(text.as_c_str(), 180)).is_ok()
+  }
+}
+
+fn current_value(env: &impl Environment, key: &CStr) -> Option<CString> {
+  env.get_variable(&key).ok().flatten().map(CStr::to_owned)
+}
+
+/// Declares an enum whose variants are the values of a core option, and
+/// implements [EnumOption] for it. The enum takes an
+/// `#[option(key = "...", desc = "...")]` attribute, and each variant an
+/// `#[option(label = "...")]` attribute, with `default` added to the default
+/// variant. Other attributes can follow the `#[option]` attributes.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::enum_option;
+/// use libretro_rs::prelude::*;
+///
+/// enum_option! {
+///   #[option(key = "mycore_region", desc = "Console region")]
+///   pub enum Region {
+///     #[option(default, label = "Automatic")]
+///     Auto,
+///     #[option(label = "NTSC (60 Hz)")]
+///     Ntsc,
+///     #[option(label = "PAL (50 Hz)")]
+///     Pal,
+///   }
+/// }
+///
+/// assert_eq!(Region::values(), [c_utf8!("Auto"), c_utf8!("Ntsc"), c_utf8!("Pal")]);
+/// assert_eq!(Region::Ntsc.label(), c_utf8!("NTSC (60 Hz)"));
+/// assert_eq!(Region::parse(c_utf8!("Pal").as_c_str()), Region::Pal);
+/// assert_eq!(Region::parse(c_utf8!("SECAM").as_c_str()), Region::Auto);
+/// ```
+#[macro_export]
+macro_rules! enum_option {
+  (@label $(default,)? label = $label:literal $(, default)?) => { $label };
+  (@is_default default, $($rest:tt)*) => { true };
+  (@is_default label = $label:literal, default) => { true };
+  (@is_default $($rest:tt)*) => { false };
+  (
+    #[option(key = $key:literal, desc = $desc:literal $(,)?)]
+    $(#[$meta:meta])*
+    $vis:vis enum $name:ident {
+      $(
+        #[option($($flags:tt)*)]
+        $(#[$variant_meta:meta])*
+        $variant:ident
+      ),* $(,)?
+    }
+  ) => {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    $(#[$meta])*
+    $vis enum $name {
+      $( $(#[$variant_meta])* $variant ),*
+    }
+
+    impl $crate::retro::core_options::EnumOption for $name {
+      const KEY: &'static $crate::c_utf8::CUtf8 = $crate::c_utf8::c_utf8!($key);
+      const DESCRIPTION: &'static $crate::c_utf8::CUtf8 = $crate::c_utf8::c_utf8!($desc);
+      const VARIANTS: &'static [Self] = &[$(Self::$variant),*];
+
+      fn default_variant() -> Self {
+        $(
+          if $crate::enum_option!(@is_default $($flags)*) {
+            return Self::$variant;
+          }
+        )*
+        Self::VARIANTS[0]
+      }
+
+      fn value(&self) -> &'static $crate::c_utf8::CUtf8 {
+        match self {
+          $( Self::$variant => $crate::c_utf8::c_utf8!(stringify!($variant)) ),*
+        }
+      }
+
+      fn label(&self) -> &'static $crate::c_utf8::CUtf8 {
+        match self {
+          $( Self::$variant => $crate::c_utf8::c_utf8!($crate::enum_option!(@label $($flags)*)) ),*
+        }
+      }
+    }
+  };
+}
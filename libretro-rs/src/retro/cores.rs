@@ -8,23 +8,76 @@
 //!
 //! Cores should be well-behaved - they shouldn't panic, crash, or invoke
 //! undefined behavior, lest they take the frontend down with it.
-//! Likewise, cores shouldn't request a shutdown in lieu of panicking.
+//! Likewise, cores shouldn't request a shutdown in lieu of panicking. As a
+//! last resort, panics in [`Core::run`] are caught if the core is built with
+//! `panic = "unwind"`; see [`Core::REPORT_PANICS`].
 //! If a core loads content successfully, it should continue to function until
 //! the user unloads it or shuts down the frontend.
 
 use crate::ffi::*;
 use crate::prelude::*;
+use core::cell::Cell;
 use core::ffi::*;
 use core::mem::MaybeUninit;
 use core::ops::*;
 use core::slice;
+use std::collections::VecDeque;
 use std::marker::PhantomData;
+use std::sync::Mutex;
+
+/// Keyboard events received from the frontend, waiting to be passed to
+/// [`Core::keyboard_event`] at the start of the next `retro_run`.
+static KEYBOARD_QUEUE: Mutex<VecDeque<(bool, Key, u32, KeyModifiers)>> =
+  Mutex::new(VecDeque::new());
+
+/// Drops keyboard events that weren't delivered, e.g. because the game was
+/// unloaded.
+fn clear_keyboard_events() {
+  if let Ok(mut queue) = KEYBOARD_QUEUE.lock() {
+    queue.clear();
+  }
+}
 
 /// A basic libretro core.
 #[allow(unused_variables)]
 pub trait Core<'a>: Sized {
   type Init: Sized + 'a;
 
+  /// Whether the core can be started without content. If true, the
+  /// [`libretro_core`] macro tells the frontend during `retro_set_environment`,
+  /// so cores don't need to call [`env::SetEnvironment::set_support_no_game`]
+  /// themselves, and [`Core::load_without_content`] is called when the player
+  /// starts the core without a game.
+  ///
+  /// Defaults to true if the core has [`Core::DEFAULT_CONTENT`].
+  const SUPPORTS_NO_GAME: bool = Self::DEFAULT_CONTENT.is_some();
+
+  /// Content built into the core (e.g. a demo), which is loaded when the
+  /// player starts the core without a game. If set, the default
+  /// [`Core::load_without_content`] passes it to [`Core::load_game`] as
+  /// [`GameData`] without a path, so the same code loads both the player's
+  /// content and the built-in one. See the `default-content` example.
+  const DEFAULT_CONTENT: Option<&'static [u8]> = None;
+
+  /// Whether to tell the player when [`Core::run`] panics. The [`libretro_core`]
+  /// macro catches panics in `run` instead of letting them unwind into the
+  /// frontend, logs them, and stops running the core until the game is
+  /// unloaded. If true, it also displays a message, so the player doesn't
+  /// just see a frozen screen.
+  ///
+  /// Panics can only be caught if the core is built with `panic = "unwind"`
+  /// (the default). With `panic = "abort"`, a panic aborts the frontend
+  /// before anything is reported, and this has no effect.
+  const REPORT_PANICS: bool = true;
+
+  /// Whether the core receives keyboard events. If true, the [`libretro_core`]
+  /// macro registers a keyboard callback during `retro_set_environment` (see
+  /// [`env::SetEnvironment::set_keyboard_callback`]), and
+  /// [`Core::keyboard_event`] is called for every event while a game is
+  /// loaded. Events are queued as they arrive and delivered at the start of
+  /// the next `retro_run`, since frontends may send them from another thread.
+  const KEYBOARD_EVENTS: bool = false;
+
   /// Called to get information about the core. This information can then be displayed in a frontend, or used to
   /// construct core-specific paths.
   fn get_system_info() -> SystemInfo;
@@ -35,7 +88,9 @@ pub trait Core<'a>: Sized {
   /// Called during `retro_init`.
   fn init(env: &mut impl env::Init) -> Self::Init;
 
-  /// Called during `retro_load_game` when a core doesn't require a game's path.
+  /// Called during `retro_load_game` when content is loaded. The content is
+  /// passed by path instead of as data if the core
+  /// [needs the full path](SystemInfo::with_need_full_path).
   fn load_game<E: env::LoadGame>(
     game: &GameInfo,
     args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
@@ -43,10 +98,18 @@ pub trait Core<'a>: Sized {
     Err(CoreError::new())
   }
 
+  /// Called during `retro_load_game` when the player starts the core without
+  /// a game, if [`Core::SUPPORTS_NO_GAME`] is true.
+  ///
+  /// The default implementation loads [`Core::DEFAULT_CONTENT`] with
+  /// [`Core::load_game`], or fails if the core doesn't have any.
   fn load_without_content<E: env::LoadGame>(
     args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
   ) -> Result<Self, CoreError> {
-    Err(CoreError::new())
+    match Self::DEFAULT_CONTENT {
+      Some(data) => Self::load_game(&GameInfo::from_data(GameData::new(data, None, None)), args),
+      None => Err(CoreError::new()),
+    }
   }
 
   fn get_system_av_info(&self, env: &mut impl env::GetAvInfo) -> SystemAVInfo;
@@ -55,11 +118,38 @@ pub trait Core<'a>: Sized {
   ///
   /// The core is expected to advance emulation by a single frame before returning.
   /// The core must call [`Callbacks::poll_inputs`] at least once.
+  ///
+  /// Audio pushed to [`Callbacks::frame_audio`] is uploaded once this returns.
   fn run(&mut self, env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled;
 
   /// Called when a player resets their game.
+  ///
+  /// The game stays loaded, and nothing is renegotiated with the frontend: the
+  /// pixel format, core options and memory maps set while loading it remain in
+  /// effect, so only the emulation state needs to be reset.
   fn reset(&mut self, env: &mut impl env::Reset);
 
+  /// Called when a key is pressed (`down`) or released, if
+  /// [`Core::KEYBOARD_EVENTS`] is true. `character` is the UTF-32 character
+  /// typed, or 0 if there isn't any; frontends may send it in a separate
+  /// event with [`Key::Unknown`], e.g. for characters composed with an input
+  /// method.
+  ///
+  /// Events are delivered in order, on the thread calling [`Core::run`], just
+  /// before it's called.
+  fn keyboard_event(&mut self, down: bool, key: Key, character: u32, modifiers: KeyModifiers) {}
+
+  /// Called during `retro_get_memory_data` and `retro_get_memory_size` to get
+  /// a memory region (e.g. save RAM), or [None] if the core doesn't have it.
+  /// Frontends read and write the region directly, so it should be the memory
+  /// the core actually uses.
+  ///
+  /// Only called if the core doesn't implement [`GetMemoryRegionCore`], which
+  /// takes precedence.
+  fn get_memory_region(&mut self, id: MemoryType) -> Option<&mut [u8]> {
+    None
+  }
+
   /// Called during `retro_unload_game`.
   ///
   /// This will be called before either `retro_deinit` or `retro_load_game`
@@ -80,14 +170,17 @@ pub struct LoadGameExtraArgs<'init, 'function, Env, Init> {
   pub pixel_format: ActiveFormat<ORGB1555>,
 }
 
-/// Save state functions.
+/// Save state functions. [`StateWriter`](crate::retro::state::StateWriter)
+/// can implement both `serialize_size` and `serialize` with a single function
+/// that writes the state directly into the frontend's buffer.
 pub trait SaveStateCore<'a>: Core<'a> {
-  /// Called to determine the size of the save state buffer. This is only ever called once per run, and the core must
-  /// not exceed the size returned here for subsequent saves.
+  /// Called to determine the size of the save state buffer, by the frontend and before every call to `serialize`. The
+  /// core must not exceed the size returned here for subsequent saves.
   fn serialize_size(&self, env: &mut impl env::SerializeSize) -> core::num::NonZeroUsize;
 
-  /// Allows a core to save its internal state into the specified buffer. The buffer is guaranteed to be at least `size`
-  /// bytes, where `size` is the value returned from `serialize_size`.
+  /// Allows a core to save its internal state into the specified buffer. The buffer is exactly `size` bytes, where
+  /// `size` is the value returned from `serialize_size`; if the frontend's buffer is smaller, the save fails without
+  /// calling this.
   fn serialize(&self, env: &mut impl env::Serialize, data: &mut [u8]) -> Result<(), CoreError>;
 
   /// Allows a core to load its internal state from the specified buffer. The buffer is guaranteed to be at least `size`
@@ -127,9 +220,22 @@ pub trait CheatsCore<'a>: Core<'a> {
 }
 
 /// Functions for getting memory regions (e.g. save RAM.)
+///
+/// Frontends read and write these regions directly, e.g. to persist save RAM
+/// or to apply cheats, so they should be the memory the core actually uses.
+/// The [`StandardMemoryType`]s are the common regions; subsystems can define
+/// their own.
 pub trait GetMemoryRegionCore<'a>: Core<'a> {
-  fn get_memory_size(&self, env: &mut impl env::GetMemorySize, id: MemoryType) -> usize;
+  /// Returns the size of the region, in bytes, or 0 if the core doesn't have
+  /// it. The default implementation returns the length of the region returned
+  /// by [`GetMemoryRegionCore::get_memory_data`].
+  fn get_memory_size(&self, env: &mut impl env::GetMemorySize, id: MemoryType) -> usize {
+    self.get_memory_data(env, id).map_or(0, |data| data.len())
+  }
 
+  /// Returns the region, or [None] if the core doesn't have it, in which case
+  /// the frontend gets a null pointer.
+  #[allow(clippy::mut_from_ref)]
   fn get_memory_data(&self, env: &mut impl env::GetMemoryData, id: MemoryType)
     -> Option<&mut [u8]>;
 }
@@ -153,10 +259,113 @@ pub trait RegionAwareCore<'a>: Core<'a> {
   fn get_region(&self, env: &mut impl env::GetRegion) -> Region;
 }
 
-/// OpenGL context management functions.
+/// Disk control functions, for content that spans multiple disk images.
+///
+/// The [`libretro_core`] macro registers these during `retro_set_environment`.
+/// If the frontend supports version 1 or later of the disk control interface,
+/// the extended interface is registered, which includes
+/// [`DiskControlCore::set_initial_image`], [`DiskControlCore::get_image_path`]
+/// and [`DiskControlCore::get_image_label`]; otherwise, only the original
+/// interface is registered and those functions are never called.
+#[allow(unused_variables)]
+pub trait DiskControlCore<'a>: Core<'a> {
+  /// Returns true if the virtual disk tray is open.
+  fn get_eject_state(&self, env: &mut impl env::DiskControl) -> bool;
+
+  /// Opens or closes the virtual disk tray.
+  fn set_eject_state(
+    &mut self,
+    env: &mut impl env::DiskControl,
+    ejected: bool,
+  ) -> Result<(), CoreError>;
+
+  /// Returns the index of the inserted disk image.
+  fn get_image_index(&self, env: &mut impl env::DiskControl) -> c_uint;
+
+  /// Inserts the disk image at `index`. Only called while the tray is open.
+  /// An index greater than or equal to [`DiskControlCore::get_num_images`]
+  /// means "no disk".
+  fn set_image_index(
+    &mut self,
+    env: &mut impl env::DiskControl,
+    index: c_uint,
+  ) -> Result<(), CoreError>;
+
+  /// Returns the number of disk images available.
+  fn get_num_images(&self, env: &mut impl env::DiskControl) -> c_uint;
+
+  /// Replaces the disk image at `index`, or removes it if `game` is [`None`].
+  /// Only called while the tray is open.
+  fn replace_image_index(
+    &mut self,
+    env: &mut impl env::DiskControl,
+    index: c_uint,
+    game: Option<&GameInfo>,
+  ) -> Result<(), CoreError>;
+
+  /// Appends an empty disk image slot, to be filled by
+  /// [`DiskControlCore::replace_image_index`].
+  fn add_image_index(&mut self, env: &mut impl env::DiskControl) -> Result<(), CoreError>;
+
+  /// Called before `retro_load_game` with the disk image the frontend would
+  /// like to start with. Cores should cache the values in `init_state` and
+  /// use them while loading, falling back to the first image if `index` is
+  /// out of range or `path` doesn't match the image at `index`.
+  fn set_initial_image(
+    env: &mut impl env::DiskControl,
+    init_state: &mut Self::Init,
+    index: c_uint,
+    path: &CStr,
+  ) -> Result<(), CoreError> {
+    Err(CoreError::new())
+  }
+
+  /// Returns the path of the disk image at `index`, if available. The path is
+  /// copied into a buffer provided by the frontend (e.g. to remember the disk
+  /// of an M3U playlist that was in use), and is ignored if it doesn't fit.
+  ///
+  /// Since the path is borrowed from the core, it has to be stored in the core
+  /// (e.g. when loading the playlist) rather than built on demand.
+  fn get_image_path(&self, env: &mut impl env::DiskControl, index: c_uint) -> Option<&CStr> {
+    None
+  }
+
+  /// Returns a user-friendly label for the disk image at `index`, if available.
+  /// Like [`DiskControlCore::get_image_path`], the label is copied to the
+  /// frontend.
+  fn get_image_label(&self, env: &mut impl env::DiskControl, index: c_uint) -> Option<&CStr> {
+    None
+  }
+}
+
+/// OpenGL context management functions, for cores that called
+/// [`LoadGame::set_hw_render_gl`](env::LoadGame::set_hw_render_gl).
+///
+/// Both functions are called by the frontend on the thread that runs the core,
+/// with the context current. The context is only current during these
+/// functions and [`Core::run`], so cores rendering on their own thread must
+/// hand the work over to it from there.
 pub unsafe trait OpenGLCore<'a>: Core<'a> {
+  /// Called when the context has been created, or recreated after being lost
+  /// (e.g. on a video driver change). Textures, shaders and buffers must be
+  /// (re)loaded here, with the functions resolved through `callbacks`.
   fn context_reset(&mut self, env: &mut impl env::Environment, callbacks: GLContextCallbacks);
 
+  /// Called before the context is destroyed, while it's still current, so the
+  /// core can release its GL objects.
+  fn context_destroy(&mut self, env: &mut impl env::Environment);
+}
+
+/// Vulkan context management functions, for cores that called
+/// [`LoadGame::set_hw_render_vulkan`](env::LoadGame::set_hw_render_vulkan).
+pub trait VulkanCore<'a>: Core<'a> {
+  /// Called when the context has been created, or recreated after being
+  /// destroyed. The device and queues are available from
+  /// [`Environment::get_hw_render_interface`](env::Environment::get_hw_render_interface).
+  fn context_reset(&mut self, env: &mut impl env::Environment);
+
+  /// Called before the context is destroyed. Every Vulkan object created by
+  /// the core must be released here.
   fn context_destroy(&mut self, env: &mut impl env::Environment);
 }
 
@@ -186,16 +395,34 @@ impl SystemInfo {
     })
   }
 
+  /// Makes the frontend pass archives (e.g. zip files) as is, rather than
+  /// extracting the content from them first.
   pub fn with_block_extract(mut self) -> Self {
     self.0.block_extract = true;
     self
   }
 
+  /// Makes the frontend pass the content by path instead of loading it in
+  /// memory, e.g. for large CD images. [`Core::load_game`] then gets a
+  /// [GameInfo] without data, whose [GamePath::path] the core has to open
+  /// itself.
   pub fn with_need_full_path(mut self) -> Self {
     self.0.need_fullpath = true;
     self
   }
 
+  /// Replaces the library version, e.g. with one that identifies the exact
+  /// build the core was compiled from. The version is typically created with
+  /// [`library_version!`], with a git revision set by the core's build script:
+  /// `library_version!(env!("CARGO_PKG_VERSION"), env!("GIT_HASH"))`.
+  pub fn with_library_version<T>(mut self, library_version: &'static T) -> Self
+  where
+    T: AsRef<CStr> + ?Sized,
+  {
+    self.0.library_version = library_version.as_ref().as_ptr();
+    self
+  }
+
   pub fn library_name(&self) -> &'static CStr {
     unsafe { CStr::from_ptr(self.0.library_name) }
   }
@@ -233,16 +460,87 @@ impl From<SystemInfo> for retro_system_info {
   }
 }
 
+/// Panics if `version` isn't a valid `library_version`: it must be non-empty
+/// and must not contain any spaces or nul bytes. Called in a const context by
+/// [`library_version!`] so that invalid versions are rejected at compile time.
+#[doc(hidden)]
+pub const fn assert_library_version(version: &str) {
+  let bytes = version.as_bytes();
+  assert!(!bytes.is_empty(), "library version must not be empty");
+  let mut i = 0;
+  while i < bytes.len() {
+    assert!(bytes[i] != b' ', "library version must not contain spaces");
+    assert!(
+      bytes[i] != b'\0',
+      "library version must not contain nul bytes"
+    );
+    i += 1;
+  }
+}
+
+/// Converts a version string literal into a static C string suitable for
+/// [`SystemInfo::new`], checking at compile time that it's a valid libretro
+/// library version. An optional second argument (e.g. a git revision) is
+/// appended after a `-`.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::library_version;
+/// assert_eq!(library_version!("1.0.2").as_str(), "1.0.2");
+/// assert_eq!(library_version!("1.0.2", "a1b2c3d").as_str(), "1.0.2-a1b2c3d");
+/// ```
+///
+/// Versions containing spaces fail to compile:
+/// ```compile_fail
+/// use libretro_rs::library_version;
+/// use libretro_rs::prelude::*;
+/// let version = library_version!("1.0 beta");
+/// ```
+#[macro_export]
+macro_rules! library_version {
+  ( $version:expr ) => {{
+    const _: () = $crate::retro::assert_library_version($version);
+    $crate::c_utf8::c_utf8!($version)
+  }};
+  ( $version:expr , $revision:expr ) => {
+    $crate::library_version!(concat!($version, "-", $revision))
+  };
+}
+
 pub trait Callbacks {
-  /// Sends audio data to the `libretro` frontend.
+  /// Sends audio data to the `libretro` frontend. See
+  /// [`Callbacks::upload_audio_frames`].
   fn upload_audio_frame(&mut self, frame: &[i16]) -> usize;
 
-  /// Sends audio data to the `libretro` frontend.
+  /// Sends interleaved stereo samples (left, right, left, right, ...) to the
+  /// `libretro` frontend, and returns the number of frames (i.e. pairs of
+  /// samples) it consumed.
+  ///
+  /// `samples.len()` should be even; if it isn't, the last sample is ignored.
+  /// To upload the audio of a whole frame at once, see
+  /// [`Callbacks::frame_audio`].
+  fn upload_audio_frames(&mut self, samples: &[i16]) -> usize {
+    self.upload_audio_frame(samples)
+  }
+
+  /// Sends a single stereo frame to the `libretro` frontend, for cores that
+  /// generate one frame at a time. Prefer [`Callbacks::upload_audio_frames`]
+  /// or [`Callbacks::frame_audio`] if possible, since frontends handle batches
+  /// more efficiently.
   fn upload_audio_sample(&mut self, left: i16, right: i16);
 
+  /// Returns the audio accumulated during the current frame, which is
+  /// uploaded in a single batch after [`Core::run`] returns. See [`FrameAudio`].
+  fn frame_audio(&mut self) -> &mut FrameAudio;
+
   /// Sends video data to the `libretro` frontend.
   /// Must not be called if hardware rendering is used;
   /// call `use_hardware_frame_buffer` instead.
+  ///
+  /// # Panics
+  /// In debug builds, panics if the pixels of `framebuffer` aren't the size of
+  /// the pixels of the negotiated format `P`, which the frontend would display
+  /// as garbage.
   fn upload_video_frame<P>(
     &mut self,
     enabled: &SoftwareRenderEnabled,
@@ -250,10 +548,79 @@ pub trait Callbacks {
     framebuffer: &impl FrameBuffer,
   );
 
+  /// Sends a rectangle of the frame whose top left corner is at `origin`,
+  /// e.g. the only part of the frame that changed, to the `libretro` frontend.
+  /// Must not be called if hardware rendering is used.
+  ///
+  /// The frontend can only receive whole frames, so the rectangle is copied
+  /// into a persistent copy of the frame, which is uploaded instead. That copy
+  /// is tightly packed: its pitch is `width * size_of::<P>()`, whatever the
+  /// pitch of `framebuffer`. It has the size of the last frame uploaded with
+  /// [`Callbacks::upload_video_frame`] (or, if there's none, the size needed
+  /// to hold the first rectangle), and the parts of the rectangle outside of
+  /// it are skipped.
+  ///
+  /// The copy is only kept once a subframe has been uploaded, so cores that
+  /// never upload one don't pay for it: the first rectangle is composited
+  /// into a black frame, e.g. upload the whole frame as a subframe at (0, 0)
+  /// first. From then on, frames uploaded with [`Callbacks::upload_video_frame`]
+  /// also update the copy, so rectangles are composited into the last whole
+  /// frame.
+  fn upload_video_subframe<P>(
+    &mut self,
+    enabled: &SoftwareRenderEnabled,
+    pixel_format: &ActiveFormat<P>,
+    framebuffer: &impl FrameBuffer<Pixel = P>,
+    origin: (u16, u16),
+  ) where
+    P: Format;
+
+  /// Sends a `width` by `height` frame filled with `color` to the `libretro`
+  /// frontend, e.g. for a splash or loading screen. Must not be called if
+  /// hardware rendering is used.
+  ///
+  /// The frame is drawn into a buffer that's kept between calls, so it's only
+  /// allocated again when the size or pixel format changes.
+  fn upload_solid_frame<P>(
+    &mut self,
+    enabled: &SoftwareRenderEnabled,
+    pixel_format: &ActiveFormat<P>,
+    width: u16,
+    height: u16,
+    color: P,
+  ) where
+    P: Format;
+
+  /// Sends video data in a pixel format that was negotiated at runtime to the
+  /// `libretro` frontend. Must not be called if hardware rendering is used.
+  fn upload_video_frame_any(
+    &mut self,
+    enabled: &SoftwareRenderEnabled,
+    framebuffer: &AnyFrameBuffer,
+  ) {
+    match framebuffer.format() {
+      AnyFormat::ORGB1555(format) => {
+        self.upload_video_frame(enabled, format, &framebuffer.typed::<ORGB1555>())
+      }
+      AnyFormat::RGB565(format) => {
+        self.upload_video_frame(enabled, format, &framebuffer.typed::<RGB565>())
+      }
+      AnyFormat::XRGB8888(format) => {
+        self.upload_video_frame(enabled, format, &framebuffer.typed::<XRGB8888>())
+      }
+    }
+  }
+
   /// Explicitly informs the `libretro` frontend to repeat the previous video frame.
   /// Must only be called if [`Environment::get_can_dupe`] returns `true`.
   fn repeat_video_frame(&mut self);
 
+  /// Informs the `libretro` frontend that the frame is identical to the last
+  /// one uploaded, by passing it a null frame with the width, height and
+  /// pitch of that frame. Frontends can skip presenting duplicate frames.
+  /// Must only be called if [`Environment::get_can_dupe`] returns `true`.
+  fn dup_video_frame(&self, enabled: &SoftwareRenderEnabled);
+
   /// When using hardware rendering, informs the `libretro` frontend that core
   /// has finished rendering to the frame buffer.
   fn use_hardware_frame_buffer(
@@ -265,10 +632,66 @@ pub trait Callbacks {
 
   /// Polls all input devices.
   /// Must be called at least once on every call to [`Environment::run`]
+  ///
+  /// `libretro.h` has no way to ask the frontend when it samples input, so
+  /// cores that compensate for input latency should assume it's sampled here,
+  /// conventionally at the start of `run`.
   fn poll_inputs(&mut self) -> InputsPolled;
 
   /// Returns true if the specified button is pressed, false otherwise.
   fn is_joypad_button_pressed(&self, port: DevicePort, btn: JoypadButton) -> bool;
+
+  /// Returns a bitmask of the pressed buttons of the joypad in the specified
+  /// port, where bit `n` is the button whose [JoypadButton] value is `n`.
+  /// Takes a single query if [`Callbacks::supports_input_bitmasks`], or one
+  /// per button otherwise.
+  fn joypad_buttons(&self, port: DevicePort) -> u16;
+
+  /// Returns true if the frontend supports reading every joypad button at
+  /// once. See [`Environment::get_input_bitmasks`].
+  fn supports_input_bitmasks(&self) -> bool;
+
+  /// Returns the raw value of a stick axis of the analog device in the
+  /// specified port, from -0x8000 to 0x7FFF. Positive values point right on
+  /// the X axis and down on the Y axis.
+  /// The device's digital buttons are read as a joypad on the same port.
+  fn analog_state(&self, port: DevicePort, axis: AnalogAxis) -> i16;
+
+  /// Returns how far the specified button of the analog device in the
+  /// specified port is pressed, from 0 to 0x7FFF. Mostly useful for analog
+  /// triggers ([JoypadButton::L2] and [JoypadButton::R2]); frontends that
+  /// don't support analog buttons return 0.
+  fn analog_button_state(&self, port: DevicePort, btn: JoypadButton) -> i16;
+
+  /// Returns the buttons and sticks of the analog device in the specified
+  /// port. The analog device extends the joypad, so buttons are read with
+  /// [`Callbacks::joypad_buttons`] and sticks with [`Callbacks::analog_state`].
+  fn read_gamepad(&self, port: DevicePort) -> Gamepad {
+    let axis = |axis| self.analog_state(port, axis);
+    Gamepad {
+      buttons: self.joypad_buttons(port),
+      left_stick: (axis(AnalogAxis::LeftX), axis(AnalogAxis::LeftY)),
+      right_stick: (axis(AnalogAxis::RightX), axis(AnalogAxis::RightY)),
+    }
+  }
+
+  /// Returns the state of the light gun in the specified port.
+  fn get_lightgun_state(&self, port: DevicePort) -> LightGunState;
+
+  /// Returns the state of the touch at `index` of the pointer device in the
+  /// specified port. Coordinates aren't rescaled to the core's geometry.
+  fn pointer_state(&self, port: DevicePort, index: u8) -> PointerState;
+
+  /// Returns the number of active touches of the pointer device in the
+  /// specified port, i.e. the indices to pass to
+  /// [`Callbacks::pointer_state`] are `0..pointer_count`.
+  fn pointer_count(&self, port: DevicePort) -> u8;
+
+  /// Returns the state of the mouse in the specified port.
+  fn mouse_state(&self, port: DevicePort) -> MouseState;
+
+  /// Returns true if `key` of the keyboard in the specified port is pressed.
+  fn key_pressed(&self, port: DevicePort, key: Key) -> bool;
 }
 
 impl Callbacks for InstanceCallbacks {
@@ -280,6 +703,10 @@ impl Callbacks for InstanceCallbacks {
     unsafe { self.upload_audio_sample(left, right) }
   }
 
+  fn frame_audio(&mut self) -> &mut FrameAudio {
+    &mut self.frame_audio
+  }
+
   fn upload_video_frame<P>(
     &mut self,
     enabled: &SoftwareRenderEnabled,
@@ -289,10 +716,39 @@ impl Callbacks for InstanceCallbacks {
     unsafe { self.upload_video_frame(enabled, pixel_format, framebuffer) }
   }
 
+  fn upload_video_subframe<P>(
+    &mut self,
+    enabled: &SoftwareRenderEnabled,
+    pixel_format: &ActiveFormat<P>,
+    framebuffer: &impl FrameBuffer<Pixel = P>,
+    origin: (u16, u16),
+  ) where
+    P: Format,
+  {
+    unsafe { self.upload_video_subframe(enabled, pixel_format, framebuffer, origin) }
+  }
+
+  fn upload_solid_frame<P>(
+    &mut self,
+    enabled: &SoftwareRenderEnabled,
+    pixel_format: &ActiveFormat<P>,
+    width: u16,
+    height: u16,
+    color: P,
+  ) where
+    P: Format,
+  {
+    unsafe { self.upload_solid_frame(enabled, pixel_format, width, height, color) }
+  }
+
   fn repeat_video_frame(&mut self) {
     unsafe { self.repeat_video_frame() }
   }
 
+  fn dup_video_frame(&self, enabled: &SoftwareRenderEnabled) {
+    unsafe { self.dup_video_frame(enabled) }
+  }
+
   fn use_hardware_frame_buffer(
     &mut self,
     hw_render_enabled: &impl HWRenderEnabled,
@@ -310,6 +766,42 @@ impl Callbacks for InstanceCallbacks {
   fn is_joypad_button_pressed(&self, port: DevicePort, btn: JoypadButton) -> bool {
     unsafe { self.is_joypad_button_pressed(port, btn) }
   }
+
+  fn joypad_buttons(&self, port: DevicePort) -> u16 {
+    unsafe { self.joypad_buttons(port) }
+  }
+
+  fn supports_input_bitmasks(&self) -> bool {
+    self.input_bitmasks
+  }
+
+  fn analog_state(&self, port: DevicePort, axis: AnalogAxis) -> i16 {
+    unsafe { self.analog_state(port, axis) }
+  }
+
+  fn analog_button_state(&self, port: DevicePort, btn: JoypadButton) -> i16 {
+    unsafe { self.analog_button_state(port, btn) }
+  }
+
+  fn get_lightgun_state(&self, port: DevicePort) -> LightGunState {
+    unsafe { self.get_lightgun_state(port) }
+  }
+
+  fn pointer_state(&self, port: DevicePort, index: u8) -> PointerState {
+    unsafe { self.pointer_state(port, index) }
+  }
+
+  fn pointer_count(&self, port: DevicePort) -> u8 {
+    unsafe { self.pointer_count(port) }
+  }
+
+  fn mouse_state(&self, port: DevicePort) -> MouseState {
+    unsafe { self.mouse_state(port) }
+  }
+
+  fn key_pressed(&self, port: DevicePort, key: Key) -> bool {
+    unsafe { self.key_pressed(port, key) }
+  }
 }
 
 pub struct InputsPolled(pub(crate) ());
@@ -340,24 +832,35 @@ pub struct Instance<I, C> {
   cb: InstanceCallbacks,
   init: MaybeUninit<I>,
   core: MaybeUninit<C>,
+  /// Set when the core panics, after which it isn't called into until the
+  /// game is unloaded, since it may have been left in an inconsistent state.
+  crashed: bool,
 }
 
 impl<I, C> Instance<I, C> {
   pub const fn new(
     context_reset: non_null_retro_hw_context_reset_t,
     context_destroy: non_null_retro_hw_context_reset_t,
+    vulkan_context_reset: non_null_retro_hw_context_reset_t,
+    vulkan_context_destroy: non_null_retro_hw_context_reset_t,
   ) -> Self {
+    let gl = InstanceGLState::new(context_reset, context_destroy);
+    let vulkan = InstanceVulkanState::new(vulkan_context_reset, vulkan_context_destroy);
     Self {
-      env: InstanceEnvironment {
-        cb: None,
-        gl: InstanceGLState::new(context_reset, context_destroy),
-      },
+      env: InstanceEnvironment::new(None, gl, vulkan),
       cb: InstanceCallbacks::new(),
       init: MaybeUninit::uninit(),
       core: MaybeUninit::uninit(),
+      crashed: false,
     }
   }
 
+  /// Returns true if the core can be called into: a game is loaded, and the
+  /// core hasn't crashed.
+  fn is_core_live(&self) -> bool {
+    self.cb.game_loaded && !self.crashed
+  }
+
   pub fn on_set_audio_sample(&mut self, cb: non_null_retro_audio_sample_t) {
     self.cb.audio_sample = Some(cb);
   }
@@ -392,15 +895,38 @@ impl<'a, C: Core<'a>> Instance<C::Init, C> {
 
   pub fn on_set_environment(&mut self, env: non_null_retro_environment_t) {
     self.env.cb = Some(env);
+    if C::SUPPORTS_NO_GAME {
+      env::SetEnvironment::set_support_no_game(&mut self.env, true).ok();
+    }
+    self.cb.input_bitmasks = env::Environment::get_input_bitmasks(&self.env);
     C::set_environment(&mut self.env);
   }
 
+  /// Registers the keyboard callback during `retro_set_environment`, if the
+  /// core wants keyboard events.
+  pub fn on_set_keyboard_callback(&mut self, callback: &retro_keyboard_callback) {
+    if C::KEYBOARD_EVENTS {
+      env::SetEnvironment::set_keyboard_callback(&mut self.env, callback).ok();
+    }
+  }
+
+  /// Queues a keyboard event for the next `retro_run`. The frontend may call
+  /// this from any thread, so it doesn't touch the instance.
+  pub fn on_keyboard_event(down: bool, keycode: c_uint, character: u32, modifiers: u16) {
+    let key = Key::try_from(keycode).unwrap_or_default();
+    let modifiers = KeyModifiers::new(modifiers);
+    if let Ok(mut queue) = KEYBOARD_QUEUE.lock() {
+      queue.push_back((down, key, character, modifiers));
+    }
+  }
+
   pub unsafe fn on_init(&mut self) {
     self.init.write(C::init(&mut self.env));
   }
 
   pub unsafe fn on_load_game(&mut self, game: *const retro_game_info) -> bool {
-    let Instance { env, init, core, .. } = self;
+    clear_keyboard_events();
+    let Instance { env, cb, init, core, .. } = self;
     // Introduce an unbounded lifetime on purpose by coercing to a pointer and back.
     // This is normally extremely dangerous, but the libretro API guarantees that the
     // init data will outlive the core.
@@ -420,6 +946,7 @@ impl<'a, C: Core<'a>> Instance<C::Init, C> {
     match result {
       Ok(system) => {
         core.write(system);
+        cb.game_loaded = true;
         true
       }
       Err(_) => false,
@@ -427,30 +954,87 @@ impl<'a, C: Core<'a>> Instance<C::Init, C> {
   }
 
   pub unsafe fn on_get_system_av_info(&mut self, info: &mut retro_system_av_info) {
+    if !self.is_core_live() {
+      return;
+    }
     let Instance { env, core, .. } = self;
     *info = core.assume_init_mut().get_system_av_info(env).into();
   }
 
   pub unsafe fn on_run(&mut self) {
-    self.core.assume_init_mut().run(&mut self.env, &mut self.cb);
+    if !self.is_core_live() {
+      return;
+    }
+    let events = match C::KEYBOARD_EVENTS {
+      true => KEYBOARD_QUEUE
+        .lock()
+        .map(|mut queue| core::mem::take(&mut *queue))
+        .unwrap_or_default(),
+      false => VecDeque::new(),
+    };
+    let Instance { env, cb, core, .. } = self;
+    let core = core.assume_init_mut();
+    let run = || {
+      for (down, key, character, modifiers) in events {
+        core.keyboard_event(down, key, character, modifiers);
+      }
+      core.run(env, cb)
+    };
+    if let Err(crash) = crate::retro::crash::catch(run) {
+      self.crashed = true;
+      self.report_crash(&crash);
+    }
+    self.cb.flush_frame_audio();
+  }
+
+  /// Logs a panic caught in [`Core::run`] and, unless the core opted out,
+  /// tells the player about it.
+  fn report_crash(&mut self, crash: &crate::retro::crash::Crash) {
+    let mut logger = FallbackLogger::new(self.env.get_log_interface().ok());
+    logger.log_display(RetroLogLevel::Error, crash);
+    if C::REPORT_PANICS {
+      let msg: &CStr = c_utf8!("Core encountered an internal error").as_ref();
+      let message = MessageExt::new(msg, 5000).with_level(RetroLogLevel::Error);
+      if self.env.set_message_ext(&message).is_err() {
+        self.env.set_message(&Message::new(msg, 300)).ok();
+      }
+    }
   }
 
   pub unsafe fn on_reset(&mut self) {
+    if !self.is_core_live() {
+      return;
+    }
     self.core.assume_init_mut().reset(&mut self.env);
   }
 
   pub unsafe fn on_unload_game(&mut self) {
-    self.core.assume_init_read().unload_game(&mut self.env);
+    self.cb.game_loaded = false;
+    clear_keyboard_events();
+    self.cb.last_frame = None;
+    self.cb.composite_frame = None;
+    self.cb.solid_frame = None;
+    if core::mem::take(&mut self.crashed) {
+      // Only drop the core; its state can't be trusted to unload the game.
+      self.core.assume_init_drop();
+    } else {
+      self.core.assume_init_read().unload_game(&mut self.env);
+    }
   }
 
   pub unsafe fn on_deinit(&mut self) {
     C::deinit(&mut self.env, self.init.assume_init_read());
+    self.env.fastforwarding_override = None;
+    self.env.get_time_usec.set(None);
   }
 }
 
 impl<'a, C: SaveStateCore<'a>> Instance<C::Init, C> {
   /// Invoked by a `libretro` frontend, with the `retro_serialize_size` API call.
   pub unsafe fn on_serialize_size(&mut self) -> usize {
+    if !self.is_core_live() {
+      return 0;
+    }
     self
       .core
       .assume_init_mut()
@@ -460,17 +1044,24 @@ impl<'a, C: SaveStateCore<'a>> Instance<C::Init, C> {
 
   /// Invoked by a `libretro` frontend, with the `retro_serialize` API call.
   pub unsafe fn on_serialize(&mut self, data: *mut (), size: usize) -> bool {
+    if !self.is_core_live() {
+      return false;
+    }
+    let core = self.core.assume_init_mut();
+    let state_size = core.serialize_size(&mut self.env).get();
+    if size < state_size {
+      return false;
+    }
     let lifetime = ();
-    let data = slice_with_lifetime_mut(data as *mut u8, size, &lifetime);
-    self
-      .core
-      .assume_init_mut()
-      .serialize(&mut self.env, data)
-      .is_ok()
+    let data = slice_with_lifetime_mut(data as *mut u8, state_size, &lifetime);
+    core.serialize(&mut self.env, data).is_ok()
   }
 
   /// Invoked by a `libretro` frontend, with the `retro_unserialize` API call.
   pub unsafe fn on_unserialize(&mut self, data: *const (), size: usize) -> bool {
+    if !self.is_core_live() {
+      return false;
+    }
     let lifetime = ();
     let data = slice_with_lifetime(data as *const u8, size, &lifetime);
     self
@@ -500,6 +1091,9 @@ impl<I, C> SaveStateCoreFallbacks for Instance<I, C> {}
 impl<'a, C: DeviceTypeAwareCore<'a>> Instance<C::Init, C> {
   /// Invoked by a `libretro` frontend, with the `retro_set_controller_port_device` API call.
   pub unsafe fn on_set_controller_port_device(&mut self, port: DevicePort, device: DeviceTypeId) {
+    if !self.is_core_live() {
+      return;
+    }
     let system = self.core.assume_init_mut();
     let env = &mut self.env;
     let _ = system.set_controller_port_device(env, port, device);
@@ -518,6 +1112,9 @@ impl<'a, C: CheatsCore<'a>> Instance<C::Init, C> {
   /// # Safety
   /// `code` must be a valid argument to [`CStr::from_ptr`].
   pub unsafe fn on_cheat_set(&mut self, index: c_uint, enabled: bool, code: *const c_char) {
+    if !self.is_core_live() {
+      return;
+    }
     let code = CStr::from_ptr(code);
     let env = &mut self.env;
     let _ = self
@@ -528,6 +1125,9 @@ impl<'a, C: CheatsCore<'a>> Instance<C::Init, C> {
 
   /// Invoked by a `libretro` frontend, with the `retro_cheat_reset` API call.
   pub unsafe fn on_cheat_reset(&mut self) {
+    if !self.is_core_live() {
+      return;
+    }
     self.core.assume_init_mut().cheat_reset(&mut self.env)
   }
 }
@@ -543,15 +1143,22 @@ impl<I, C> CheatsCoreFallbacks for Instance<I, C> {}
 impl<'a, C: GetMemoryRegionCore<'a>> Instance<C::Init, C> {
   /// Invoked by a `libretro` frontend, with the `retro_get_memory_data` API call.
   pub unsafe fn on_get_memory_data(&mut self, id: MemoryType) -> *mut () {
+    if !self.is_core_live() {
+      return core::ptr::null_mut();
+    }
     self
       .core
       .assume_init_mut()
       .get_memory_data(&mut self.env, id)
+      .filter(|data| !data.is_empty())
       .map_or_else(std::ptr::null_mut, |data| data.as_mut_ptr() as *mut ())
   }
 
   /// Invoked by a `libretro` frontend, with the `retro_get_memory_size` API call.
   pub unsafe fn on_get_memory_size(&mut self, id: MemoryType) -> usize {
+    if !self.is_core_live() {
+      return 0;
+    }
     self
       .core
       .assume_init_mut()
@@ -561,15 +1168,34 @@ impl<'a, C: GetMemoryRegionCore<'a>> Instance<C::Init, C> {
 
 #[doc(hidden)]
 pub trait GetMemoryRegionCoreFallbacks {
-  unsafe fn on_get_memory_data(&mut self, _id: MemoryType) -> *mut () {
-    core::ptr::null_mut()
+  unsafe fn on_get_memory_data(&mut self, id: MemoryType) -> *mut ();
+
+  unsafe fn on_get_memory_size(&mut self, id: MemoryType) -> usize;
+}
+impl<'a, C: Core<'a>> GetMemoryRegionCoreFallbacks for Instance<C::Init, C> {
+  unsafe fn on_get_memory_data(&mut self, id: MemoryType) -> *mut () {
+    if !self.is_core_live() {
+      return core::ptr::null_mut();
+    }
+    self
+      .core
+      .assume_init_mut()
+      .get_memory_region(id)
+      .filter(|data| !data.is_empty())
+      .map_or_else(std::ptr::null_mut, |data| data.as_mut_ptr() as *mut ())
   }
 
-  unsafe fn on_get_memory_size(&mut self, _id: MemoryType) -> usize {
-    0
+  unsafe fn on_get_memory_size(&mut self, id: MemoryType) -> usize {
+    if !self.is_core_live() {
+      return 0;
+    }
+    self
+      .core
+      .assume_init_mut()
+      .get_memory_region(id)
+      .map_or(0, |data| data.len())
   }
 }
-impl<I, C> GetMemoryRegionCoreFallbacks for Instance<I, C> {}
 
 impl<'a, C: SpecialGameCore<'a>> Instance<C::Init, C> {
   /// Invoked by a `libretro` frontend, with the `retro_load_game_special` API call.
@@ -579,17 +1205,22 @@ impl<'a, C: SpecialGameCore<'a>> Instance<C::Init, C> {
     info: *const retro_game_info,
     num_info: usize,
   ) -> bool {
-    let Instance { env, init, core, .. } = self;
+    clear_keyboard_events();
+    let Instance { env, cb, init, core, .. } = self;
     // Introduce an unbounded lifetime on purpose by coercing to a pointer and back.
     // This is normally extremely dangerous, but the libretro API guarantees that the
     // init data will outlive the core.
     let init: &mut C::Init = &mut *(init.assume_init_mut() as *mut C::Init);
     let lifetime = ();
-    let games = slice_with_lifetime(info.cast(), num_info, &lifetime);
+    let games = match info.is_null() {
+      true => &[],
+      false => slice_with_lifetime(info.cast(), num_info, &lifetime),
+    };
     let result = <C as SpecialGameCore>::load_game(env, init, game_type, games);
     match result {
       Ok(system) => {
         core.write(system);
+        cb.game_loaded = true;
         true
       }
       Err(_) => false,
@@ -613,6 +1244,9 @@ impl<I, C> SpecialGameCoreFallbacks for Instance<I, C> {}
 impl<'a, C: RegionAwareCore<'a>> Instance<C::Init, C> {
   /// Invoked by a `libretro` frontend, with the `retro_get_region` API call.
   pub unsafe fn on_get_region(&mut self) -> c_uint {
+    if !self.is_core_live() {
+      return RETRO_REGION_NTSC;
+    }
     let env = &mut self.env;
     self.core.assume_init_mut().get_region(env).into()
   }
@@ -626,6 +1260,168 @@ pub trait RegionAwareCoreFallbacks {
 }
 impl<I, C> RegionAwareCoreFallbacks for Instance<I, C> {}
 
+impl<'a, C: DiskControlCore<'a>> Instance<C::Init, C> {
+  /// Registers the disk control interface during `retro_set_environment`.
+  pub fn on_set_disk_control_interface(&mut self, callbacks: &retro_disk_control_ext_callback) {
+    let _ = env::SetEnvironment::set_disk_control_interface(&mut self.env, callbacks);
+  }
+
+  pub unsafe fn on_get_eject_state(&mut self) -> bool {
+    if !self.is_core_live() {
+      return false;
+    }
+    self.core.assume_init_ref().get_eject_state(&mut self.env)
+  }
+
+  pub unsafe fn on_set_eject_state(&mut self, ejected: bool) -> bool {
+    if !self.is_core_live() {
+      return false;
+    }
+    let env = &mut self.env;
+    let core = self.core.assume_init_mut();
+    core.set_eject_state(env, ejected).is_ok()
+  }
+
+  pub unsafe fn on_get_image_index(&mut self) -> c_uint {
+    if !self.is_core_live() {
+      return 0;
+    }
+    self.core.assume_init_ref().get_image_index(&mut self.env)
+  }
+
+  pub unsafe fn on_set_image_index(&mut self, index: c_uint) -> bool {
+    if !self.is_core_live() {
+      return false;
+    }
+    let env = &mut self.env;
+    let core = self.core.assume_init_mut();
+    core.set_image_index(env, index).is_ok()
+  }
+
+  pub unsafe fn on_get_num_images(&mut self) -> c_uint {
+    if !self.is_core_live() {
+      return 0;
+    }
+    self.core.assume_init_ref().get_num_images(&mut self.env)
+  }
+
+  pub unsafe fn on_replace_image_index(
+    &mut self,
+    index: c_uint,
+    info: *const retro_game_info,
+  ) -> bool {
+    if !self.is_core_live() {
+      return false;
+    }
+    let lifetime = ();
+    let game = as_ref_with_lifetime(info.cast::<GameInfo>(), &lifetime);
+    let env = &mut self.env;
+    let core = self.core.assume_init_mut();
+    core.replace_image_index(env, index, game).is_ok()
+  }
+
+  pub unsafe fn on_add_image_index(&mut self) -> bool {
+    if !self.is_core_live() {
+      return false;
+    }
+    let env = &mut self.env;
+    self.core.assume_init_mut().add_image_index(env).is_ok()
+  }
+
+  /// # Safety
+  /// `path` must be null or a valid argument to [`CStr::from_ptr`].
+  pub unsafe fn on_set_initial_image(&mut self, index: c_uint, path: *const c_char) -> bool {
+    if path.is_null() {
+      return false;
+    }
+    let path = CStr::from_ptr(path);
+    let init_state = self.init.assume_init_mut();
+    C::set_initial_image(&mut self.env, init_state, index, path).is_ok()
+  }
+
+  /// # Safety
+  /// `path` must point to a buffer of at least `len` bytes.
+  pub unsafe fn on_get_image_path(&mut self, index: c_uint, path: *mut c_char, len: usize) -> bool {
+    if !self.is_core_live() {
+      return false;
+    }
+    let core = self.core.assume_init_ref();
+    let image_path = core.get_image_path(&mut self.env, index);
+    image_path.is_some_and(|image_path| copy_c_str(image_path, path, len))
+  }
+
+  /// # Safety
+  /// `label` must point to a buffer of at least `len` bytes.
+  pub unsafe fn on_get_image_label(
+    &mut self,
+    index: c_uint,
+    label: *mut c_char,
+    len: usize,
+  ) -> bool {
+    if !self.is_core_live() {
+      return false;
+    }
+    let core = self.core.assume_init_ref();
+    let image_label = core.get_image_label(&mut self.env, index);
+    image_label.is_some_and(|image_label| copy_c_str(image_label, label, len))
+  }
+}
+
+#[doc(hidden)]
+pub trait DiskControlCoreFallbacks {
+  fn on_set_disk_control_interface(&mut self, _callbacks: &retro_disk_control_ext_callback) {}
+
+  unsafe fn on_get_eject_state(&mut self) -> bool {
+    false
+  }
+
+  unsafe fn on_set_eject_state(&mut self, _ejected: bool) -> bool {
+    false
+  }
+
+  unsafe fn on_get_image_index(&mut self) -> c_uint {
+    0
+  }
+
+  unsafe fn on_set_image_index(&mut self, _index: c_uint) -> bool {
+    false
+  }
+
+  unsafe fn on_get_num_images(&mut self) -> c_uint {
+    0
+  }
+
+  unsafe fn on_replace_image_index(
+    &mut self,
+    _index: c_uint,
+    _info: *const retro_game_info,
+  ) -> bool {
+    false
+  }
+
+  unsafe fn on_add_image_index(&mut self) -> bool {
+    false
+  }
+
+  unsafe fn on_set_initial_image(&mut self, _index: c_uint, _path: *const c_char) -> bool {
+    false
+  }
+
+  unsafe fn on_get_image_path(&mut self, _index: c_uint, _path: *mut c_char, _len: usize) -> bool {
+    false
+  }
+
+  unsafe fn on_get_image_label(
+    &mut self,
+    _index: c_uint,
+    _label: *mut c_char,
+    _len: usize,
+  ) -> bool {
+    false
+  }
+}
+impl<I, C> DiskControlCoreFallbacks for Instance<I, C> {}
+
 impl<'a, C: OpenGLCore<'a>> Instance<C::Init, C> {
   pub unsafe fn on_context_reset(&mut self) {
     let callbacks = self.env.gl.core_callbacks.unwrap_unchecked();
@@ -648,16 +1444,47 @@ pub trait OpenGLCoreFallbacks {
 }
 impl<I, C> OpenGLCoreFallbacks for Instance<I, C> {}
 
+impl<'a, C: VulkanCore<'a>> Instance<C::Init, C> {
+  pub unsafe fn on_vulkan_context_reset(&mut self) {
+    self.core.assume_init_mut().context_reset(&mut self.env);
+  }
+
+  pub unsafe fn on_vulkan_context_destroy(&mut self) {
+    self.core.assume_init_mut().context_destroy(&mut self.env);
+  }
+}
+
 #[doc(hidden)]
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub trait VulkanCoreFallbacks {
+  unsafe fn on_vulkan_context_reset(&mut self) {}
+
+  unsafe fn on_vulkan_context_destroy(&mut self) {}
+}
+impl<I, C> VulkanCoreFallbacks for Instance<I, C> {}
+
+#[doc(hidden)]
+#[derive(Clone, Debug)]
 pub struct InstanceEnvironment {
   cb: retro_environment_t,
   gl: InstanceGLState,
+  vulkan: InstanceVulkanState,
+  fastforwarding_override: Option<FastForwardingOverride>,
+  get_time_usec: Cell<Option<retro_perf_get_time_usec_t>>,
 }
 
 impl InstanceEnvironment {
-  pub const fn new(cb: retro_environment_t, gl: InstanceGLState) -> Self {
-    Self { cb, gl }
+  pub const fn new(
+    cb: retro_environment_t,
+    gl: InstanceGLState,
+    vulkan: InstanceVulkanState,
+  ) -> Self {
+    Self {
+      cb,
+      gl,
+      vulkan,
+      fastforwarding_override: None,
+      get_time_usec: Cell::new(None),
+    }
   }
 }
 
@@ -665,6 +1492,28 @@ impl env::Environment for InstanceEnvironment {
   fn get_ptr(&self) -> non_null_retro_environment_t {
     unsafe { self.cb.unwrap_unchecked() }
   }
+
+  fn set_fastforwarding_override(&mut self, data: FastForwardingOverride) -> env::Result<()> {
+    unsafe { self.set(RETRO_ENVIRONMENT_SET_FASTFORWARDING_OVERRIDE, &data) }?;
+    self.fastforwarding_override = Some(data);
+    Ok(())
+  }
+
+  fn fastforwarding_override(&self) -> Option<FastForwardingOverride> {
+    self.fastforwarding_override
+  }
+
+  fn get_time_usec(&self) -> Option<u64> {
+    let get_time_usec = match self.get_time_usec.get() {
+      Some(get_time_usec) => get_time_usec,
+      None => {
+        let get_time_usec = env::get_time_usec_callback(self);
+        self.get_time_usec.set(Some(get_time_usec));
+        get_time_usec
+      }
+    }?;
+    u64::try_from(unsafe { get_time_usec() }).ok()
+  }
 }
 
 impl env::LoadGame for InstanceEnvironment {
@@ -677,14 +1526,24 @@ impl env::LoadGame for InstanceEnvironment {
     let mut data: retro_hw_render_callback = options.into();
     data.context_destroy = Some(self.gl.context_destroy);
     data.context_reset = Some(self.gl.context_reset);
+    let data: retro_hw_render_callback =
+      unsafe { self.cmd(RETRO_ENVIRONMENT_SET_HW_RENDER, data) }?;
     unsafe {
-      let data: retro_hw_render_callback = self.cmd(RETRO_ENVIRONMENT_SET_HW_RENDER, data)?;
       self.gl.core_callbacks = Some(GLContextCallbacks {
         get_current_framebuffer_cb: data.get_current_framebuffer.unwrap_unchecked(),
         get_proc_address_cb: data.get_proc_address.unwrap_unchecked(),
       });
     }
-    Ok(GLRenderEnabled(()))
+    // The frontend may downgrade the version it was asked for.
+    Ok(GLRenderEnabled(GLOptions::from(data).get_negotiated()))
+  }
+
+  fn set_hw_render_vulkan(&mut self, options: VulkanOptions) -> env::Result<VulkanRenderEnabled> {
+    let mut data: retro_hw_render_callback = options.into();
+    data.context_destroy = Some(self.vulkan.context_destroy);
+    data.context_reset = Some(self.vulkan.context_reset);
+    unsafe { self.cmd(RETRO_ENVIRONMENT_SET_HW_RENDER, data) }
+      .map(|_: retro_hw_render_callback| VulkanRenderEnabled(()))
   }
 }
 
@@ -696,6 +1555,20 @@ pub struct InstanceCallbacks {
   input_poll: retro_input_poll_t,
   input_state: retro_input_state_t,
   video_refresh: retro_video_refresh_t,
+  frame_audio: FrameAudio,
+  /// Set while a game is loaded. Audio and video uploads are ignored
+  /// otherwise, so stale callbacks are never called during teardown.
+  game_loaded: bool,
+  /// The width, height and pitch of the last frame uploaded to the frontend.
+  last_frame: Option<(u16, u16, usize)>,
+  /// A copy of the last frame uploaded, which subframes are composited into.
+  /// Only kept once a subframe has been uploaded.
+  composite_frame: Option<CompositeFrame>,
+  /// The buffer of the last frame uploaded with `upload_solid_frame`.
+  solid_frame: Option<CompositeFrame>,
+  /// Whether the frontend supports `RETRO_DEVICE_ID_JOYPAD_MASK`, queried once
+  /// when the environment is set.
+  input_bitmasks: bool,
 }
 
 impl InstanceCallbacks {
@@ -706,24 +1579,112 @@ impl InstanceCallbacks {
       input_poll: None,
       input_state: None,
       video_refresh: None,
+      frame_audio: FrameAudio::new(),
+      game_loaded: false,
+      last_frame: None,
+      composite_frame: None,
+      solid_frame: None,
+      input_bitmasks: false,
     }
   }
 
   unsafe fn upload_audio_frame(&mut self, frame: &[i16]) -> usize {
-    self.audio_sample_batch.unwrap_unchecked()(frame.as_ptr(), frame.len() / 2)
+    match self.audio_sample_batch {
+      Some(cb) if self.game_loaded => cb(frame.as_ptr(), frame.len() / 2),
+      _ => 0,
+    }
   }
 
   unsafe fn upload_audio_sample(&mut self, left: i16, right: i16) {
-    self.audio_sample.unwrap_unchecked()(left, right)
+    match self.audio_sample {
+      Some(cb) if self.game_loaded => cb(left, right),
+      _ => {}
+    }
+  }
+
+  /// Uploads the audio accumulated in [`InstanceCallbacks::frame_audio`], if any.
+  unsafe fn flush_frame_audio(&mut self) {
+    if !self.frame_audio.is_empty() {
+      let samples = self.frame_audio.samples();
+      if let Some(audio_sample_batch) = self.audio_sample_batch.filter(|_| self.game_loaded) {
+        audio_sample_batch(samples.as_ptr(), samples.len() / 2);
+      }
+      self.frame_audio.clear();
+    }
   }
 
-  unsafe fn upload_video_frame<P>(
+  unsafe fn upload_video_frame<P, F: FrameBuffer>(
     &mut self,
     _enabled: &SoftwareRenderEnabled,
     _pixel_format: &ActiveFormat<P>,
-    framebuffer: &impl FrameBuffer,
+    framebuffer: &F,
+  ) {
+    // The frontend reads the frame with the size of the negotiated format's
+    // pixels, so a frame buffer of another format would be garbled.
+    debug_assert_eq!(
+      core::mem::size_of::<F::Pixel>(),
+      core::mem::size_of::<P>(),
+      "uploaded a frame of {} pixels, but the negotiated pixel format is {}",
+      core::any::type_name::<F::Pixel>(),
+      core::any::type_name::<P>(),
+    );
+    // Once the core uploads subframes, keep the copy they're composited into
+    // up to date.
+    if let Some(composite) = &mut self.composite_frame {
+      composite.resize::<F::Pixel>(framebuffer.width(), framebuffer.height());
+      composite.blit(framebuffer, (0, 0));
+    }
+    self.refresh_video(framebuffer)
+  }
+
+  unsafe fn upload_video_subframe<P: Format>(
+    &mut self,
+    _enabled: &SoftwareRenderEnabled,
+    _pixel_format: &ActiveFormat<P>,
+    framebuffer: &impl FrameBuffer<Pixel = P>,
+    (x, y): (u16, u16),
+  ) {
+    let (width, height) = match self.last_frame {
+      Some((width, height, _)) => (width, height),
+      None => (
+        x.saturating_add(framebuffer.width()),
+        y.saturating_add(framebuffer.height()),
+      ),
+    };
+    let mut composite = self.composite_frame.take().unwrap_or_default();
+    composite.resize::<P>(width, height);
+    composite.blit(framebuffer, (x, y));
+    self.refresh_video(&composite.typed::<P>());
+    self.composite_frame = Some(composite);
+  }
+
+  unsafe fn upload_solid_frame<P: Format>(
+    &mut self,
+    enabled: &SoftwareRenderEnabled,
+    pixel_format: &ActiveFormat<P>,
+    width: u16,
+    height: u16,
+    color: P,
   ) {
-    self.video_refresh.unwrap_unchecked()(
+    let mut frame = self.solid_frame.take().unwrap_or_default();
+    frame.resize::<P>(width, height);
+    frame.fill(&color);
+    self.upload_video_frame(enabled, pixel_format, &frame.typed::<P>());
+    self.solid_frame = Some(frame);
+  }
+
+  unsafe fn refresh_video<F: FrameBuffer>(&mut self, framebuffer: &F) {
+    let Some(video_refresh) = self.video_refresh.filter(|_| self.game_loaded) else {
+      return;
+    };
+    #[cfg(feature = "screenshot")]
+    crate::retro::screenshot::capture(framebuffer);
+    self.last_frame = Some((
+      framebuffer.width(),
+      framebuffer.height(),
+      framebuffer.pitch(),
+    ));
+    video_refresh(
       framebuffer.data().as_ptr() as *const c_void,
       framebuffer.width() as u32,
       framebuffer.height() as u32,
@@ -732,7 +1693,16 @@ impl InstanceCallbacks {
   }
 
   unsafe fn repeat_video_frame(&mut self) {
-    self.video_refresh.unwrap_unchecked()(core::ptr::null(), 0, 0, 0)
+    if let Some(video_refresh) = self.video_refresh.filter(|_| self.game_loaded) {
+      video_refresh(core::ptr::null(), 0, 0, 0)
+    }
+  }
+
+  unsafe fn dup_video_frame(&self, _enabled: &SoftwareRenderEnabled) {
+    if let Some(video_refresh) = self.video_refresh.filter(|_| self.game_loaded) {
+      let (width, height, pitch) = self.last_frame.unwrap_or_default();
+      video_refresh(core::ptr::null(), width.into(), height.into(), pitch)
+    }
   }
 
   unsafe fn use_hardware_frame_buffer(
@@ -741,7 +1711,9 @@ impl InstanceCallbacks {
     width: c_uint,
     height: c_uint,
   ) {
-    self.video_refresh.unwrap_unchecked()(RETRO_HW_FRAME_BUFFER_VALID, width, height, 0)
+    if let Some(video_refresh) = self.video_refresh.filter(|_| self.game_loaded) {
+      video_refresh(RETRO_HW_FRAME_BUFFER_VALID, width, height, 0)
+    }
   }
 
   unsafe fn poll_inputs(&mut self) -> InputsPolled {
@@ -757,6 +1729,83 @@ impl InstanceCallbacks {
     let id = btn.into();
     self.input_state.unwrap_unchecked()(port, device, index, id) != 0
   }
+
+  unsafe fn joypad_buttons(&self, port: DevicePort) -> u16 {
+    let port = port.into_inner();
+    let input_state = self.input_state.unwrap_unchecked();
+    let get = |id| input_state(port, RETRO_DEVICE_JOYPAD, 0, id);
+    match self.input_bitmasks {
+      true => get(RETRO_DEVICE_ID_JOYPAD_MASK) as u16,
+      false => (0..16)
+        .filter(|&id| get(id) != 0)
+        .fold(0, |mask, id| mask | 1 << id),
+    }
+  }
+
+  unsafe fn analog_state(&self, port: DevicePort, axis: AnalogAxis) -> i16 {
+    let port = port.into_inner();
+    let (index, id) = (axis.index(), axis.id());
+    self.input_state.unwrap_unchecked()(port, RETRO_DEVICE_ANALOG, index, id)
+  }
+
+  unsafe fn analog_button_state(&self, port: DevicePort, btn: JoypadButton) -> i16 {
+    let port = port.into_inner();
+    let index = RETRO_DEVICE_INDEX_ANALOG_BUTTON;
+    self.input_state.unwrap_unchecked()(port, RETRO_DEVICE_ANALOG, index, btn.into())
+  }
+
+  unsafe fn get_lightgun_state(&self, port: DevicePort) -> LightGunState {
+    let port = port.into_inner();
+    let input_state = self.input_state.unwrap_unchecked();
+    let get = |id| input_state(port, RETRO_DEVICE_LIGHTGUN, 0, id);
+    LightGunState {
+      screen_x: get(RETRO_DEVICE_ID_LIGHTGUN_SCREEN_X),
+      screen_y: get(RETRO_DEVICE_ID_LIGHTGUN_SCREEN_Y),
+      is_offscreen: get(RETRO_DEVICE_ID_LIGHTGUN_IS_OFFSCREEN) != 0,
+      trigger: get(RETRO_DEVICE_ID_LIGHTGUN_TRIGGER) != 0,
+      reload: get(RETRO_DEVICE_ID_LIGHTGUN_RELOAD) != 0,
+    }
+  }
+
+  unsafe fn pointer_state(&self, port: DevicePort, index: u8) -> PointerState {
+    let port = port.into_inner();
+    let input_state = self.input_state.unwrap_unchecked();
+    let get = |id| input_state(port, RETRO_DEVICE_POINTER, index.into(), id);
+    PointerState {
+      x: get(RETRO_DEVICE_ID_POINTER_X),
+      y: get(RETRO_DEVICE_ID_POINTER_Y),
+      pressed: get(RETRO_DEVICE_ID_POINTER_PRESSED) != 0,
+    }
+  }
+
+  unsafe fn pointer_count(&self, port: DevicePort) -> u8 {
+    let port = port.into_inner();
+    let id = RETRO_DEVICE_ID_POINTER_COUNT;
+    let count = self.input_state.unwrap_unchecked()(port, RETRO_DEVICE_POINTER, 0, id);
+    count.clamp(0, u8::MAX.into()) as u8
+  }
+
+  unsafe fn mouse_state(&self, port: DevicePort) -> MouseState {
+    let port = port.into_inner();
+    let input_state = self.input_state.unwrap_unchecked();
+    let get = |id| input_state(port, RETRO_DEVICE_MOUSE, 0, id);
+    MouseState {
+      x: get(RETRO_DEVICE_ID_MOUSE_X),
+      y: get(RETRO_DEVICE_ID_MOUSE_Y),
+      left: get(RETRO_DEVICE_ID_MOUSE_LEFT) != 0,
+      right: get(RETRO_DEVICE_ID_MOUSE_RIGHT) != 0,
+      middle: get(RETRO_DEVICE_ID_MOUSE_MIDDLE) != 0,
+      button_4: get(RETRO_DEVICE_ID_MOUSE_BUTTON_4) != 0,
+      button_5: get(RETRO_DEVICE_ID_MOUSE_BUTTON_5) != 0,
+      wheel_up: get(RETRO_DEVICE_ID_MOUSE_WHEELUP) != 0,
+      wheel_down: get(RETRO_DEVICE_ID_MOUSE_WHEELDOWN) != 0,
+    }
+  }
+
+  unsafe fn key_pressed(&self, port: DevicePort, key: Key) -> bool {
+    let port = port.into_inner();
+    self.input_state.unwrap_unchecked()(port, RETRO_DEVICE_KEYBOARD, 0, key.into()) != 0
+  }
 }
 
 #[doc(hidden)]
@@ -780,6 +1829,22 @@ impl InstanceGLState {
   }
 }
 
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub struct InstanceVulkanState {
+  context_reset: non_null_retro_hw_context_reset_t,
+  context_destroy: non_null_retro_hw_context_reset_t,
+}
+
+impl InstanceVulkanState {
+  pub const fn new(
+    context_reset: non_null_retro_hw_context_reset_t,
+    context_destroy: non_null_retro_hw_context_reset_t,
+  ) -> Self {
+    Self { context_reset, context_destroy }
+  }
+}
+
 unsafe fn as_ref_with_lifetime<T>(ptr: *const T, _lifetime: &()) -> Option<&T> {
   ptr.as_ref()
 }
@@ -792,6 +1857,17 @@ unsafe fn slice_with_lifetime_mut<T>(ptr: *mut T, len: usize, _lifetime: &()) ->
   slice::from_raw_parts_mut(ptr, len)
 }
 
+/// Copies `src` into the `len` byte buffer at `dst`, including the nul
+/// terminator. Returns false without copying anything if it doesn't fit.
+unsafe fn copy_c_str(src: &CStr, dst: *mut c_char, len: usize) -> bool {
+  let src = src.to_bytes_with_nul();
+  if dst.is_null() || src.len() > len {
+    return false;
+  }
+  core::ptr::copy_nonoverlapping(src.as_ptr().cast(), dst, src.len());
+  true
+}
+
 #[macro_export]
 macro_rules! libretro_core {
   ($core:ty) => {
@@ -803,8 +1879,12 @@ macro_rules! libretro_core {
       use libretro_rs::libretro_core;
       use libretro_rs::prelude::*;
 
-      static mut RETRO_INSTANCE: Instance<<$core as Core>::Init, $core> =
-        Instance::new(on_context_reset, on_context_destroy);
+      static mut RETRO_INSTANCE: Instance<<$core as Core>::Init, $core> = Instance::new(
+        on_context_reset,
+        on_context_destroy,
+        on_vulkan_context_reset,
+        on_vulkan_context_destroy,
+      );
 
       #[no_mangle]
       extern "C" fn retro_api_version() -> c_uint {
@@ -833,7 +1913,9 @@ macro_rules! libretro_core {
 
       #[no_mangle]
       unsafe extern "C" fn retro_set_environment(cb: non_null_retro_environment_t) {
-        RETRO_INSTANCE.on_set_environment(cb)
+        RETRO_INSTANCE.on_set_environment(cb);
+        RETRO_INSTANCE.on_set_disk_control_interface(&DISK_CONTROL_CALLBACKS);
+        RETRO_INSTANCE.on_set_keyboard_callback(&KEYBOARD_CALLBACK)
       }
 
       #[no_mangle]
@@ -912,7 +1994,7 @@ macro_rules! libretro_core {
       #[no_mangle]
       unsafe extern "C" fn retro_load_game_special(
         game_type: GameType,
-        info: &retro_game_info,
+        info: *const retro_game_info,
         num_info: usize,
       ) -> bool {
         RETRO_INSTANCE.on_load_game_special(game_type, info, num_info)
@@ -946,6 +2028,92 @@ macro_rules! libretro_core {
       unsafe extern "C" fn on_context_destroy() {
         RETRO_INSTANCE.on_context_destroy()
       }
+
+      unsafe extern "C" fn on_vulkan_context_reset() {
+        RETRO_INSTANCE.on_vulkan_context_reset()
+      }
+
+      unsafe extern "C" fn on_vulkan_context_destroy() {
+        RETRO_INSTANCE.on_vulkan_context_destroy()
+      }
+
+      const KEYBOARD_CALLBACK: retro_keyboard_callback =
+        retro_keyboard_callback { callback: Some(on_keyboard_event) };
+
+      unsafe extern "C" fn on_keyboard_event(
+        down: bool,
+        keycode: c_uint,
+        character: u32,
+        key_modifiers: u16,
+      ) {
+        Instance::<<$core as Core>::Init, $core>::on_keyboard_event(
+          down,
+          keycode,
+          character,
+          key_modifiers,
+        )
+      }
+
+      const DISK_CONTROL_CALLBACKS: retro_disk_control_ext_callback =
+        retro_disk_control_ext_callback {
+          set_eject_state: Some(on_set_eject_state),
+          get_eject_state: Some(on_get_eject_state),
+          get_image_index: Some(on_get_image_index),
+          set_image_index: Some(on_set_image_index),
+          get_num_images: Some(on_get_num_images),
+          replace_image_index: Some(on_replace_image_index),
+          add_image_index: Some(on_add_image_index),
+          set_initial_image: Some(on_set_initial_image),
+          get_image_path: Some(on_get_image_path),
+          get_image_label: Some(on_get_image_label),
+        };
+
+      unsafe extern "C" fn on_set_eject_state(ejected: bool) -> bool {
+        RETRO_INSTANCE.on_set_eject_state(ejected)
+      }
+
+      unsafe extern "C" fn on_get_eject_state() -> bool {
+        RETRO_INSTANCE.on_get_eject_state()
+      }
+
+      unsafe extern "C" fn on_get_image_index() -> c_uint {
+        RETRO_INSTANCE.on_get_image_index()
+      }
+
+      unsafe extern "C" fn on_set_image_index(index: c_uint) -> bool {
+        RETRO_INSTANCE.on_set_image_index(index)
+      }
+
+      unsafe extern "C" fn on_get_num_images() -> c_uint {
+        RETRO_INSTANCE.on_get_num_images()
+      }
+
+      unsafe extern "C" fn on_replace_image_index(
+        index: c_uint,
+        info: *const retro_game_info,
+      ) -> bool {
+        RETRO_INSTANCE.on_replace_image_index(index, info)
+      }
+
+      unsafe extern "C" fn on_add_image_index() -> bool {
+        RETRO_INSTANCE.on_add_image_index()
+      }
+
+      unsafe extern "C" fn on_set_initial_image(index: c_uint, path: *const c_char) -> bool {
+        RETRO_INSTANCE.on_set_initial_image(index, path)
+      }
+
+      unsafe extern "C" fn on_get_image_path(index: c_uint, path: *mut c_char, len: usize) -> bool {
+        RETRO_INSTANCE.on_get_image_path(index, path, len)
+      }
+
+      unsafe extern "C" fn on_get_image_label(
+        index: c_uint,
+        label: *mut c_char,
+        len: usize,
+      ) -> bool {
+        RETRO_INSTANCE.on_get_image_label(index, label, len)
+      }
     }
   };
 }
@@ -0,0 +1,81 @@
+//! Catches panics in [`Core::run`](crate::retro::Core::run), so they can be
+//! reported instead of unwinding into the frontend.
+//!
+//! This only works when the core is built with `panic = "unwind"`; with
+//! `panic = "abort"`, [catch] never sees the panic.
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+thread_local! {
+  /// The location of the last panic on this thread, recorded by the hook
+  /// installed by [catch].
+  static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+
+  /// Whether this thread is inside [catch].
+  static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A panic caught by [catch].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Crash {
+  message: String,
+  location: Option<String>,
+}
+
+impl fmt::Display for Crash {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("core panicked")?;
+    if let Some(location) = &self.location {
+      write!(f, " at {location}")?;
+    }
+    write!(f, ": {}", self.message)
+  }
+}
+
+/// Calls `f`, catching any panic.
+pub(crate) fn catch<R>(f: impl FnOnce() -> R) -> Result<R, Crash> {
+  install_hook();
+  CATCHING.with(|catching| catching.set(true));
+  let result = panic::catch_unwind(AssertUnwindSafe(f));
+  CATCHING.with(|catching| catching.set(false));
+  result.map_err(|payload| Crash {
+    message: payload_message(payload.as_ref()),
+    location: LOCATION
+      .try_with(|location| location.borrow_mut().take())
+      .ok()
+      .flatten(),
+  })
+}
+
+/// Wraps the process-wide panic hook (which belongs to the frontend, or to
+/// another core) to record the location of panics caught by [catch], which
+/// isn't available from the payload. The previous hook is always called, so
+/// panics elsewhere in the process are handled exactly as before.
+fn install_hook() {
+  static INSTALL: Once = Once::new();
+  INSTALL.call_once(|| {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+      if CATCHING.try_with(Cell::get).unwrap_or(false) {
+        let panic_location = info.location().map(ToString::to_string);
+        _ = LOCATION.try_with(|location| *location.borrow_mut() = panic_location);
+      }
+      previous(info);
+    }));
+  });
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+  match (
+    payload.downcast_ref::<&str>(),
+    payload.downcast_ref::<String>(),
+  ) {
+    (Some(message), _) => message.to_string(),
+    (_, Some(message)) => message.clone(),
+    _ => "unknown panic payload".to_string(),
+  }
+}
@@ -0,0 +1,128 @@
+//! Deferring expensive initialization from `load_game` to the first `run`.
+//!
+//! Frontends usually block their UI while `retro_load_game` runs, so cores
+//! with slow setup (e.g. decompressing content or building lookup tables) feel
+//! unresponsive. Instead, [`Core::load_game`](crate::retro::Core::load_game)
+//! can return quickly with a [DeferredInit], and [`Core::run`](crate::retro::Core::run)
+//! can upload a "loading" frame before finishing the work on its first call.
+//!
+//! # Examples
+//! ```
+//! use libretro_rs::prelude::*;
+//! use libretro_rs::retro::deferred::DeferredInit;
+//!
+//! struct Emulator { /* ... */ }
+//!
+//! struct Core {
+//!   emulator: DeferredInit<Emulator, Box<dyn FnOnce() -> Emulator>>,
+//! }
+//!
+//! impl Core {
+//!   fn run(&mut self, callbacks: &mut impl Callbacks) -> InputsPolled {
+//!     if self.emulator.is_pending() {
+//!       // Upload a "loading" frame here, so it's shown while the emulator
+//!       // is set up.
+//!     }
+//!     let emulator = self.emulator.get_or_init();
+//!     // Advance `emulator` by a frame...
+//!     callbacks.poll_inputs()
+//!   }
+//! }
+//! ```
+
+use std::mem;
+
+/// A value that is initialized on first use, rather than when it's created.
+/// See the [module documentation](self).
+///
+/// # Examples
+/// ```
+/// use libretro_rs::retro::deferred::DeferredInit;
+/// let mut value = DeferredInit::new(|| vec![0u8; 1024]);
+/// assert!(value.is_pending());
+/// assert_eq!(value.get(), None);
+/// assert_eq!(value.get_or_init().len(), 1024);
+/// assert!(value.is_ready());
+/// ```
+pub struct DeferredInit<T, F = fn() -> T> {
+  state: State<T, F>,
+}
+
+enum State<T, F> {
+  Pending(F),
+  Ready(T),
+  /// Only observable if `init` panicked.
+  Poisoned,
+}
+
+impl<T, F: FnOnce() -> T> DeferredInit<T, F> {
+  /// Creates a value that will be initialized by calling `init` on first use.
+  pub fn new(init: F) -> Self {
+    Self { state: State::Pending(init) }
+  }
+
+  /// Creates a value that is already initialized.
+  pub fn ready(value: T) -> Self {
+    Self { state: State::Ready(value) }
+  }
+
+  /// Returns true if the value hasn't been initialized yet.
+  pub fn is_pending(&self) -> bool {
+    matches!(self.state, State::Pending(_))
+  }
+
+  /// Returns true if the value has been initialized.
+  pub fn is_ready(&self) -> bool {
+    matches!(self.state, State::Ready(_))
+  }
+
+  /// Returns the value, running the initialization first if it hasn't run yet.
+  ///
+  /// # Panics
+  /// Panics if a previous initialization panicked.
+  pub fn get_or_init(&mut self) -> &mut T {
+    if let State::Pending(_) = self.state {
+      if let State::Pending(init) = mem::replace(&mut self.state, State::Poisoned) {
+        self.state = State::Ready(init());
+      }
+    }
+    match &mut self.state {
+      State::Ready(value) => value,
+      _ => panic!("DeferredInit was poisoned by a panic during initialization"),
+    }
+  }
+
+  /// Returns the value if it has been initialized.
+  pub fn get(&self) -> Option<&T> {
+    match &self.state {
+      State::Ready(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  /// Returns the value if it has been initialized.
+  pub fn get_mut(&mut self) -> Option<&mut T> {
+    match &mut self.state {
+      State::Ready(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  /// Consumes this wrapper and returns the value if it has been initialized.
+  pub fn into_inner(self) -> Option<T> {
+    match self.state {
+      State::Ready(value) => Some(value),
+      _ => None,
+    }
+  }
+}
+
+impl<T: std::fmt::Debug, F> std::fmt::Debug for DeferredInit<T, F> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match &self.state {
+      State::Pending(_) => f.write_str("DeferredInit(<pending>)"),
+      State::Ready(value) => f.debug_tuple("DeferredInit").field(value).finish(),
+      State::Poisoned => f.write_str("DeferredInit(<poisoned>)"),
+    }
+  }
+}
@@ -1,4 +1,9 @@
+use crate::ffi::retro_mod::*;
+use crate::ffi::{retro_input_descriptor, retro_rumble_effect, retro_rumble_interface};
+use crate::retro::av::GameGeometry;
 use ::core::ffi::*;
+use ::core::marker::PhantomData;
+use ::core::ops::{BitOr, BitOrAssign};
 
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -38,6 +43,9 @@ pub enum DeviceType {
   Mouse = 2,
   Keyboard = 3,
   LightGun = 4,
+  /// A joypad with analog sticks. Its digital buttons are still read as a
+  /// [DeviceType::Joypad] on the same port; see
+  /// [`Callbacks::read_gamepad`](crate::retro::Callbacks::read_gamepad).
   Analog = 5,
   Pointer = 6,
 }
@@ -117,3 +125,668 @@ impl From<JoypadButton> for c_uint {
     button as c_uint
   }
 }
+
+/// A key of a [DeviceType::Keyboard], identified by its `RETROK_*` value.
+/// Letters are identified by their lowercase ASCII value, regardless of
+/// modifiers, and digits by their ASCII value.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Key {
+  #[default]
+  Unknown = 0,
+  Backspace = 8,
+  Tab = 9,
+  Clear = 12,
+  Return = 13,
+  Pause = 19,
+  Escape = 27,
+  Space = 32,
+  Exclaim = 33,
+  QuoteDbl = 34,
+  Hash = 35,
+  Dollar = 36,
+  Ampersand = 38,
+  Quote = 39,
+  LeftParen = 40,
+  RightParen = 41,
+  Asterisk = 42,
+  Plus = 43,
+  Comma = 44,
+  Minus = 45,
+  Period = 46,
+  Slash = 47,
+  Num0 = 48,
+  Num1 = 49,
+  Num2 = 50,
+  Num3 = 51,
+  Num4 = 52,
+  Num5 = 53,
+  Num6 = 54,
+  Num7 = 55,
+  Num8 = 56,
+  Num9 = 57,
+  Colon = 58,
+  Semicolon = 59,
+  Less = 60,
+  Equals = 61,
+  Greater = 62,
+  Question = 63,
+  At = 64,
+  LeftBracket = 91,
+  Backslash = 92,
+  RightBracket = 93,
+  Caret = 94,
+  Underscore = 95,
+  Backquote = 96,
+  A = 97,
+  B = 98,
+  C = 99,
+  D = 100,
+  E = 101,
+  F = 102,
+  G = 103,
+  H = 104,
+  I = 105,
+  J = 106,
+  K = 107,
+  L = 108,
+  M = 109,
+  N = 110,
+  O = 111,
+  P = 112,
+  Q = 113,
+  R = 114,
+  S = 115,
+  T = 116,
+  U = 117,
+  V = 118,
+  W = 119,
+  X = 120,
+  Y = 121,
+  Z = 122,
+  LeftBrace = 123,
+  Bar = 124,
+  RightBrace = 125,
+  Tilde = 126,
+  Delete = 127,
+  Kp0 = 256,
+  Kp1 = 257,
+  Kp2 = 258,
+  Kp3 = 259,
+  Kp4 = 260,
+  Kp5 = 261,
+  Kp6 = 262,
+  Kp7 = 263,
+  Kp8 = 264,
+  Kp9 = 265,
+  KpPeriod = 266,
+  KpDivide = 267,
+  KpMultiply = 268,
+  KpMinus = 269,
+  KpPlus = 270,
+  KpEnter = 271,
+  KpEquals = 272,
+  Up = 273,
+  Down = 274,
+  Right = 275,
+  Left = 276,
+  Insert = 277,
+  Home = 278,
+  End = 279,
+  PageUp = 280,
+  PageDown = 281,
+  F1 = 282,
+  F2 = 283,
+  F3 = 284,
+  F4 = 285,
+  F5 = 286,
+  F6 = 287,
+  F7 = 288,
+  F8 = 289,
+  F9 = 290,
+  F10 = 291,
+  F11 = 292,
+  F12 = 293,
+  F13 = 294,
+  F14 = 295,
+  F15 = 296,
+  NumLock = 300,
+  CapsLock = 301,
+  ScrollLock = 302,
+  RShift = 303,
+  LShift = 304,
+  RCtrl = 305,
+  LCtrl = 306,
+  RAlt = 307,
+  LAlt = 308,
+  RMeta = 309,
+  LMeta = 310,
+  LSuper = 311,
+  RSuper = 312,
+  Mode = 313,
+  Compose = 314,
+  Help = 315,
+  Print = 316,
+  SysReq = 317,
+  Break = 318,
+  Menu = 319,
+  Power = 320,
+  Euro = 321,
+  Undo = 322,
+  Oem102 = 323,
+}
+
+impl TryFrom<c_uint> for Key {
+  type Error = ();
+
+  fn try_from(val: c_uint) -> Result<Self, Self::Error> {
+    match val {
+      0 => Ok(Self::Unknown),
+      8 => Ok(Self::Backspace),
+      9 => Ok(Self::Tab),
+      12 => Ok(Self::Clear),
+      13 => Ok(Self::Return),
+      19 => Ok(Self::Pause),
+      27 => Ok(Self::Escape),
+      32 => Ok(Self::Space),
+      33 => Ok(Self::Exclaim),
+      34 => Ok(Self::QuoteDbl),
+      35 => Ok(Self::Hash),
+      36 => Ok(Self::Dollar),
+      38 => Ok(Self::Ampersand),
+      39 => Ok(Self::Quote),
+      40 => Ok(Self::LeftParen),
+      41 => Ok(Self::RightParen),
+      42 => Ok(Self::Asterisk),
+      43 => Ok(Self::Plus),
+      44 => Ok(Self::Comma),
+      45 => Ok(Self::Minus),
+      46 => Ok(Self::Period),
+      47 => Ok(Self::Slash),
+      48 => Ok(Self::Num0),
+      49 => Ok(Self::Num1),
+      50 => Ok(Self::Num2),
+      51 => Ok(Self::Num3),
+      52 => Ok(Self::Num4),
+      53 => Ok(Self::Num5),
+      54 => Ok(Self::Num6),
+      55 => Ok(Self::Num7),
+      56 => Ok(Self::Num8),
+      57 => Ok(Self::Num9),
+      58 => Ok(Self::Colon),
+      59 => Ok(Self::Semicolon),
+      60 => Ok(Self::Less),
+      61 => Ok(Self::Equals),
+      62 => Ok(Self::Greater),
+      63 => Ok(Self::Question),
+      64 => Ok(Self::At),
+      91 => Ok(Self::LeftBracket),
+      92 => Ok(Self::Backslash),
+      93 => Ok(Self::RightBracket),
+      94 => Ok(Self::Caret),
+      95 => Ok(Self::Underscore),
+      96 => Ok(Self::Backquote),
+      97 => Ok(Self::A),
+      98 => Ok(Self::B),
+      99 => Ok(Self::C),
+      100 => Ok(Self::D),
+      101 => Ok(Self::E),
+      102 => Ok(Self::F),
+      103 => Ok(Self::G),
+      104 => Ok(Self::H),
+      105 => Ok(Self::I),
+      106 => Ok(Self::J),
+      107 => Ok(Self::K),
+      108 => Ok(Self::L),
+      109 => Ok(Self::M),
+      110 => Ok(Self::N),
+      111 => Ok(Self::O),
+      112 => Ok(Self::P),
+      113 => Ok(Self::Q),
+      114 => Ok(Self::R),
+      115 => Ok(Self::S),
+      116 => Ok(Self::T),
+      117 => Ok(Self::U),
+      118 => Ok(Self::V),
+      119 => Ok(Self::W),
+      120 => Ok(Self::X),
+      121 => Ok(Self::Y),
+      122 => Ok(Self::Z),
+      123 => Ok(Self::LeftBrace),
+      124 => Ok(Self::Bar),
+      125 => Ok(Self::RightBrace),
+      126 => Ok(Self::Tilde),
+      127 => Ok(Self::Delete),
+      256 => Ok(Self::Kp0),
+      257 => Ok(Self::Kp1),
+      258 => Ok(Self::Kp2),
+      259 => Ok(Self::Kp3),
+      260 => Ok(Self::Kp4),
+      261 => Ok(Self::Kp5),
+      262 => Ok(Self::Kp6),
+      263 => Ok(Self::Kp7),
+      264 => Ok(Self::Kp8),
+      265 => Ok(Self::Kp9),
+      266 => Ok(Self::KpPeriod),
+      267 => Ok(Self::KpDivide),
+      268 => Ok(Self::KpMultiply),
+      269 => Ok(Self::KpMinus),
+      270 => Ok(Self::KpPlus),
+      271 => Ok(Self::KpEnter),
+      272 => Ok(Self::KpEquals),
+      273 => Ok(Self::Up),
+      274 => Ok(Self::Down),
+      275 => Ok(Self::Right),
+      276 => Ok(Self::Left),
+      277 => Ok(Self::Insert),
+      278 => Ok(Self::Home),
+      279 => Ok(Self::End),
+      280 => Ok(Self::PageUp),
+      281 => Ok(Self::PageDown),
+      282 => Ok(Self::F1),
+      283 => Ok(Self::F2),
+      284 => Ok(Self::F3),
+      285 => Ok(Self::F4),
+      286 => Ok(Self::F5),
+      287 => Ok(Self::F6),
+      288 => Ok(Self::F7),
+      289 => Ok(Self::F8),
+      290 => Ok(Self::F9),
+      291 => Ok(Self::F10),
+      292 => Ok(Self::F11),
+      293 => Ok(Self::F12),
+      294 => Ok(Self::F13),
+      295 => Ok(Self::F14),
+      296 => Ok(Self::F15),
+      300 => Ok(Self::NumLock),
+      301 => Ok(Self::CapsLock),
+      302 => Ok(Self::ScrollLock),
+      303 => Ok(Self::RShift),
+      304 => Ok(Self::LShift),
+      305 => Ok(Self::RCtrl),
+      306 => Ok(Self::LCtrl),
+      307 => Ok(Self::RAlt),
+      308 => Ok(Self::LAlt),
+      309 => Ok(Self::RMeta),
+      310 => Ok(Self::LMeta),
+      311 => Ok(Self::LSuper),
+      312 => Ok(Self::RSuper),
+      313 => Ok(Self::Mode),
+      314 => Ok(Self::Compose),
+      315 => Ok(Self::Help),
+      316 => Ok(Self::Print),
+      317 => Ok(Self::SysReq),
+      318 => Ok(Self::Break),
+      319 => Ok(Self::Menu),
+      320 => Ok(Self::Power),
+      321 => Ok(Self::Euro),
+      322 => Ok(Self::Undo),
+      323 => Ok(Self::Oem102),
+      _ => Err(()),
+    }
+  }
+}
+
+impl From<Key> for c_uint {
+  fn from(key: Key) -> c_uint {
+    key as c_uint
+  }
+}
+
+/// The modifier keys held, and lock keys toggled, during a keyboard event
+/// received by [`Core::keyboard_event`](crate::retro::Core::keyboard_event).
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let modifiers = KeyModifiers::SHIFT | KeyModifiers::CAPSLOCK;
+/// assert!(modifiers.contains(KeyModifiers::SHIFT));
+/// assert!(!modifiers.contains(KeyModifiers::SHIFT | KeyModifiers::CTRL));
+/// assert!(modifiers.shift() && modifiers.capslock() && !modifiers.ctrl());
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeyModifiers(u16);
+
+impl KeyModifiers {
+  pub const NONE: Self = Self(RETROKMOD_NONE as u16);
+  pub const SHIFT: Self = Self(RETROKMOD_SHIFT as u16);
+  pub const CTRL: Self = Self(RETROKMOD_CTRL as u16);
+  pub const ALT: Self = Self(RETROKMOD_ALT as u16);
+  pub const META: Self = Self(RETROKMOD_META as u16);
+  pub const NUMLOCK: Self = Self(RETROKMOD_NUMLOCK as u16);
+  pub const CAPSLOCK: Self = Self(RETROKMOD_CAPSLOCK as u16);
+  pub const SCROLLLOCK: Self = Self(RETROKMOD_SCROLLOCK as u16);
+
+  pub fn new(bits: u16) -> Self {
+    Self(bits)
+  }
+
+  pub fn into_inner(self) -> u16 {
+    self.0
+  }
+
+  /// Returns true if all of the modifiers in `other` are set.
+  pub fn contains(self, other: Self) -> bool {
+    self.0 & other.0 == other.0
+  }
+
+  pub fn shift(self) -> bool {
+    self.contains(Self::SHIFT)
+  }
+
+  pub fn ctrl(self) -> bool {
+    self.contains(Self::CTRL)
+  }
+
+  pub fn alt(self) -> bool {
+    self.contains(Self::ALT)
+  }
+
+  /// Returns true if a meta key (e.g. the Windows or Command key) is held.
+  pub fn meta(self) -> bool {
+    self.contains(Self::META)
+  }
+
+  /// Returns true if num lock is on, rather than if its key is held, like the
+  /// other lock keys.
+  pub fn numlock(self) -> bool {
+    self.contains(Self::NUMLOCK)
+  }
+
+  pub fn capslock(self) -> bool {
+    self.contains(Self::CAPSLOCK)
+  }
+
+  pub fn scrolllock(self) -> bool {
+    self.contains(Self::SCROLLLOCK)
+  }
+}
+
+impl BitOr for KeyModifiers {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self {
+    Self(self.0 | rhs.0)
+  }
+}
+
+impl BitOrAssign for KeyModifiers {
+  fn bitor_assign(&mut self, rhs: Self) {
+    self.0 |= rhs.0
+  }
+}
+
+impl From<u16> for KeyModifiers {
+  fn from(bits: u16) -> Self {
+    Self(bits)
+  }
+}
+
+impl From<KeyModifiers> for u16 {
+  fn from(modifiers: KeyModifiers) -> Self {
+    modifiers.into_inner()
+  }
+}
+
+/// An axis of one of the sticks of a [DeviceType::Analog], as read by
+/// [`Callbacks::analog_state`](crate::retro::Callbacks::analog_state).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AnalogAxis {
+  LeftX,
+  LeftY,
+  RightX,
+  RightY,
+}
+
+impl AnalogAxis {
+  /// Returns the `RETRO_DEVICE_INDEX_ANALOG_*` index of the stick.
+  pub fn index(self) -> c_uint {
+    match self {
+      Self::LeftX | Self::LeftY => 0,
+      Self::RightX | Self::RightY => 1,
+    }
+  }
+
+  /// Returns the `RETRO_DEVICE_ID_ANALOG_*` id of the axis.
+  pub fn id(self) -> c_uint {
+    match self {
+      Self::LeftX | Self::RightX => 0,
+      Self::LeftY | Self::RightY => 1,
+    }
+  }
+}
+
+/// The buttons and sticks of a [DeviceType::Analog], as returned by
+/// [`Callbacks::read_gamepad`](crate::retro::Callbacks::read_gamepad).
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let gamepad = Gamepad { buttons: 1 << 8, left_stick: (-100, 0), ..Default::default() };
+/// assert!(gamepad.is_pressed(JoypadButton::A));
+/// assert!(!gamepad.is_pressed(JoypadButton::B));
+/// assert_eq!(gamepad.stick(AnalogAxis::LeftX), -100);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Gamepad {
+  /// A bitmask of the pressed buttons, where bit `n` is the button whose
+  /// [JoypadButton] value is `n`.
+  pub buttons: u16,
+  /// The X and Y axes of the left stick, from -0x8000 to 0x7FFF.
+  pub left_stick: (i16, i16),
+  /// The X and Y axes of the right stick, from -0x8000 to 0x7FFF.
+  pub right_stick: (i16, i16),
+}
+
+impl Gamepad {
+  /// Returns true if `button` is pressed.
+  pub fn is_pressed(&self, button: JoypadButton) -> bool {
+    self.buttons & 1 << (c_uint::from(button) & 0xF) != 0
+  }
+
+  /// Returns the value of a stick axis.
+  pub fn stick(&self, axis: AnalogAxis) -> i16 {
+    match axis {
+      AnalogAxis::LeftX => self.left_stick.0,
+      AnalogAxis::LeftY => self.left_stick.1,
+      AnalogAxis::RightX => self.right_stick.0,
+      AnalogAxis::RightY => self.right_stick.1,
+    }
+  }
+}
+
+/// The state of a [DeviceType::LightGun], as returned by
+/// [`Callbacks::get_lightgun_state`](crate::retro::Callbacks::get_lightgun_state).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LightGunState {
+  /// The horizontal position the gun is pointing at, from -0x7FFF (the left
+  /// edge of the screen) to 0x7FFF (the right edge). -0x8000 means offscreen.
+  pub screen_x: i16,
+  /// The vertical position the gun is pointing at, from -0x7FFF (the top edge
+  /// of the screen) to 0x7FFF (the bottom edge). -0x8000 means offscreen.
+  pub screen_y: i16,
+  /// True if the gun is pointing outside of the screen.
+  pub is_offscreen: bool,
+  pub trigger: bool,
+  /// True if the player is reloading, i.e. shooting offscreen.
+  pub reload: bool,
+}
+
+impl LightGunState {
+  /// Converts the screen position into pixel coordinates within the core's
+  /// `geometry` (using its base size), or returns [None] if the gun is
+  /// pointing offscreen.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let geometry = GameGeometry::fixed(320, 240);
+  /// let gun = LightGunState { screen_x: 0, screen_y: 0, ..Default::default() };
+  /// assert_eq!(gun.screen_position(&geometry), Some((160, 120)));
+  /// let gun = LightGunState { screen_x: -0x7FFF, screen_y: 0x7FFF, ..Default::default() };
+  /// assert_eq!(gun.screen_position(&geometry), Some((0, 239)));
+  /// let gun = LightGunState { is_offscreen: true, ..Default::default() };
+  /// assert_eq!(gun.screen_position(&geometry), None);
+  /// ```
+  pub fn screen_position(&self, geometry: &GameGeometry) -> Option<(u16, u16)> {
+    let x = screen_to_pixel(self.screen_x, geometry.base_width());
+    let y = screen_to_pixel(self.screen_y, geometry.base_height());
+    match self.is_offscreen {
+      true => None,
+      false => Some((x?, y?)),
+    }
+  }
+}
+
+/// The state of a touch of a [DeviceType::Pointer], as returned by
+/// [`Callbacks::pointer_state`](crate::retro::Callbacks::pointer_state).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PointerState {
+  /// The horizontal position of the touch, from -0x7FFF (the left edge of the
+  /// screen) to 0x7FFF (the right edge).
+  pub x: i16,
+  /// The vertical position of the touch, from -0x7FFF (the top edge of the
+  /// screen) to 0x7FFF (the bottom edge).
+  pub y: i16,
+  pub pressed: bool,
+}
+
+/// The state of a [DeviceType::Mouse], as returned by
+/// [`Callbacks::mouse_state`](crate::retro::Callbacks::mouse_state).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MouseState {
+  /// The horizontal movement since the last poll, in screen pixels.
+  pub x: i16,
+  /// The vertical movement since the last poll, in screen pixels.
+  pub y: i16,
+  pub left: bool,
+  pub right: bool,
+  pub middle: bool,
+  pub button_4: bool,
+  pub button_5: bool,
+  pub wheel_up: bool,
+  pub wheel_down: bool,
+}
+
+/// A rumble motor of a joypad. See [RumbleInterface].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RumbleEffect {
+  Strong,
+  Weak,
+}
+
+impl From<RumbleEffect> for retro_rumble_effect {
+  fn from(effect: RumbleEffect) -> Self {
+    match effect {
+      RumbleEffect::Strong => retro_rumble_effect::RETRO_RUMBLE_STRONG,
+      RumbleEffect::Weak => retro_rumble_effect::RETRO_RUMBLE_WEAK,
+    }
+  }
+}
+
+/// The frontend's rumble interface, as returned by
+/// [`Environment::get_rumble_interface`](crate::retro::env::Environment::get_rumble_interface).
+///
+/// Each motor is controlled independently, and keeps its strength until it's
+/// set again, so cores should set a strength of 0 to stop rumbling.
+#[derive(Clone, Copy, Debug)]
+pub struct RumbleInterface {
+  set_rumble_state: unsafe extern "C" fn(c_uint, retro_rumble_effect, u16) -> bool,
+}
+
+impl RumbleInterface {
+  /// Wraps an interface returned by the frontend, or returns [None] if it
+  /// doesn't provide a `set_rumble_state` callback.
+  pub fn new(iface: &retro_rumble_interface) -> Option<Self> {
+    let set_rumble_state = iface.set_rumble_state?;
+    Some(Self { set_rumble_state })
+  }
+
+  /// Sets the `strength` of the `effect` motor of the joypad on `port`, from 0
+  /// (off) to 0xFFFF. Returns false if the frontend couldn't apply it, e.g.
+  /// because the joypad doesn't support rumble.
+  pub fn set_rumble(&self, port: DevicePort, effect: RumbleEffect, strength: u16) -> bool {
+    unsafe { (self.set_rumble_state)(port.into_inner(), effect.into(), strength) }
+  }
+}
+
+/// Names an input of a device, e.g. `"Jump"` for the A button of the joypad on
+/// port 0, so the frontend can show what it does. See
+/// [`SetEnvironment::set_input_descriptors`](crate::retro::env::SetEnvironment::set_input_descriptors).
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let jump = InputDescriptor::new(
+///   DevicePort::new(0),
+///   DeviceType::Joypad,
+///   0,
+///   JoypadButton::A.into(),
+///   c_utf8!("Jump"),
+/// );
+/// assert_eq!(jump.description().to_str(), Ok("Jump"));
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+pub struct InputDescriptor<'a>(retro_input_descriptor, PhantomData<&'a ()>);
+
+impl<'a> InputDescriptor<'a> {
+  /// `index` selects the analog stick for [DeviceType::Analog] axes, and is 0
+  /// otherwise; `id` is the button, axis or key, e.g. a [JoypadButton].
+  pub fn new<T>(
+    port: DevicePort,
+    device: DeviceType,
+    index: c_uint,
+    id: c_uint,
+    description: &'a T,
+  ) -> Self
+  where
+    T: AsRef<CStr> + ?Sized,
+  {
+    Self(
+      retro_input_descriptor {
+        port: port.into_inner(),
+        device: device as c_uint,
+        index,
+        id,
+        description: description.as_ref().as_ptr(),
+      },
+      PhantomData,
+    )
+  }
+
+  pub fn port(&self) -> DevicePort {
+    DevicePort::new(self.0.port)
+  }
+
+  pub fn device(&self) -> DeviceTypeId {
+    DeviceTypeId::new(self.0.device)
+  }
+
+  pub fn index(&self) -> c_uint {
+    self.0.index
+  }
+
+  pub fn id(&self) -> c_uint {
+    self.0.id
+  }
+
+  pub fn description(&self) -> &'a CStr {
+    unsafe { CStr::from_ptr(self.0.description) }
+  }
+
+  pub fn into_inner(self) -> retro_input_descriptor {
+    self.0
+  }
+}
+
+/// Maps a coordinate in -0x7FFF..=0x7FFF onto 0..size.
+fn screen_to_pixel(coord: i16, size: u16) -> Option<u16> {
+  if coord == i16::MIN || size == 0 {
+    return None;
+  }
+  let offset = (coord as i32 + 0x7FFF) as u32;
+  let pixel = offset * size as u32 / 0xFFFE;
+  Some(pixel.min(size as u32 - 1) as u16)
+}
@@ -1,10 +1,13 @@
 pub use crate::convert::*;
 use crate::ffi::*;
 use crate::prelude::*;
-use crate::retro::pixel::format::{ActiveFormat, Format, ORGB1555, RGB565, XRGB8888};
+use crate::retro::mic::MicrophoneInterface;
+use crate::retro::pixel::format::{ActiveFormat, AnyFormat, Format, ORGB1555, RGB565, XRGB8888};
+use crate::retro::vfs::VfsInterface;
 use libretro_rs_ffi::retro_pixel_format::{
   RETRO_PIXEL_FORMAT_0RGB1555, RETRO_PIXEL_FORMAT_RGB565, RETRO_PIXEL_FORMAT_XRGB8888,
 };
+use std::ffi::CString;
 use std::marker::PhantomData;
 
 pub type Result<T> = core::result::Result<T, CommandError>;
@@ -90,6 +93,47 @@ pub trait Environment: Sized {
     unsafe { self.set(RETRO_ENVIRONMENT_SET_MESSAGE, message) }
   }
 
+  /// Like [Environment::set_message], but lets the core set the severity and
+  /// priority of the message. Fails if the frontend doesn't support it, in
+  /// which case [Environment::set_message] can be used instead.
+  fn set_message_ext(&mut self, message: &MessageExt) -> Result<()> {
+    unsafe { self.set(RETRO_ENVIRONMENT_SET_MESSAGE_EXT, message) }
+  }
+
+  /// Overrides the frontend's fast-forwarding mode, e.g. to skip through
+  /// cutscenes. Fails if the frontend doesn't support overriding it.
+  fn set_fastforwarding_override(&mut self, data: FastForwardingOverride) -> Result<()> {
+    unsafe { self.set(RETRO_ENVIRONMENT_SET_FASTFORWARDING_OVERRIDE, &data) }
+  }
+
+  /// Returns true if the frontend supports [Environment::set_fastforwarding_override].
+  fn supports_fastforwarding_override(&self) -> bool {
+    let cmd = RETRO_ENVIRONMENT_SET_FASTFORWARDING_OVERRIDE;
+    unsafe { self.get_ptr()(cmd, core::ptr::null_mut()) }
+  }
+
+  /// Returns true if the frontend can return the state of every joypad button
+  /// at once, as a bitmask. The environment passed to a
+  /// [`Core`](crate::retro::Core) is only queried once, when the core is
+  /// loaded, and [`Callbacks::joypad_buttons`](crate::retro::Callbacks::joypad_buttons)
+  /// uses bitmasks automatically when they're supported.
+  fn get_input_bitmasks(&self) -> bool {
+    let cmd = RETRO_ENVIRONMENT_GET_INPUT_BITMASKS;
+    unsafe { self.get_ptr()(cmd, core::ptr::null_mut()) }
+  }
+
+  /// Returns the override last set with [Environment::set_fastforwarding_override],
+  /// if this environment keeps track of it. The environment passed to a
+  /// [`Core`](crate::retro::Core) does, until the core is deinitialized.
+  fn fastforwarding_override(&self) -> Option<FastForwardingOverride> {
+    None
+  }
+
+  /// Queries whether the frontend is currently fast-forwarding.
+  fn get_fastforwarding(&self) -> Result<bool> {
+    unsafe { self.get(RETRO_ENVIRONMENT_GET_FASTFORWARDING) }
+  }
+
   /// Queries the path where the current libretro core resides.
   fn get_libretro_path(&self) -> Result<Option<&CStr>> {
     unsafe { self.get(RETRO_ENVIRONMENT_GET_LIBRETRO_PATH).unsafe_into() }
@@ -125,6 +169,27 @@ pub trait Environment: Sized {
     }
   }
 
+  /// Queries all of the frontend's directories at once. See [Directories].
+  fn directories(&self) -> Directories {
+    Directories::new(self)
+  }
+
+  /// Creates a directory for the core's own files (e.g. logs, configuration
+  /// or converted saves) at `<save directory>/<library name of C>/<subdir>`,
+  /// along with any missing parents, and returns its path. The directory is
+  /// created through the frontend's [VFS](Environment::get_vfs_interface) if
+  /// it supports directories, and [std::fs] otherwise.
+  ///
+  /// Fails with [std::io::ErrorKind::NotFound] if the frontend doesn't provide
+  /// a save directory.
+  fn create_core_save_dir<'a, C: Core<'a>>(
+    &self,
+    subdir: impl AsRef<std::path::Path>,
+  ) -> std::io::Result<std::path::PathBuf> {
+    let core_name = C::get_system_info().library_name();
+    crate::retro::fs::create_core_save_dir(self, core_name, subdir.as_ref())
+  }
+
   fn get_variable(&self, key: &impl AsRef<CStr>) -> Result<Option<&CStr>> {
     let variable = retro_variable {
       key: key.as_ref().as_ptr(),
@@ -137,11 +202,181 @@ pub trait Environment: Sized {
     }
   }
 
+  /// Queries whether the frontend changed any option since the last call to
+  /// [Environment::get_variable]. This is usually checked at the start of
+  /// `run`, to re-read the options only when needed.
+  fn get_variable_update(&self) -> Result<bool> {
+    unsafe { self.get(RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE) }
+  }
+
+  /// Returns true if the frontend changed any option since the last call to
+  /// [Environment::get_variable], or false if it didn't or can't tell.
+  fn variable_update(&self) -> bool {
+    self.get_variable_update().unwrap_or(false)
+  }
+
+  /// Queries the version of the core options interface the frontend supports.
+  /// [SetEnvironment::set_core_options_v2] requires version 2.
+  fn get_core_options_version(&self) -> Result<c_uint> {
+    unsafe { self.get(RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION) }
+  }
+
+  /// Reads the value of `option`, falling back to its default if it isn't set
+  /// or can't be parsed. See [IntOption].
+  fn get_int_option(&self, option: &IntOption) -> i64 {
+    match self.get_variable(&option.key()) {
+      Ok(Some(value)) => option.parse(value),
+      _ => option.default_value(),
+    }
+  }
+
+  /// Reads the value of `T`'s option, falling back to its default variant if
+  /// it isn't set or doesn't match a variant. See [EnumOption].
+  fn get_enum_option<T: EnumOption>(&self) -> T {
+    match self.get_variable(&T::KEY.as_c_str()) {
+      Ok(Some(value)) => T::parse(value),
+      _ => T::default_variant(),
+    }
+  }
+
   /// Queries the username associated with the frontend.
   fn get_username(&self) -> Result<Option<&CStr>> {
     unsafe { self.get(RETRO_ENVIRONMENT_GET_USERNAME).unsafe_into() }
   }
 
+  /// Queries how many users the frontend supports, so that cores can size
+  /// their per-port state accordingly rather than assuming a fixed number of
+  /// players.
+  fn get_input_max_users(&self) -> Result<c_uint> {
+    unsafe { self.get(RETRO_ENVIRONMENT_GET_INPUT_MAX_USERS) }
+  }
+
+  /// Queries the version of the disk control interface supported by the frontend.
+  /// Version 1 and later support [`retro_disk_control_ext_callback`]. If the
+  /// command fails, the frontend only supports version 0.
+  fn get_disk_control_interface_version(&self) -> Result<c_uint> {
+    unsafe { self.get(RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION) }
+  }
+
+  /// Returns the version of the disk control interface the frontend supports,
+  /// e.g. to only build image labels for version 1 and later. Frontends that
+  /// don't answer [`Environment::get_disk_control_interface_version`] only
+  /// support version 0.
+  fn disk_control_interface_version(&self) -> c_uint {
+    self.get_disk_control_interface_version().unwrap_or(0)
+  }
+
+  /// Queries the refresh rate the frontend is currently targeting, which cores
+  /// can use to pick an ideal frame rate.
+  fn get_target_refresh_rate(&self) -> Result<f32> {
+    unsafe { self.get(RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE) }
+  }
+
+  /// Returns the frontend's monotonic clock, in microseconds, or [None] if it
+  /// doesn't provide a performance interface.
+  ///
+  /// Only `get_time_usec` is used from the performance interface. This
+  /// queries the interface on every call; the environment passed to a
+  /// [`Core`](crate::retro::Core) queries it once and caches the callback.
+  fn get_time_usec(&self) -> Option<u64> {
+    let get_time_usec = get_time_usec_callback(self)?;
+    u64::try_from(unsafe { get_time_usec() }).ok()
+  }
+
+  /// Queries how the frontend is currently pacing calls to `retro_run`
+  /// (e.g. normal speed, fast-forwarding or frame stepping).
+  fn get_throttle_state(&self) -> Result<retro_throttle_state> {
+    unsafe { self.get(RETRO_ENVIRONMENT_GET_THROTTLE_STATE) }
+  }
+
+  /// Queries the API specific rendering interface of the current hardware
+  /// context, or [None] if the frontend doesn't provide one for the API in use.
+  ///
+  /// This can't be called before the core's `context_reset` callback, and the
+  /// returned interface is invalidated once `context_destroy` returns.
+  fn get_hw_render_interface(&self) -> Result<Option<HWRenderInterface<'_>>> {
+    unsafe {
+      self.get(RETRO_ENVIRONMENT_GET_HW_RENDER_INTERFACE).map(
+        |interface: Option<&retro_hw_render_interface>| {
+          interface.map(|interface| HWRenderInterface::from_raw(interface))
+        },
+      )
+    }
+  }
+
+  /// Requests the frontend's virtual file system interface, implementing at
+  /// least `min_version`. Returns [None] if the frontend doesn't provide a VFS
+  /// or only supports older versions. See [vfs](crate::retro::vfs) for what
+  /// each version supports.
+  ///
+  /// This should be called from `set_environment`, so the frontend knows the
+  /// core supports VFS before handing out any paths.
+  fn get_vfs_interface(&self, min_version: u32) -> Option<VfsInterface> {
+    let info = retro_vfs_interface_info {
+      required_interface_version: min_version,
+      iface: core::ptr::null_mut(),
+    };
+    unsafe {
+      let info: retro_vfs_interface_info = self
+        .get_with(RETRO_ENVIRONMENT_GET_VFS_INTERFACE, info)
+        .ok()?;
+      let iface = info.iface.as_ref()?;
+      // Older frontends don't report the version they implement.
+      let version = info.required_interface_version.max(min_version);
+      Some(VfsInterface::from_raw(iface, version))
+    }
+  }
+
+  /// Requests the frontend's microphone interface, or [None] if the frontend
+  /// doesn't support microphones. See [mic](crate::retro::mic).
+  fn get_microphone_interface(&self) -> Option<MicrophoneInterface> {
+    let iface = retro_microphone_interface {
+      interface_version: RETRO_MICROPHONE_INTERFACE_VERSION,
+      ..Default::default()
+    };
+    unsafe {
+      let iface = self
+        .get_with(RETRO_ENVIRONMENT_GET_MICROPHONE_INTERFACE, iface)
+        .ok()?;
+      Some(MicrophoneInterface::from_raw(iface))
+    }
+  }
+
+  /// Requests the frontend's rumble interface, or [None] if the frontend
+  /// doesn't support rumble.
+  fn get_rumble_interface(&self) -> Option<RumbleInterface> {
+    let iface: retro_rumble_interface =
+      unsafe { self.get(RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE) }.ok()?;
+    RumbleInterface::new(&iface)
+  }
+
+  /// Queries extended information about the content being loaded, such as its
+  /// display name, or [None] if the frontend doesn't provide it.
+  ///
+  /// This can only be called from `load_game` or `load_game_special`; in the
+  /// latter, the first content slot is returned.
+  fn get_game_info_ext(&self) -> Option<GameInfoExt<'_>> {
+    unsafe {
+      self
+        .get(RETRO_ENVIRONMENT_GET_GAME_INFO_EXT)
+        .ok()
+        .flatten()
+        .map(|info: &retro_game_info_ext| GameInfoExt::from_raw(*info))
+    }
+  }
+
+  /// Queries the frontend's clear-thread-waits callback, or [None] if the frontend doesn't
+  /// provide one. See [ClearThreadWaits] for when to call it.
+  fn get_clear_all_thread_waits(&self) -> Option<ClearThreadWaits> {
+    unsafe {
+      self
+        .get(RETRO_ENVIRONMENT_GET_CLEAR_ALL_THREAD_WAITS_CB)
+        .ok()
+        .flatten()
+        .map(ClearThreadWaits::new)
+    }
+  }
+
   /// Gets an interface for logging. This is useful for logging in a cross-platform way as certain
   /// platforms cannot use stderr for logging. It also allows the frontend to show logging
   /// information in a more suitable way. If this interface is not used, libretro cores should log
@@ -149,6 +384,81 @@ pub trait Environment: Sized {
   fn get_log_interface(&self) -> Result<PlatformLogger> {
     unsafe { self.get(RETRO_ENVIRONMENT_GET_LOG_INTERFACE).unsafe_into() }
   }
+
+  /// Probes which of the queries wrapped by this crate the frontend answers,
+  /// returning each command with whether it succeeded. Only queries without
+  /// side effects that can be sent at any time are probed, so this doesn't
+  /// cover every command in [supported_commands].
+  fn probe_commands(&self) -> Vec<(c_uint, bool)> {
+    vec![
+      (RETRO_ENVIRONMENT_GET_CAN_DUPE, self.get_can_dupe().is_ok()),
+      (
+        RETRO_ENVIRONMENT_GET_SYSTEM_DIRECTORY,
+        self.get_system_directory().is_ok(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE,
+        self.get_rumble_interface().is_some(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_LOG_INTERFACE,
+        self.get_log_interface().is_ok(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_PERF_INTERFACE,
+        get_time_usec_callback(self).is_some(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_CORE_ASSETS_DIRECTORY,
+        self.get_core_assets_directory().is_ok(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_SAVE_DIRECTORY,
+        self.get_save_directory().is_ok(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_LIBRETRO_PATH,
+        self.get_libretro_path().is_ok(),
+      ),
+      (RETRO_ENVIRONMENT_GET_USERNAME, self.get_username().is_ok()),
+      (
+        RETRO_ENVIRONMENT_GET_INPUT_MAX_USERS,
+        self.get_input_max_users().is_ok(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_INPUT_BITMASKS,
+        self.get_input_bitmasks(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION,
+        self.get_core_options_version().is_ok(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION,
+        self.get_disk_control_interface_version().is_ok(),
+      ),
+      (
+        RETRO_ENVIRONMENT_SET_FASTFORWARDING_OVERRIDE,
+        self.supports_fastforwarding_override(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_FASTFORWARDING,
+        self.get_fastforwarding().is_ok(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE,
+        self.get_target_refresh_rate().is_ok(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_THROTTLE_STATE,
+        self.get_throttle_state().is_ok(),
+      ),
+      (
+        RETRO_ENVIRONMENT_GET_MICROPHONE_INTERFACE,
+        self.get_microphone_interface().is_some(),
+      ),
+    ]
+  }
 }
 
 impl Environment for non_null_retro_environment_t {
@@ -161,12 +471,372 @@ pub trait SetEnvironment: Environment {
   fn set_support_no_game(&mut self, data: bool) -> Result<()> {
     unsafe { self.set(RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME, &data) }
   }
+
+  /// Declares the subsystems the core can load with `retro_load_game_special`.
+  ///
+  /// Frontends keep pointers to the descriptions, identifiers and ROM slots
+  /// rather than copying them, so they must be `'static`. The array passed to
+  /// the frontend is leaked for the same reason, so this should only be called
+  /// once, from `set_environment`.
+  fn set_subsystem_info(&mut self, subsystems: &[SubsystemInfo<'static>]) -> Result<()> {
+    let mut info: Vec<retro_subsystem_info> = subsystems.iter().map(|s| s.into_inner()).collect();
+    // The array is terminated by a zeroed subsystem.
+    info.push(retro_subsystem_info::default());
+    let info: &'static [retro_subsystem_info] = Vec::leak(info);
+    unsafe { self.set(RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO, &info[0]) }
+  }
+
+  /// Registers the disk control callbacks, picking the interface based on
+  /// [`Environment::disk_control_interface_version`]. If the frontend only
+  /// supports version 0, the optional `set_initial_image`, `get_image_path` and
+  /// `get_image_label` callbacks are dropped.
+  ///
+  /// Returns the version of the interface that was registered.
+  fn set_disk_control_interface(
+    &mut self,
+    callbacks: &retro_disk_control_ext_callback,
+  ) -> Result<c_uint> {
+    match self.disk_control_interface_version() {
+      0 => {
+        let callbacks = retro_disk_control_callback {
+          set_eject_state: callbacks.set_eject_state,
+          get_eject_state: callbacks.get_eject_state,
+          get_image_index: callbacks.get_image_index,
+          set_image_index: callbacks.set_image_index,
+          get_num_images: callbacks.get_num_images,
+          replace_image_index: callbacks.replace_image_index,
+          add_image_index: callbacks.add_image_index,
+        };
+        unsafe { self.set(RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE, &callbacks) }.map(|_| 0)
+      }
+      version => unsafe { self.set(RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE, callbacks) }
+        .map(|_| version),
+    }
+  }
+
+  /// Tells the frontend what each of the core's inputs does, so it can label
+  /// them in its input settings. This may be called at any time, and replaces
+  /// any descriptors that were previously set.
+  ///
+  /// Frontends may keep pointers to the descriptions rather than copying them,
+  /// so they must be `'static`.
+  fn set_input_descriptors(&mut self, descriptors: &[InputDescriptor<'static>]) -> Result<()> {
+    let mut raw: Vec<retro_input_descriptor> = descriptors.iter().map(|d| d.into_inner()).collect();
+    // The array is terminated by a descriptor with a null description.
+    raw.push(retro_input_descriptor::default());
+    unsafe { self.set(RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS, &raw[0]) }
+  }
+
+  /// Declares integer core options to the frontend. The frontend copies the
+  /// strings, so `options` doesn't need to outlive this call.
+  ///
+  /// Note that this replaces any options that were previously declared.
+  fn set_int_options(&mut self, options: &[IntOption]) -> Result<()> {
+    let values: Vec<CString> = options.iter().map(IntOption::to_value_string).collect();
+    let mut variables: Vec<retro_variable> = options
+      .iter()
+      .zip(&values)
+      .map(|(option, value)| retro_variable { key: option.key().as_ptr(), value: value.as_ptr() })
+      .collect();
+    // The array is terminated by an empty variable.
+    variables.push(retro_variable { key: core::ptr::null(), value: core::ptr::null() });
+    unsafe { self.set(RETRO_ENVIRONMENT_SET_VARIABLES, &variables[0]) }
+  }
+
+  /// Declares the core's options and their categories. This should be called
+  /// from `set_environment`, and only if
+  /// [Environment::get_core_options_version] is at least 2; older frontends
+  /// need [SetEnvironment::set_int_options] instead. It may be called again
+  /// later to update the options, as long as their number doesn't change.
+  ///
+  /// Returns true if the frontend shows the options by category.
+  fn set_core_options_v2(&mut self, options: &CoreOptions) -> bool {
+    unsafe { self.set(RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2, &options.as_raw()) }.is_ok()
+  }
+
+  /// Registers a callback that the frontend calls when a key is pressed or
+  /// released, which unlike polling [`Callbacks::key_pressed`](crate::retro::Callbacks::key_pressed)
+  /// doesn't miss short presses, and also reports the typed character and the
+  /// held modifiers. Cores using the [`libretro_core`](crate::libretro_core)
+  /// macro should set [`Core::KEYBOARD_EVENTS`](crate::retro::Core::KEYBOARD_EVENTS)
+  /// instead, which registers a callback that forwards events to
+  /// [`Core::keyboard_event`](crate::retro::Core::keyboard_event).
+  fn set_keyboard_callback(&mut self, callback: &retro_keyboard_callback) -> Result<()> {
+    unsafe { self.set(RETRO_ENVIRONMENT_SET_KEYBOARD_CALLBACK, callback) }
+  }
+
+  /// Exposes the core's `get_proc_address` interface to the frontend, so it
+  /// can call core-specific extension functions directly.
+  fn set_proc_address_callback(
+    &mut self,
+    interface: &retro_get_proc_address_interface,
+  ) -> Result<()> {
+    unsafe { self.set(RETRO_ENVIRONMENT_SET_PROC_ADDRESS_CALLBACK, interface) }
+  }
 }
 impl<T: Environment> SetEnvironment for T {}
 
-pub trait Init: Environment {}
+/// The settings a core declares to the frontend, applied together with
+/// [EnvironmentConfig::configure]. Settings that aren't given are left alone.
+///
+/// The pixel format can't be set from `set_environment`, so it's applied
+/// separately, with [EnvironmentConfig::configure_pixel_format].
+///
+/// ```
+/// # use libretro_rs::prelude::*;
+/// # use libretro_rs::retro::env::EnvironmentConfig;
+/// fn set_environment(env: &mut impl env::SetEnvironment) {
+///   let options = [IntOption::new("frameskip", "Frameskip", 0, 5)];
+///   let jump = c_utf8!("Jump");
+///   let inputs = [InputDescriptor::new(
+///     DevicePort::new(0),
+///     DeviceType::Joypad,
+///     0,
+///     JoypadButton::A.into(),
+///     jump,
+///   )];
+///   let result = EnvironmentConfig::new()
+///     .support_no_game(true)
+///     .int_options(&options)
+///     .input_descriptors(&inputs)
+///     .configure(env);
+///   if let Err(err) = result {
+///     eprintln!("{err}");
+///   }
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnvironmentConfig<'a> {
+  support_no_game: Option<bool>,
+  subsystems: Option<&'a [SubsystemInfo<'static>]>,
+  int_options: Option<&'a [IntOption]>,
+  core_options: Option<&'a CoreOptions>,
+  input_descriptors: Option<&'a [InputDescriptor<'static>]>,
+  pixel_formats: Option<&'a [retro_pixel_format]>,
+}
+
+impl<'a> EnvironmentConfig<'a> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// See [SetEnvironment::set_support_no_game].
+  pub fn support_no_game(mut self, support_no_game: bool) -> Self {
+    self.support_no_game = Some(support_no_game);
+    self
+  }
+
+  /// See [SetEnvironment::set_subsystem_info].
+  pub fn subsystems(mut self, subsystems: &'a [SubsystemInfo<'static>]) -> Self {
+    self.subsystems = Some(subsystems);
+    self
+  }
+
+  /// See [SetEnvironment::set_int_options]. If [core options](Self::core_options)
+  /// are also given, these are only declared to frontends that don't support
+  /// the latter.
+  pub fn int_options(mut self, options: &'a [IntOption]) -> Self {
+    self.int_options = Some(options);
+    self
+  }
+
+  /// See [SetEnvironment::set_core_options_v2]. These are only declared if
+  /// [Environment::get_core_options_version] is at least 2; otherwise, the
+  /// [int options](Self::int_options) are declared instead, and
+  /// `RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2` is reported as failed if there
+  /// are none.
+  pub fn core_options(mut self, options: &'a CoreOptions) -> Self {
+    self.core_options = Some(options);
+    self
+  }
+
+  /// See [SetEnvironment::set_input_descriptors].
+  pub fn input_descriptors(mut self, descriptors: &'a [InputDescriptor<'static>]) -> Self {
+    self.input_descriptors = Some(descriptors);
+    self
+  }
+
+  /// The pixel formats to offer, in order of preference. See
+  /// [GetAvInfo::set_pixel_formats] and
+  /// [EnvironmentConfig::configure_pixel_format].
+  pub fn pixel_formats(mut self, formats: &'a [retro_pixel_format]) -> Self {
+    self.pixel_formats = Some(formats);
+    self
+  }
+
+  /// Applies every setting but the pixel format, even if some of them fail.
+  /// The error lists the commands the frontend rejected.
+  pub fn configure(
+    &self,
+    env: &mut impl SetEnvironment,
+  ) -> core::result::Result<(), ConfigureError> {
+    let mut failed = Vec::new();
+    let mut check = |cmd, result: Result<()>| {
+      if result.is_err() {
+        failed.push(cmd);
+      }
+    };
+    if let Some(support_no_game) = self.support_no_game {
+      let result = env.set_support_no_game(support_no_game);
+      check(RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME, result);
+    }
+    if let Some(subsystems) = self.subsystems {
+      let result = env.set_subsystem_info(subsystems);
+      check(RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO, result);
+    }
+    let core_options = self.core_options.filter(|_| {
+      env
+        .get_core_options_version()
+        .is_ok_and(|version| version >= 2)
+    });
+    let core_options_set = core_options.is_some_and(|options| {
+      // The frontend also answers false if it accepted the options but doesn't
+      // show categories, so ask it for one of them to tell the two apart.
+      env.set_core_options_v2(options)
+        || options
+          .keys()
+          .next()
+          .is_none_or(|key| matches!(env.get_variable(&key), Ok(Some(_))))
+    });
+    if !core_options_set {
+      match (self.int_options, self.core_options) {
+        (Some(options), _) => {
+          let result = env.set_int_options(options);
+          check(RETRO_ENVIRONMENT_SET_VARIABLES, result);
+        }
+        // There's nothing to fall back to.
+        (None, Some(_)) => check(
+          RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2,
+          Err(CommandError::new()),
+        ),
+        (None, None) => {}
+      }
+    }
+    if let Some(descriptors) = self.input_descriptors {
+      let result = env.set_input_descriptors(descriptors);
+      check(RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS, result);
+    }
+    match failed.is_empty() {
+      true => Ok(()),
+      false => Err(ConfigureError::new(failed)),
+    }
+  }
+
+  /// Offers the [pixel formats](Self::pixel_formats) to the frontend, from
+  /// `load_game` or `get_system_av_info`. Returns the format that's active
+  /// afterwards: the first one the frontend accepted, or `current_format` if
+  /// it accepted none (which is reported as `RETRO_ENVIRONMENT_SET_PIXEL_FORMAT`
+  /// in the error) or if no pixel formats were given.
+  pub fn configure_pixel_format<P>(
+    &self,
+    env: &mut impl GetAvInfo,
+    current_format: ActiveFormat<P>,
+  ) -> (AnyFormat, core::result::Result<(), ConfigureError>)
+  where
+    AnyFormat: From<ActiveFormat<P>>,
+  {
+    let Some(formats) = self.pixel_formats else {
+      return (current_format.into(), Ok(()));
+    };
+    match env.set_pixel_formats(current_format, formats) {
+      Ok(format) => (format, Ok(())),
+      Err(format) => (
+        format.into(),
+        Err(ConfigureError::new(vec![
+          RETRO_ENVIRONMENT_SET_PIXEL_FORMAT,
+        ])),
+      ),
+    }
+  }
+}
+
+/// Commands that can be used from either `retro_init` or `retro_load_game`.
+pub trait Init: Environment {
+  /// Tells the frontend about the limitations of the core's save states. The
+  /// frontend clears any quirks it doesn't recognize or support, and sets
+  /// [SerializationQuirks::FRONT_VARIABLE_SIZE] if it supports variable-sized
+  /// states; the result is returned.
+  ///
+  /// This must be called from either `init` or `load_game`, but not both.
+  fn set_serialization_quirks(
+    &mut self,
+    quirks: SerializationQuirks,
+  ) -> Result<SerializationQuirks> {
+    unsafe { self.cmd(RETRO_ENVIRONMENT_SET_SERIALIZATION_QUIRKS, quirks) }
+  }
+
+  /// Tells the frontend that save states can only be loaded in the session
+  /// they were created in, so it won't try to restore them after the core is
+  /// restarted (e.g. from auto-saved states). Use this when states contain data
+  /// that's only valid for the lifetime of the process, such as pointers or
+  /// handles to host resources; rewind and run-ahead keep working.
+  ///
+  /// This sets [SerializationQuirks::SINGLE_SESSION] via
+  /// [Init::set_serialization_quirks], so the same restrictions apply; if the
+  /// core has other quirks, set them all at once with that method instead.
+  fn disable_cross_session_states(&mut self) -> Result<()> {
+    self
+      .set_serialization_quirks(SerializationQuirks::SINGLE_SESSION)
+      .map(|_| ())
+  }
+
+  /// Describes the core's emulated address space to the frontend, so
+  /// achievement and cheat engines can read and write its memory. See
+  /// [MemoryDescriptor].
+  ///
+  /// This replaces any memory maps that were previously set.
+  ///
+  /// # Safety
+  /// Each mapped descriptor's memory must be valid for reads and writes of
+  /// [MemoryDescriptor::offset] + [MemoryDescriptor::len] bytes.
+  ///
+  /// The frontend copies the descriptors, but keeps pointers to the memory
+  /// and address space names they refer to. Both must stay valid (and must
+  /// not move) until the maps are set again or the core is deinitialized,
+  /// e.g. by keeping the memory in boxes or vectors that the core owns and
+  /// never resizes.
+  unsafe fn set_memory_maps(&mut self, descriptors: &[MemoryDescriptor]) -> Result<()> {
+    let map = retro_memory_map {
+      descriptors: descriptors.as_ptr().cast(),
+      num_descriptors: descriptors.len() as c_uint,
+    };
+    self.set(RETRO_ENVIRONMENT_SET_MEMORY_MAPS, &map)
+  }
+
+  /// Logs which environment commands this crate wraps and which of them the
+  /// frontend answers (see [Environment::probe_commands]), to help tell apart
+  /// features the frontend doesn't support from bugs in the core. The summary
+  /// is logged at [RetroLogLevel::Info] and each command at
+  /// [RetroLogLevel::Debug].
+  fn log_capabilities<T: LogInterface>(&self, logger: &mut FallbackLogger<T>) {
+    let probed = self.probe_commands();
+    let accepted = probed.iter().filter(|(_, ok)| *ok).count();
+    logger.log_display(
+      RetroLogLevel::Info,
+      format_args!(
+        "libretro-rs wraps {} environment commands; the frontend answered {accepted} of {} probed",
+        supported_commands().len(),
+        probed.len()
+      ),
+    );
+    for (cmd, ok) in probed {
+      let name = command_name(cmd).unwrap_or("unknown command");
+      let answer = match ok {
+        true => "supported",
+        false => "not supported",
+      };
+      logger.log_display(
+        RetroLogLevel::Debug,
+        format_args!("{name} ({cmd}): {answer}"),
+      );
+    }
+  }
+}
 impl<T: Environment> Init for T {}
 
+pub trait DiskControl: Environment {}
+impl<T: Environment> DiskControl for T {}
+
 pub trait SetPortDevice: Environment {}
 impl<T: Environment> SetPortDevice for T {}
 
@@ -179,9 +849,79 @@ pub trait Run: Environment {
     unsafe { self.cmd(RETRO_ENVIRONMENT_SHUTDOWN, ()) }
   }
 
+  /// Changes the base width, height and aspect ratio of the video output, e.g.
+  /// when switching to a high resolution mode, without the full video
+  /// reinitialization of [`RETRO_ENVIRONMENT_SET_SYSTEM_AV_INFO`]. The timings
+  /// are unchanged.
+  ///
+  /// The max width and height of `geometry` are ignored: frames can still be
+  /// at most as large as the max size reported by
+  /// [`Core::get_system_av_info`](crate::retro::Core::get_system_av_info).
+  /// Frontends may not support this command, in which case it fails and the
+  /// core should keep uploading frames of the previous size, or letterbox.
   fn set_geometry(&mut self, geometry: &GameGeometry) -> Result<()> {
     unsafe { self.set(RETRO_ENVIRONMENT_SET_GEOMETRY, geometry) }
   }
+
+  /// Changes both the geometry and the timing of the video and audio output,
+  /// e.g. when switching between NTSC and PAL, which changes the frame rate
+  /// and sample rate. `av_info` replaces the one returned by
+  /// [`Core::get_system_av_info`](crate::retro::Core::get_system_av_info),
+  /// including the max width and height.
+  ///
+  /// This is much heavier than [`Run::set_geometry`]: the frontend may
+  /// reinitialize its audio and video drivers, so only use it when the timing
+  /// changes. Frontends may not support this command, in which case it fails.
+  fn set_system_av_info(&mut self, av_info: &SystemAVInfo) -> Result<()> {
+    unsafe { self.set(RETRO_ENVIRONMENT_SET_SYSTEM_AV_INFO, av_info) }
+  }
+
+  /// Returns true if the frontend reports that it's paused, in which case the
+  /// core can skip work that doesn't affect emulation (e.g. audio mixing).
+  ///
+  /// A paused frontend usually doesn't call `retro_run` at all; it's only
+  /// called while paused to step single frames, which the frontend reports as
+  /// [RETRO_THROTTLE_FRAME_STEPPING]. Frontends that don't report their
+  /// throttle state are never considered paused. To detect a pause without
+  /// the frontend's help, see [`RunTracker`](crate::retro::timing::RunTracker).
+  fn is_paused(&self) -> bool {
+    self
+      .get_throttle_state()
+      .is_ok_and(|state| state.mode == RETRO_THROTTLE_FRAME_STEPPING)
+  }
+
+  /// Returns true if the core has overridden fast-forwarding and the frontend
+  /// is honouring the override. Frontends that don't report whether they're
+  /// fast-forwarding are assumed to honour any override they accepted.
+  fn is_fastforwarding_overridden(&self) -> bool {
+    match self.fastforwarding_override() {
+      Some(data) => self
+        .get_fastforwarding()
+        .map_or(true, |fastforwarding| fastforwarding == data.fastforward()),
+      None => false,
+    }
+  }
+
+  /// Returns true if the core's fast-forwarding override prevents the user
+  /// from toggling fast-forwarding.
+  fn is_fastforwarding_toggle_inhibited(&self) -> bool {
+    self
+      .fastforwarding_override()
+      .is_some_and(|data| data.inhibit_toggle())
+  }
+
+  /// Returns true if the frontend changed any option since the options were
+  /// last read, so `run` only needs to re-read them with
+  /// [Environment::get_variable] when this returns true. Frontends that don't
+  /// support the query are assumed to never change options.
+  ///
+  /// Each call is forwarded to the frontend, which owns the flag; nothing is
+  /// cached, so calling this several times in a frame only ever observes the
+  /// frontend's own state. Note that some frontends clear the flag when it's
+  /// queried, so the result should be kept until the options are re-read.
+  fn variables_need_update(&mut self) -> bool {
+    self.variable_update()
+  }
 }
 impl<T: Environment> Run for T {}
 
@@ -200,7 +940,7 @@ impl<T: Environment> CheatReset for T {}
 pub trait CheatSet: Environment {}
 impl<T: Environment> CheatSet for T {}
 
-pub trait LoadGame: Environment {
+pub trait LoadGame: Init {
   /// Gives a hint to the frontend how demanding this implementation is on a system. E.g. Reporting
   /// a level of 2 means this implementation should run decently on all frontends of level 2 and up.
   ///
@@ -249,9 +989,45 @@ pub trait LoadGame: Environment {
     GetAvInfo::set_pixel_format_rgb565(self, current_format)
   }
 
+  fn set_pixel_formats<P>(
+    &mut self,
+    current_format: ActiveFormat<P>,
+    formats: &[retro_pixel_format],
+  ) -> core::result::Result<AnyFormat, ActiveFormat<P>>
+  where
+    P: Format,
+  {
+    GetAvInfo::set_pixel_formats(self, current_format, formats)
+  }
+
   fn set_hw_render_none(&mut self) -> Result<()>;
 
   fn set_hw_render_gl(&mut self, options: GLOptions) -> Result<GLRenderEnabled>;
+
+  /// Requests a Vulkan context. The core is notified when the context is
+  /// created or destroyed through [VulkanCore](crate::retro::VulkanCore).
+  fn set_hw_render_vulkan(&mut self, options: VulkanOptions) -> Result<VulkanRenderEnabled>;
+
+  /// Sets the interface the frontend uses to negotiate the creation of the
+  /// hardware context with the core, e.g. to let a Vulkan core pick the
+  /// instance and device extensions. Must be called after one of the
+  /// `set_hw_render_*` functions.
+  ///
+  /// # Safety
+  /// `interface` must be the header of the full interface struct for its
+  /// `interface_type` and `interface_version` (e.g.
+  /// `retro_hw_render_context_negotiation_interface_vulkan` in
+  /// `libretro_vulkan.h`), whose function pointers must remain valid for the
+  /// lifetime of the core.
+  unsafe fn set_hw_render_context_negotiation_interface(
+    &mut self,
+    interface: &'static retro_hw_render_context_negotiation_interface,
+  ) -> Result<()> {
+    self.set(
+      RETRO_ENVIRONMENT_SET_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE,
+      interface,
+    )
+  }
 }
 
 #[non_exhaustive]
@@ -310,6 +1086,35 @@ pub trait GetAvInfo: Environment {
         .map_err(|_| current_format)
     }
   }
+
+  /// Offers `formats` to the frontend in order of preference, and returns the
+  /// first one it accepts. If none are accepted, the current format is
+  /// returned as the error. Formats this crate doesn't support are skipped.
+  fn set_pixel_formats<F>(
+    &mut self,
+    current_format: ActiveFormat<F>,
+    formats: &[retro_pixel_format],
+  ) -> core::result::Result<AnyFormat, ActiveFormat<F>> {
+    let mut current_format = current_format;
+    for format in formats {
+      current_format = match format {
+        RETRO_PIXEL_FORMAT_0RGB1555 => match self.set_pixel_format_0rgb1555(current_format) {
+          Ok(format) => return Ok(format.into()),
+          Err(format) => format,
+        },
+        RETRO_PIXEL_FORMAT_RGB565 => match self.set_pixel_format_rgb565(current_format) {
+          Ok(format) => return Ok(format.into()),
+          Err(format) => format,
+        },
+        RETRO_PIXEL_FORMAT_XRGB8888 => match self.set_pixel_format_xrgb8888(current_format) {
+          Ok(format) => return Ok(format.into()),
+          Err(format) => format,
+        },
+        _ => current_format,
+      };
+    }
+    Err(current_format)
+  }
 }
 impl<T: Environment> GetAvInfo for T {}
 
@@ -331,6 +1136,79 @@ impl<T: Environment> GetMemorySize for T {}
 pub trait Deinit: Environment {}
 impl<T: Environment> Deinit for T {}
 
+/// Queries the `get_time_usec` callback of the frontend's performance
+/// interface, if it provides one.
+pub(crate) fn get_time_usec_callback(env: &impl Environment) -> retro_perf_get_time_usec_t {
+  let perf: retro_perf_callback = unsafe { env.get(RETRO_ENVIRONMENT_GET_PERF_INTERFACE) }.ok()?;
+  perf.get_time_usec
+}
+
+macro_rules! supported_commands {
+  ($($cmd:ident),* $(,)?) => {
+    /// Returns the `RETRO_ENVIRONMENT_*` commands this crate wraps. Whether the
+    /// frontend supports them can be checked with [Environment::probe_commands].
+    pub fn supported_commands() -> &'static [c_uint] {
+      &[$($cmd),*]
+    }
+
+    /// Returns the name of `cmd` if it's one of the [supported_commands].
+    pub fn command_name(cmd: c_uint) -> Option<&'static str> {
+      match cmd {
+        $($cmd => Some(stringify!($cmd)),)*
+        _ => None,
+      }
+    }
+  };
+}
+
+supported_commands![
+  RETRO_ENVIRONMENT_SET_ROTATION,
+  RETRO_ENVIRONMENT_GET_CAN_DUPE,
+  RETRO_ENVIRONMENT_SET_MESSAGE,
+  RETRO_ENVIRONMENT_SHUTDOWN,
+  RETRO_ENVIRONMENT_SET_PERFORMANCE_LEVEL,
+  RETRO_ENVIRONMENT_GET_SYSTEM_DIRECTORY,
+  RETRO_ENVIRONMENT_SET_PIXEL_FORMAT,
+  RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS,
+  RETRO_ENVIRONMENT_SET_KEYBOARD_CALLBACK,
+  RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE,
+  RETRO_ENVIRONMENT_SET_HW_RENDER,
+  RETRO_ENVIRONMENT_GET_VARIABLE,
+  RETRO_ENVIRONMENT_SET_VARIABLES,
+  RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE,
+  RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME,
+  RETRO_ENVIRONMENT_GET_LIBRETRO_PATH,
+  RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE,
+  RETRO_ENVIRONMENT_GET_LOG_INTERFACE,
+  RETRO_ENVIRONMENT_GET_PERF_INTERFACE,
+  RETRO_ENVIRONMENT_GET_CORE_ASSETS_DIRECTORY,
+  RETRO_ENVIRONMENT_GET_SAVE_DIRECTORY,
+  RETRO_ENVIRONMENT_SET_SYSTEM_AV_INFO,
+  RETRO_ENVIRONMENT_SET_PROC_ADDRESS_CALLBACK,
+  RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO,
+  RETRO_ENVIRONMENT_SET_MEMORY_MAPS,
+  RETRO_ENVIRONMENT_SET_GEOMETRY,
+  RETRO_ENVIRONMENT_GET_USERNAME,
+  RETRO_ENVIRONMENT_GET_HW_RENDER_INTERFACE,
+  RETRO_ENVIRONMENT_SET_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE,
+  RETRO_ENVIRONMENT_SET_SERIALIZATION_QUIRKS,
+  RETRO_ENVIRONMENT_GET_VFS_INTERFACE,
+  RETRO_ENVIRONMENT_GET_INPUT_BITMASKS,
+  RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION,
+  RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2,
+  RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION,
+  RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE,
+  RETRO_ENVIRONMENT_SET_MESSAGE_EXT,
+  RETRO_ENVIRONMENT_GET_INPUT_MAX_USERS,
+  RETRO_ENVIRONMENT_SET_FASTFORWARDING_OVERRIDE,
+  RETRO_ENVIRONMENT_GET_GAME_INFO_EXT,
+  RETRO_ENVIRONMENT_GET_FASTFORWARDING,
+  RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE,
+  RETRO_ENVIRONMENT_GET_THROTTLE_STATE,
+  RETRO_ENVIRONMENT_GET_MICROPHONE_INTERFACE,
+  RETRO_ENVIRONMENT_GET_CLEAR_ALL_THREAD_WAITS_CB,
+];
+
 unsafe fn with_ref(
   cb: non_null_retro_environment_t,
   cmd: c_uint,
@@ -373,15 +1251,38 @@ impl CommandData for () {}
 impl CommandData for bool {}
 impl CommandData for c_int {}
 impl CommandData for c_uint {}
+impl CommandData for f32 {}
 impl CommandData for Option<&c_char> {}
 impl CommandData for Option<&c_void> {}
+impl CommandData for retro_environment_t {}
+impl CommandData for retro_disk_control_callback {}
+impl CommandData for retro_disk_control_ext_callback {}
 impl CommandData for retro_hw_render_callback {}
+impl CommandData for retro_hw_render_context_negotiation_interface {}
+impl CommandData for retro_input_descriptor {}
+impl CommandData for retro_keyboard_callback {}
+impl CommandData for Option<&retro_hw_render_interface> {}
+impl CommandData for Option<&retro_game_info_ext> {}
 impl CommandData for retro_game_geometry {}
+impl CommandData for retro_get_proc_address_interface {}
 impl CommandData for GameGeometry {}
+impl CommandData for retro_fastforwarding_override {}
+impl CommandData for FastForwardingOverride {}
 impl CommandData for retro_log_callback {}
+impl CommandData for retro_perf_callback {}
+impl CommandData for retro_core_options_v2 {}
+impl CommandData for retro_memory_map {}
+impl CommandData for retro_microphone_interface {}
 impl CommandData for retro_message {}
 impl CommandData for Message {}
+impl CommandData for retro_message_ext {}
+impl CommandData for MessageExt {}
 impl CommandData for retro_pixel_format {}
+impl CommandData for retro_rumble_interface {}
+impl CommandData for retro_subsystem_info {}
 impl CommandData for retro_system_av_info {}
+impl CommandData for retro_throttle_state {}
 impl CommandData for SystemAVInfo {}
+impl CommandData for SerializationQuirks {}
 impl CommandData for retro_variable {}
+impl CommandData for retro_vfs_interface_info {}
@@ -1,3 +1,4 @@
+use core::ffi::c_uint;
 use core::fmt::{Display, Formatter};
 use std::error::Error;
 use std::fmt::Debug;
@@ -56,15 +57,98 @@ macro_rules! retro_error {
   };
 }
 
-retro_error!(CoreError, "a libretro API function call did not succeed");
+/// The error returned by fallible core callbacks. The frontend only learns
+/// that the call failed, but the error that caused it (if any) is kept as the
+/// [source](Error::source) for debugging.
+#[derive(Debug, Default)]
+pub struct CoreError(Option<Box<dyn Error + Send + Sync>>);
+
+impl CoreError {
+  pub fn new() -> Self {
+    Self(None)
+  }
+
+  /// Creates an error caused by `source`.
+  pub fn with_source(source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+    Self(Some(source.into()))
+  }
+}
+
+impl Display for CoreError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    write!(f, "a libretro API function call did not succeed")
+  }
+}
+
+impl Error for CoreError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    self
+      .0
+      .as_deref()
+      .map(|source| source as &(dyn Error + 'static))
+  }
+}
+
+impl<T> From<::core::result::Result<T, Box<dyn Error>>> for CoreError {
+  fn from(_value: ::core::result::Result<T, Box<dyn Error>>) -> Self {
+    Self::new()
+  }
+}
+
+impl From<std::io::Error> for CoreError {
+  fn from(value: std::io::Error) -> Self {
+    Self::with_source(value)
+  }
+}
+
 retro_error!(
   CommandError,
   "a libretro environment command did not succeed"
 );
 
+/// The error returned by
+/// [`EnvironmentConfig::configure`](crate::retro::env::EnvironmentConfig::configure)
+/// when the frontend rejected some of the settings.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConfigureError(Vec<c_uint>);
+
+impl ConfigureError {
+  pub fn new(failed_commands: Vec<c_uint>) -> Self {
+    Self(failed_commands)
+  }
+
+  /// Returns the `RETRO_ENVIRONMENT_*` commands that failed, in the order
+  /// they were issued.
+  pub fn failed_commands(&self) -> &[c_uint] {
+    &self.0
+  }
+}
+
+impl Display for ConfigureError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    write!(f, "the frontend rejected")?;
+    for (i, &cmd) in self.0.iter().enumerate() {
+      let separator = if i == 0 { " " } else { ", " };
+      match crate::retro::env::command_name(cmd) {
+        Some(name) => write!(f, "{separator}{name}")?,
+        None => write!(f, "{separator}{cmd}")?,
+      }
+    }
+    Ok(())
+  }
+}
+
+impl Error for ConfigureError {}
+
+impl From<ConfigureError> for CoreError {
+  fn from(value: ConfigureError) -> Self {
+    Self::with_source(value)
+  }
+}
+
 impl From<CommandError> for CoreError {
-  fn from(_value: CommandError) -> Self {
-    Self::new()
+  fn from(value: CommandError) -> Self {
+    Self::with_source(value)
   }
 }
 
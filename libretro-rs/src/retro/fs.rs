@@ -1,4 +1,9 @@
+use crate::retro::env::Environment;
+use crate::retro::vfs::VfsInterface;
 use ::core::ffi::*;
+use std::ffi::CString;
+use std::io;
+use std::path::{Path, PathBuf};
 
 /// A list of file extensions encoded in a pipe-delimited static C string,
 /// as specified by the libretro API. The [ext!] macro provides a convenient
@@ -51,3 +56,125 @@ macro_rules! ext {
     $crate::retro::fs::Extensions::new(c_utf8!(concat!($head, $("|", $tail),+)))
   }
 }
+
+/// The directories provided by the frontend, queried all at once by
+/// [Environment::directories]. Each directory is [None] if the frontend
+/// doesn't define it (or doesn't support the query).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Directories {
+  /// The "system" directory, for BIOSes, configuration data, etc.
+  /// See [Environment::get_system_directory].
+  pub system: Option<PathBuf>,
+  /// The directory for save data such as SRAM and memory cards.
+  /// See [Environment::get_save_directory].
+  pub save: Option<PathBuf>,
+  /// The directory for assets shipped alongside the core.
+  /// See [Environment::get_core_assets_directory].
+  pub core_assets: Option<PathBuf>,
+  /// The path of the core itself. See [Environment::get_libretro_path].
+  pub libretro: Option<PathBuf>,
+}
+
+impl Directories {
+  /// Queries every directory from the frontend.
+  pub fn new(env: &impl Environment) -> Self {
+    Self {
+      system: to_path_buf(env.get_system_directory()),
+      save: to_path_buf(env.get_save_directory()),
+      core_assets: to_path_buf(env.get_core_assets_directory()),
+      libretro: to_path_buf(env.get_libretro_path()),
+    }
+  }
+
+  /// Returns the path of `file` within the system directory.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// use std::path::PathBuf;
+  /// let dirs = Directories { system: Some("/bios".into()), ..Directories::default() };
+  /// assert_eq!(dirs.system_file("scph1001.bin"), Some(PathBuf::from("/bios/scph1001.bin")));
+  /// assert_eq!(dirs.save_file("game.srm"), None);
+  /// ```
+  pub fn system_file(&self, file: impl AsRef<Path>) -> Option<PathBuf> {
+    join(&self.system, file)
+  }
+
+  /// Returns the path of `file` within the save directory.
+  pub fn save_file(&self, file: impl AsRef<Path>) -> Option<PathBuf> {
+    join(&self.save, file)
+  }
+
+  /// Returns the path of `file` within the core assets directory.
+  pub fn core_assets_file(&self, file: impl AsRef<Path>) -> Option<PathBuf> {
+    join(&self.core_assets, file)
+  }
+}
+
+fn join(dir: &Option<PathBuf>, file: impl AsRef<Path>) -> Option<PathBuf> {
+  dir.as_ref().map(|dir| dir.join(file))
+}
+
+fn to_path_buf<E>(path: Result<Option<&CStr>, E>) -> Option<PathBuf> {
+  path.ok().flatten().map(c_str_to_path)
+}
+
+fn c_str_to_path(path: &CStr) -> PathBuf {
+  #[cfg(unix)]
+  {
+    use std::os::unix::ffi::OsStrExt;
+    std::ffi::OsStr::from_bytes(path.to_bytes()).into()
+  }
+  #[cfg(not(unix))]
+  path.to_string_lossy().into_owned().into()
+}
+
+fn path_to_c_string(path: &Path) -> io::Result<CString> {
+  #[cfg(unix)]
+  let bytes = {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+  };
+  #[cfg(not(unix))]
+  let bytes = path
+    .to_str()
+    .ok_or(io::ErrorKind::InvalidInput)?
+    .as_bytes()
+    .to_vec();
+  CString::new(bytes).map_err(|_| io::ErrorKind::InvalidInput.into())
+}
+
+/// See [Environment::create_core_save_dir].
+pub(crate) fn create_core_save_dir(
+  env: &impl Environment,
+  core_name: &CStr,
+  subdir: &Path,
+) -> io::Result<PathBuf> {
+  let save = to_path_buf(env.get_save_directory()).ok_or_else(|| {
+    io::Error::new(
+      io::ErrorKind::NotFound,
+      "the frontend has no save directory",
+    )
+  })?;
+  let relative = c_str_to_path(core_name).join(subdir);
+  let dir = save.join(&relative);
+  match env.get_vfs_interface(3) {
+    Some(vfs) => create_dir_all_with_vfs(&vfs, &save, &relative)?,
+    None => std::fs::create_dir_all(&dir)?,
+  }
+  Ok(dir)
+}
+
+/// Creates each missing directory of `relative` within `base`, which must
+/// already exist.
+fn create_dir_all_with_vfs(vfs: &VfsInterface, base: &Path, relative: &Path) -> io::Result<()> {
+  let mut dir = base.to_path_buf();
+  for component in relative.components() {
+    dir.push(component);
+    match vfs.mkdir(&path_to_c_string(&dir)?) {
+      Err(err) if err.kind() != io::ErrorKind::AlreadyExists => return Err(err),
+      _ => {}
+    }
+  }
+  Ok(())
+}
@@ -1,10 +1,13 @@
 use crate::convert::*;
 use crate::ffi::*;
 use crate::option::Option as _;
+use crate::retro::fs::Extensions;
+use crate::retro::hash::{Crc32, Hasher, Sha1};
 use c_utf8::CUtf8;
 use core::ffi::*;
 use core::fmt::{Debug, Formatter};
 use core::{ptr, slice};
+use std::io;
 use std::marker::PhantomData;
 
 /// Game data loaded from a file.
@@ -123,6 +126,99 @@ impl UnsafeFrom<retro_game_info> for GamePath<'_> {
   }
 }
 
+/// Rust interface for [`retro_game_info_ext`]: extended information about the
+/// content being loaded, queried with
+/// [`Environment::get_game_info_ext`](crate::retro::env::Environment::get_game_info_ext).
+///
+/// Frontends only provide it while the content is being loaded, i.e. from
+/// `load_game` or `load_game_special`, and it's only valid until they return.
+/// Copy anything that's needed afterwards.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct GameInfoExt<'a>(retro_game_info_ext, PhantomData<&'a ()>);
+
+impl<'a> GameInfoExt<'a> {
+  pub unsafe fn from_raw(info: retro_game_info_ext) -> Self {
+    Self(info, PhantomData)
+  }
+
+  /// The path to the content file. Always present unless
+  /// [GameInfoExt::file_in_archive] is true.
+  pub fn full_path(&self) -> Option<&'a CUtf8> {
+    unsafe { self.0.full_path.as_ref().unsafe_into() }
+  }
+
+  /// The absolute path to the archive containing the content, if any.
+  pub fn archive_path(&self) -> Option<&'a CUtf8> {
+    unsafe { self.0.archive_path.as_ref().unsafe_into() }
+  }
+
+  /// The path of the content file inside [GameInfoExt::archive_path], if any.
+  pub fn archive_file(&self) -> Option<&'a CUtf8> {
+    unsafe { self.0.archive_file.as_ref().unsafe_into() }
+  }
+
+  /// The directory containing the content file, or the archive containing it.
+  pub fn dir(&self) -> Option<&'a CUtf8> {
+    unsafe { self.0.dir.as_ref().unsafe_into() }
+  }
+
+  /// The display name of the content: its file name without the directory or
+  /// extension, e.g. `Super Game` for `/roms/Super Game.sfc`. Intended for
+  /// naming companion files like texture packs or save files, including for
+  /// content extracted from an archive, where [GameInfoExt::full_path] may be
+  /// missing. For archives, frontends may use either the name of the archive
+  /// or of the file inside it (RetroArch uses the archive).
+  pub fn name(&self) -> Option<&'a CStr> {
+    unsafe { self.0.name.as_ref().unsafe_into() }
+  }
+
+  /// The extension of the content file, in lowercase and without the leading
+  /// dot.
+  pub fn ext(&self) -> Option<&'a CStr> {
+    unsafe { self.0.ext.as_ref().unsafe_into() }
+  }
+
+  /// Implementation-specific metadata. See [GameInfo::meta].
+  pub fn meta(&self) -> Option<&'a CStr> {
+    unsafe { self.0.meta.as_ref().unsafe_into() }
+  }
+
+  /// The content loaded in memory, or [None] if the core asked for the content
+  /// to be passed by path.
+  pub fn data(&self) -> Option<&'a [u8]> {
+    if self.0.data.is_null() {
+      None
+    } else {
+      Some(unsafe { slice::from_raw_parts(self.0.data.cast(), self.0.size) })
+    }
+  }
+
+  /// Whether the content was extracted from an archive.
+  pub fn file_in_archive(&self) -> bool {
+    self.0.file_in_archive
+  }
+
+  /// Whether [GameInfoExt::data] stays valid until `deinit` returns, rather
+  /// than only while the content is being loaded.
+  pub fn persistent_data(&self) -> bool {
+    self.0.persistent_data
+  }
+}
+
+impl Debug for GameInfoExt<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "GameInfoExt({:?})", &self.0)
+  }
+}
+
+impl AsRef<retro_game_info_ext> for GameInfoExt<'_> {
+  fn as_ref(&self) -> &retro_game_info_ext {
+    &self.0
+  }
+}
+
+#[repr(C)]
 #[derive(Clone, Copy)]
 pub union GameInfo<'a> {
   info: retro_game_info,
@@ -172,6 +268,44 @@ impl<'a> GameInfo<'a> {
   pub fn is_path(&self) -> bool {
     !self.is_data()
   }
+
+  /// Implementation-specific metadata passed along by the frontend (e.g. how
+  /// compressed content was extracted), regardless of whether the content was
+  /// passed as data or by path.
+  ///
+  /// The crate hands the frontend's [`retro_game_info`] to the core as is, so
+  /// the metadata is never modified. It's only valid while the content is
+  /// being loaded; copy it if it's needed afterwards.
+  pub fn meta(&self) -> Option<&'a CStr> {
+    unsafe { self.info.meta.as_ref().unsafe_into() }
+  }
+
+  /// Returns a cursor over the content, e.g. to parse its header with
+  /// [std::io::Read], or [None] if it was passed by path (i.e. the core sets
+  /// `need_fullpath`), in which case the file at [GamePath::path] should be
+  /// opened instead.
+  pub fn cursor(&self) -> Option<io::Cursor<&'a [u8]>> {
+    self.as_data().map(|data| io::Cursor::new(data.data()))
+  }
+
+  /// Returns the CRC-32 of the content, which is a stable identifier for it
+  /// (e.g. to name per-game save or config files). If the content was passed
+  /// by path, the file is read in chunks rather than loaded all at once.
+  pub fn content_crc32(&self) -> io::Result<u32> {
+    self.content_hash::<Crc32>()
+  }
+
+  /// Returns the SHA-1 of the content. See [GameInfo::content_crc32].
+  pub fn content_sha1(&self) -> io::Result<[u8; 20]> {
+    self.content_hash::<Sha1>()
+  }
+
+  fn content_hash<H: Hasher>(&self) -> io::Result<H::Output> {
+    match self.as_ref() {
+      GameInfoKind::Data(data) => Ok(H::hash(data.data())),
+      GameInfoKind::Path(path) => H::read_from(std::fs::File::open(path.path().as_str())?),
+    }
+  }
 }
 
 impl Debug for GameInfo<'_> {
@@ -192,6 +326,7 @@ where
 /// Game info for `retro_load_game_special`.
 ///
 /// Unlike [`GameInfo`], a value of this type may not contain any data.
+#[repr(C)]
 #[derive(Clone, Copy)]
 pub union SpecialGameInfo<'a> {
   info: retro_game_info,
@@ -260,6 +395,11 @@ impl<'a> SpecialGameInfo<'a> {
   pub fn is_none(&self) -> bool {
     unsafe { self.info.data.is_null() && self.info.path.is_null() }
   }
+
+  /// Implementation-specific metadata. See [GameInfo::meta].
+  pub fn meta(&self) -> Option<&'a CStr> {
+    unsafe { self.info.meta.as_ref().unsafe_into() }
+  }
 }
 
 impl Debug for SpecialGameInfo<'_> {
@@ -357,3 +497,145 @@ impl From<GameType> for c_uint {
     game_type.into_inner()
   }
 }
+
+/// Rust interface for [`retro_subsystem_rom_info`]: one of the content files
+/// loaded by a subsystem. See [SubsystemInfo].
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+pub struct SubsystemRomInfo<'a>(retro_subsystem_rom_info, PhantomData<&'a ()>);
+
+impl<'a> SubsystemRomInfo<'a> {
+  /// Minimal constructor. Leaves [`SubsystemRomInfo::required`],
+  /// [`SubsystemRomInfo::need_fullpath`] and [`SubsystemRomInfo::block_extract`]
+  /// set to [false].
+  pub fn new<T>(desc: &'a T, valid_extensions: Extensions<'a>) -> Self
+  where
+    T: AsRef<CStr> + ?Sized,
+  {
+    Self(
+      retro_subsystem_rom_info {
+        desc: desc.as_ref().as_ptr(),
+        valid_extensions: valid_extensions.as_ptr(),
+        need_fullpath: false,
+        block_extract: false,
+        required: false,
+        memory: ptr::null(),
+        num_memory: 0,
+      },
+      PhantomData,
+    )
+  }
+
+  /// Makes the frontend refuse to load the subsystem without this content.
+  pub fn with_required(mut self) -> Self {
+    self.0.required = true;
+    self
+  }
+
+  /// Makes the frontend pass this content by path rather than loading it in
+  /// memory, e.g. for a CD image, independently of the other slots.
+  pub fn with_need_full_path(mut self) -> Self {
+    self.0.need_fullpath = true;
+    self
+  }
+
+  pub fn with_block_extract(mut self) -> Self {
+    self.0.block_extract = true;
+    self
+  }
+
+  pub fn desc(&self) -> &'a CStr {
+    unsafe { CStr::from_ptr(self.0.desc) }
+  }
+
+  pub fn valid_extensions(&self) -> Extensions<'a> {
+    Extensions::new(unsafe { CStr::from_ptr(self.0.valid_extensions) })
+  }
+
+  pub fn required(&self) -> bool {
+    self.0.required
+  }
+
+  pub fn need_fullpath(&self) -> bool {
+    self.0.need_fullpath
+  }
+
+  pub fn block_extract(&self) -> bool {
+    self.0.block_extract
+  }
+
+  pub fn into_inner(self) -> retro_subsystem_rom_info {
+    self.0
+  }
+}
+
+/// Rust interface for [`retro_subsystem_info`]: a kind of game loaded with
+/// `retro_load_game_special`, e.g. a Super Game Boy cartridge with a Game Boy
+/// ROM. Declared with
+/// [`SetEnvironment::set_subsystem_info`](crate::retro::env::SetEnvironment::set_subsystem_info).
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// // The frontend keeps pointers to the slots, so they have to live as long
+/// // as the core.
+/// let roms: &'static [_] = Box::leak(Box::new([
+///   SubsystemRomInfo::new(c_utf8!("Boot ROM"), ext!["bin"]).with_required(),
+///   SubsystemRomInfo::new(c_utf8!("CD image"), ext!["cue", "chd"])
+///     .with_required()
+///     .with_need_full_path(),
+/// ]));
+/// let subsystem = SubsystemInfo::new(c_utf8!("CD system"), c_utf8!("cd"), GameType::new(1), roms);
+/// assert!(!subsystem.roms()[0].need_fullpath());
+/// assert!(subsystem.roms()[1].need_fullpath());
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+pub struct SubsystemInfo<'a>(retro_subsystem_info, PhantomData<&'a ()>);
+
+impl<'a> SubsystemInfo<'a> {
+  /// `ident` should only contain lowercase letters. `game_type` is passed to
+  /// [`SpecialGameCore::load_game`](crate::retro::SpecialGameCore::load_game)
+  /// along with the content of each slot in `roms`, in order.
+  pub fn new<T, U>(
+    desc: &'a T,
+    ident: &'a U,
+    game_type: GameType,
+    roms: &'a [SubsystemRomInfo<'a>],
+  ) -> Self
+  where
+    T: AsRef<CStr> + ?Sized,
+    U: AsRef<CStr> + ?Sized,
+  {
+    Self(
+      retro_subsystem_info {
+        desc: desc.as_ref().as_ptr(),
+        ident: ident.as_ref().as_ptr(),
+        roms: roms.as_ptr().cast(),
+        num_roms: roms.len() as c_uint,
+        id: game_type.into_inner(),
+      },
+      PhantomData,
+    )
+  }
+
+  pub fn desc(&self) -> &'a CStr {
+    unsafe { CStr::from_ptr(self.0.desc) }
+  }
+
+  pub fn ident(&self) -> &'a CStr {
+    unsafe { CStr::from_ptr(self.0.ident) }
+  }
+
+  pub fn game_type(&self) -> GameType {
+    GameType::new(self.0.id)
+  }
+
+  pub fn roms(&self) -> &'a [SubsystemRomInfo<'a>] {
+    unsafe { slice::from_raw_parts(self.0.roms.cast(), self.0.num_roms as usize) }
+  }
+
+  pub fn into_inner(self) -> retro_subsystem_info {
+    self.0
+  }
+}
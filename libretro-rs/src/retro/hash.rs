@@ -0,0 +1,189 @@
+//! Streaming checksums for identifying loaded content, e.g. to name save files.
+//! See [`GameInfo::content_crc32`](crate::retro::game::GameInfo::content_crc32)
+//! and [`GameInfo::content_sha1`](crate::retro::game::GameInfo::content_sha1).
+
+use std::io::{self, Read};
+
+/// The size of the chunks read by [Hasher::read_from].
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A checksum that can be computed incrementally.
+pub trait Hasher: Default {
+  type Output;
+
+  /// Feeds `data` into the checksum.
+  fn update(&mut self, data: &[u8]);
+
+  /// Returns the checksum of all the data fed so far.
+  fn finish(self) -> Self::Output;
+
+  /// Returns the checksum of `data`.
+  fn hash(data: &[u8]) -> Self::Output {
+    let mut hasher = Self::default();
+    hasher.update(data);
+    hasher.finish()
+  }
+
+  /// Returns the checksum of everything read from `reader`, reading it in
+  /// chunks so large files don't have to be loaded into memory.
+  fn read_from(mut reader: impl Read) -> io::Result<Self::Output> {
+    let mut hasher = Self::default();
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+      match reader.read(&mut buf) {
+        Ok(0) => return Ok(hasher.finish()),
+        Ok(len) => hasher.update(&buf[..len]),
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+        Err(err) => return Err(err),
+      }
+    }
+  }
+}
+
+/// The CRC-32 (IEEE 802.3) checksum, as used by zip files and most ROM
+/// databases.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::retro::hash::{Crc32, Hasher};
+/// assert_eq!(Crc32::hash(b"123456789"), 0xCBF43926);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Crc32(u32);
+
+impl Default for Crc32 {
+  fn default() -> Self {
+    Self(!0)
+  }
+}
+
+impl Hasher for Crc32 {
+  type Output = u32;
+
+  fn update(&mut self, data: &[u8]) {
+    for &byte in data {
+      self.0 = CRC32_TABLE[((self.0 ^ byte as u32) & 0xFF) as usize] ^ (self.0 >> 8);
+    }
+  }
+
+  fn finish(self) -> u32 {
+    !self.0
+  }
+}
+
+const CRC32_TABLE: [u32; 256] = {
+  let mut table = [0; 256];
+  let mut i = 0;
+  while i < 256 {
+    let mut crc = i as u32;
+    let mut bit = 0;
+    while bit < 8 {
+      crc = if crc & 1 != 0 { 0xEDB88320 ^ (crc >> 1) } else { crc >> 1 };
+      bit += 1;
+    }
+    table[i] = crc;
+    i += 1;
+  }
+  table
+};
+
+/// The SHA-1 digest. It's no longer secure, but it's still what most content
+/// databases (including libretro's) use to identify games.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::retro::hash::{Hasher, Sha1};
+/// let digest = Sha1::hash(b"abc");
+/// assert_eq!(digest[..4], [0xA9, 0x99, 0x3E, 0x36]);
+/// assert_eq!(digest[16..], [0x9C, 0xD0, 0xD8, 0x9D]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sha1 {
+  state: [u32; 5],
+  block: [u8; 64],
+  block_len: usize,
+  total_len: u64,
+}
+
+impl Default for Sha1 {
+  fn default() -> Self {
+    Self {
+      state: [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0],
+      block: [0; 64],
+      block_len: 0,
+      total_len: 0,
+    }
+  }
+}
+
+impl Sha1 {
+  fn compress(&mut self) {
+    let mut w = [0u32; 80];
+    for (i, word) in self.block.chunks_exact(4).enumerate() {
+      w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for i in 16..80 {
+      w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = self.state;
+    for (i, &w) in w.iter().enumerate() {
+      let (f, k) = match i {
+        0..=19 => ((b & c) | (!b & d), 0x5A827999),
+        20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+        40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+        _ => (b ^ c ^ d, 0xCA62C1D6),
+      };
+      let temp = a
+        .rotate_left(5)
+        .wrapping_add(f)
+        .wrapping_add(e)
+        .wrapping_add(k)
+        .wrapping_add(w);
+      e = d;
+      d = c;
+      c = b.rotate_left(30);
+      b = a;
+      a = temp;
+    }
+
+    for (state, value) in self.state.iter_mut().zip([a, b, c, d, e]) {
+      *state = state.wrapping_add(value);
+    }
+  }
+}
+
+impl Hasher for Sha1 {
+  type Output = [u8; 20];
+
+  fn update(&mut self, mut data: &[u8]) {
+    self.total_len += data.len() as u64;
+    while !data.is_empty() {
+      let len = data.len().min(64 - self.block_len);
+      self.block[self.block_len..self.block_len + len].copy_from_slice(&data[..len]);
+      self.block_len += len;
+      data = &data[len..];
+      if self.block_len == 64 {
+        self.compress();
+        self.block_len = 0;
+      }
+    }
+  }
+
+  fn finish(mut self) -> [u8; 20] {
+    let bit_len = self.total_len.wrapping_mul(8);
+    // Pad with a single 1 bit, then zeros up to the last 8 bytes of a block,
+    // which hold the length of the message in bits.
+    let padding = if self.block_len < 56 { 56 - self.block_len } else { 120 - self.block_len };
+    let mut tail = [0; 72];
+    tail[0] = 0x80;
+    tail[padding..padding + 8].copy_from_slice(&bit_len.to_be_bytes());
+    self.update(&tail[..padding + 8]);
+
+    let mut digest = [0; 20];
+    for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+      bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+  }
+}
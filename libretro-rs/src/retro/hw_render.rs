@@ -1,8 +1,10 @@
 use libretro_rs_ffi::{
-  non_null_retro_hw_get_current_framebuffer_t, non_null_retro_hw_get_proc_address_t,
-  retro_hw_context_type, retro_hw_render_callback,
+  non_null_retro_environment_t, non_null_retro_hw_get_current_framebuffer_t,
+  non_null_retro_hw_get_proc_address_t, retro_hw_context_type, retro_hw_render_callback,
+  retro_hw_render_interface, retro_hw_render_interface_type,
 };
-use std::ffi::c_uint;
+use std::ffi::{c_uint, c_void, CStr, CString};
+use std::ptr::{self, NonNull};
 
 mod private {
   pub trait Sealed {}
@@ -14,19 +16,60 @@ pub trait RenderType: private::Sealed {}
 pub struct SoftwareRenderEnabled(pub(crate) ());
 
 #[derive(Debug, PartialEq, Eq, Hash)]
-pub struct GLRenderEnabled(pub(crate) ());
+pub struct GLRenderEnabled(pub(crate) Option<GLContextType>);
+
+impl GLRenderEnabled {
+  /// Returns the context type the frontend agreed to, which may be lower than
+  /// the one requested, or [None] if it isn't one of the [GLContextType]s.
+  pub fn negotiated(&self) -> Option<GLContextType> {
+    self.0
+  }
+}
 
 pub trait HWRenderEnabled: private::Sealed {}
 
 impl private::Sealed for GLRenderEnabled {}
 impl HWRenderEnabled for GLRenderEnabled {}
 
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct VulkanRenderEnabled(pub(crate) ());
+
+impl private::Sealed for VulkanRenderEnabled {}
+impl HWRenderEnabled for VulkanRenderEnabled {}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct GLContextCallbacks {
   pub get_proc_address_cb: non_null_retro_hw_get_proc_address_t,
   pub get_current_framebuffer_cb: non_null_retro_hw_get_current_framebuffer_t,
 }
 
+impl GLContextCallbacks {
+  /// Returns the id of the framebuffer object the core should render to.
+  ///
+  /// Only call this from `run` (or other callbacks invoked after the context has been reset), as
+  /// the frontend may change the framebuffer between frames. The result should be bound with
+  /// `glBindFramebuffer` every frame, rather than cached.
+  pub fn current_framebuffer(&self) -> u32 {
+    // GL object names are `GLuint`s, the frontend just returns them as a `uintptr_t`.
+    unsafe { (self.get_current_framebuffer_cb)() as u32 }
+  }
+
+  /// Returns the address of the GL function `name`, or [None] if the frontend
+  /// doesn't know it.
+  pub fn get_proc_address(&self, name: &CStr) -> Option<NonNull<c_void>> {
+    let function = unsafe { (self.get_proc_address_cb)(name.as_ptr()) }?;
+    NonNull::new(function as *mut c_void)
+  }
+
+  /// Like [GLContextCallbacks::get_proc_address], for loaders that pass the
+  /// name as a `&str` (e.g. `gl::load_with`). Returns [None] if `name`
+  /// contains a nul byte.
+  pub fn get_proc_address_str(&self, name: &str) -> Option<NonNull<c_void>> {
+    let name = CString::new(name).ok()?;
+    self.get_proc_address(&name)
+  }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum GLContextType {
   OpenGL2,
@@ -66,6 +109,30 @@ impl From<GLContextType> for GLVersion {
   }
 }
 
+impl TryFrom<GLVersion> for GLContextType {
+  type Error = ();
+
+  fn try_from(value: GLVersion) -> Result<Self, Self::Error> {
+    use retro_hw_context_type::*;
+    use GLContextType::*;
+    match value {
+      GLVersion(RETRO_HW_CONTEXT_OPENGL, _, _) => Ok(OpenGL2),
+      GLVersion(RETRO_HW_CONTEXT_OPENGL_CORE, 3, 2) => Ok(OpenGLCore3_2),
+      GLVersion(RETRO_HW_CONTEXT_OPENGL_CORE, 3, 3) => Ok(OpenGLCore3_3),
+      GLVersion(RETRO_HW_CONTEXT_OPENGL_CORE, 4, 0) => Ok(OpenGLCore4_0),
+      GLVersion(RETRO_HW_CONTEXT_OPENGL_CORE, 4, 1) => Ok(OpenGLCore4_1),
+      GLVersion(RETRO_HW_CONTEXT_OPENGL_CORE, 4, 2) => Ok(OpenGLCore4_2),
+      GLVersion(RETRO_HW_CONTEXT_OPENGL_CORE, 4, 3) => Ok(OpenGLCore4_3),
+      GLVersion(RETRO_HW_CONTEXT_OPENGL_CORE, 4, 4) => Ok(OpenGLCore4_4),
+      GLVersion(RETRO_HW_CONTEXT_OPENGL_CORE, 4, 5) => Ok(OpenGLCore4_5),
+      GLVersion(RETRO_HW_CONTEXT_OPENGL_CORE, 4, 6) => Ok(OpenGLCore4_6),
+      GLVersion(RETRO_HW_CONTEXT_OPENGLES2, _, _) => Ok(OpenGLES2),
+      GLVersion(RETRO_HW_CONTEXT_OPENGLES3, _, _) => Ok(OpenGLES3),
+      _ => Err(()),
+    }
+  }
+}
+
 #[repr(transparent)]
 pub struct GLOptions(retro_hw_render_callback);
 
@@ -122,6 +189,34 @@ impl GLOptions {
     self.0.debug_context = debug_context;
     self
   }
+
+  /// Returns the context type these options describe, or [None] if it isn't
+  /// one of the [GLContextType]s. For options read back from the frontend
+  /// after [`LoadGame::set_hw_render_gl`](crate::retro::env::LoadGame::set_hw_render_gl),
+  /// this is the context it agreed to, which cores can use to downgrade
+  /// gracefully; see also [GLRenderEnabled::negotiated].
+  pub fn get_negotiated(&self) -> Option<GLContextType> {
+    // Read the type as an integer, since frontends may write back values that
+    // aren't valid variants of the (Rust) enum.
+    let context_type = unsafe { *(&self.0.context_type as *const _ as *const c_uint) };
+    let context_type = [
+      retro_hw_context_type::RETRO_HW_CONTEXT_OPENGL,
+      retro_hw_context_type::RETRO_HW_CONTEXT_OPENGL_CORE,
+      retro_hw_context_type::RETRO_HW_CONTEXT_OPENGLES2,
+      retro_hw_context_type::RETRO_HW_CONTEXT_OPENGLES3,
+    ]
+    .into_iter()
+    .find(|&known| known as c_uint == context_type)?;
+    let major = u8::try_from(self.0.version_major).ok()?;
+    let minor = u8::try_from(self.0.version_minor).ok()?;
+    GLVersion(context_type, major, minor).try_into().ok()
+  }
+}
+
+impl From<retro_hw_render_callback> for GLOptions {
+  fn from(value: retro_hw_render_callback) -> Self {
+    GLOptions(value)
+  }
 }
 
 impl From<GLOptions> for retro_hw_render_callback {
@@ -129,3 +224,144 @@ impl From<GLOptions> for retro_hw_render_callback {
     value.0
   }
 }
+
+/// Options for a Vulkan context, set with
+/// [`LoadGame::set_hw_render_vulkan`](crate::retro::env::LoadGame::set_hw_render_vulkan).
+///
+/// The device itself is created by the frontend, optionally negotiated through
+/// [`LoadGame::set_hw_render_context_negotiation_interface`](crate::retro::env::LoadGame::set_hw_render_context_negotiation_interface).
+/// Once the context is reset, the core gets at it through
+/// [`Environment::get_hw_render_interface`](crate::retro::env::Environment::get_hw_render_interface).
+#[repr(transparent)]
+pub struct VulkanOptions(retro_hw_render_callback);
+
+impl VulkanOptions {
+  /// Requests a context supporting at least Vulkan `major.minor.patch`.
+  pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+    VulkanOptions(retro_hw_render_callback {
+      context_type: retro_hw_context_type::RETRO_HW_CONTEXT_VULKAN,
+      // The version is packed like `VK_MAKE_VERSION`.
+      version_major: (major << 22) | (minor << 12) | patch,
+      ..Default::default()
+    })
+  }
+
+  pub fn set_cache_context(mut self, cache_context: bool) -> Self {
+    self.0.cache_context = cache_context;
+    self
+  }
+
+  pub fn set_debug_context(mut self, debug_context: bool) -> Self {
+    self.0.debug_context = debug_context;
+    self
+  }
+}
+
+impl From<VulkanOptions> for retro_hw_render_callback {
+  fn from(value: VulkanOptions) -> Self {
+    value.0
+  }
+}
+
+/// The API specific rendering interface returned by
+/// [`Environment::get_hw_render_interface`](crate::retro::env::Environment::get_hw_render_interface).
+///
+/// Each variant holds the common header of the interface. The full struct is
+/// defined by the backend's header (e.g. `retro_hw_render_interface_vulkan` in
+/// `libretro_vulkan.h`), and the reference can be cast to it once
+/// [`retro_hw_render_interface::interface_version`] has been checked.
+///
+/// The interface is only valid between the core's `context_reset` and
+/// `context_destroy` callbacks; don't keep it around past the latter.
+#[derive(Clone, Copy, Debug)]
+pub enum HWRenderInterface<'a> {
+  Vulkan(&'a retro_hw_render_interface),
+  D3D9(&'a retro_hw_render_interface),
+  D3D10(&'a retro_hw_render_interface),
+  D3D11(&'a retro_hw_render_interface),
+  D3D12(&'a retro_hw_render_interface),
+  GSKitPS2(&'a retro_hw_render_interface),
+  /// An interface type this version of the bindings doesn't know about.
+  Unknown(c_uint, &'a retro_hw_render_interface),
+}
+
+impl<'a> HWRenderInterface<'a> {
+  /// Wraps the interface returned by the frontend.
+  ///
+  /// # Safety
+  /// `interface` must point to a valid [`retro_hw_render_interface`] that
+  /// outlives `'a`.
+  pub unsafe fn from_raw(interface: &'a retro_hw_render_interface) -> Self {
+    use retro_hw_render_interface_type::*;
+    // Read the type as an integer, since frontends may return values that
+    // aren't valid variants of the (Rust) enum.
+    let interface_type = *(interface as *const _ as *const c_uint);
+    match interface_type {
+      t if t == RETRO_HW_RENDER_INTERFACE_VULKAN as c_uint => Self::Vulkan(interface),
+      t if t == RETRO_HW_RENDER_INTERFACE_D3D9 as c_uint => Self::D3D9(interface),
+      t if t == RETRO_HW_RENDER_INTERFACE_D3D10 as c_uint => Self::D3D10(interface),
+      t if t == RETRO_HW_RENDER_INTERFACE_D3D11 as c_uint => Self::D3D11(interface),
+      t if t == RETRO_HW_RENDER_INTERFACE_D3D12 as c_uint => Self::D3D12(interface),
+      t if t == RETRO_HW_RENDER_INTERFACE_GSKIT_PS2 as c_uint => Self::GSKitPS2(interface),
+      t => Self::Unknown(t, interface),
+    }
+  }
+
+  /// Returns the common header of the interface.
+  pub fn as_raw(&self) -> &'a retro_hw_render_interface {
+    match *self {
+      Self::Vulkan(interface)
+      | Self::D3D9(interface)
+      | Self::D3D10(interface)
+      | Self::D3D11(interface)
+      | Self::D3D12(interface)
+      | Self::GSKitPS2(interface)
+      | Self::Unknown(_, interface) => interface,
+    }
+  }
+
+  /// Returns the version of the backend specific interface.
+  pub fn version(&self) -> c_uint {
+    self.as_raw().interface_version
+  }
+}
+
+/// The callback returned by
+/// [`Environment::get_clear_all_thread_waits`](crate::retro::env::Environment::get_clear_all_thread_waits).
+///
+/// Threaded cores (typically ones rendering with GL on their own thread) can deadlock on
+/// teardown if the render thread is blocked waiting on the frontend while the main thread waits
+/// for it to exit. Calling [`ClearThreadWaits::clear`] before joining such threads releases the
+/// frontend's waits, and [`ClearThreadWaits::restore`] re-enables them afterwards.
+///
+/// Both methods must be called from the thread that called into the core (e.g. from
+/// `on_unload_game` or `context_destroy`), not from the core's own threads.
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct ClearThreadWaits(non_null_retro_environment_t);
+
+impl ClearThreadWaits {
+  pub fn new(cb: non_null_retro_environment_t) -> Self {
+    Self(cb)
+  }
+
+  pub fn into_inner(self) -> non_null_retro_environment_t {
+    self.0
+  }
+
+  /// Releases any threads blocked waiting on the frontend.
+  pub fn clear(&self) -> bool {
+    unsafe { (self.0)(1, ptr::null_mut()) }
+  }
+
+  /// Re-enables the waits released by [`ClearThreadWaits::clear`].
+  pub fn restore(&self) -> bool {
+    unsafe { (self.0)(0, ptr::null_mut()) }
+  }
+}
+
+impl From<non_null_retro_environment_t> for ClearThreadWaits {
+  fn from(cb: non_null_retro_environment_t) -> Self {
+    Self(cb)
+  }
+}
@@ -0,0 +1,313 @@
+//! Reading input through abstract actions instead of raw buttons, and shaping
+//! analog input.
+
+use crate::retro::device::{DevicePort, JoypadButton};
+use crate::retro::Callbacks;
+use core::ffi::c_uint;
+
+/// The joypad buttons held on each port at a point in time, so input can be
+/// read repeatedly during a frame without querying the frontend each time.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let mut snapshot = InputSnapshot::new();
+/// snapshot.set_pressed(DevicePort::new(1), JoypadButton::A, true);
+/// assert!(snapshot.is_pressed(DevicePort::new(1), JoypadButton::A));
+/// assert!(!snapshot.is_pressed(DevicePort::new(0), JoypadButton::A));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct InputSnapshot {
+  /// A bitmask of the pressed buttons for each port.
+  joypads: Vec<u16>,
+}
+
+impl InputSnapshot {
+  /// Creates a snapshot where no buttons are pressed.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Reads the joypad state of the first `ports` ports from the frontend, with
+  /// [`Callbacks::joypad_buttons`]. This should be called after
+  /// [`Callbacks::poll_inputs`].
+  pub fn capture(callbacks: &impl Callbacks, ports: c_uint) -> Self {
+    let joypads = (0..ports)
+      .map(|port| callbacks.joypad_buttons(DevicePort::new(port)))
+      .collect();
+    Self { joypads }
+  }
+
+  /// Returns true if `button` was held on `port`.
+  pub fn is_pressed(&self, port: DevicePort, button: JoypadButton) -> bool {
+    let mask = self.joypads.get(port.into_inner() as usize).copied();
+    mask.is_some_and(|mask| mask & bit(button) != 0)
+  }
+
+  /// Overrides the state of `button` on `port`, e.g. to replay recorded input.
+  pub fn set_pressed(&mut self, port: DevicePort, button: JoypadButton, pressed: bool) {
+    let port = port.into_inner() as usize;
+    if self.joypads.len() <= port {
+      self.joypads.resize(port + 1, 0);
+    }
+    match pressed {
+      true => self.joypads[port] |= bit(button),
+      false => self.joypads[port] &= !bit(button),
+    }
+  }
+}
+
+fn bit(button: JoypadButton) -> u16 {
+  1 << (c_uint::from(button) & 0xF)
+}
+
+/// Associates abstract actions (e.g. "jump" or "fire") with joypad buttons, so
+/// game logic doesn't depend on raw button ids and bindings can be remapped.
+/// An action can be bound to several buttons, and is active if any of them is
+/// pressed.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+///
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum Action {
+///   Jump,
+///   Fire,
+/// }
+///
+/// let player1 = DevicePort::new(0);
+/// let mut map = InputMap::new();
+/// map.bind(Action::Jump, player1, JoypadButton::B);
+/// map.bind(Action::Fire, player1, JoypadButton::A);
+///
+/// let mut snapshot = InputSnapshot::new();
+/// snapshot.set_pressed(player1, JoypadButton::B, true);
+/// assert!(map.is_active(Action::Jump, &snapshot));
+/// assert!(!map.is_active(Action::Fire, &snapshot));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InputMap<Action> {
+  bindings: Vec<(Action, DevicePort, JoypadButton)>,
+}
+
+impl<Action> Default for InputMap<Action> {
+  fn default() -> Self {
+    Self { bindings: Vec::new() }
+  }
+}
+
+impl<Action: PartialEq> InputMap<Action> {
+  /// Creates a map without any bindings.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Binds `action` to `button` on `port`, in addition to its existing bindings.
+  pub fn bind(&mut self, action: Action, port: DevicePort, button: JoypadButton) {
+    let binding = (action, port, button);
+    if !self.bindings.contains(&binding) {
+      self.bindings.push(binding);
+    }
+  }
+
+  /// Removes all of the bindings of `action`.
+  pub fn unbind(&mut self, action: &Action) {
+    self.bindings.retain(|(bound, _, _)| bound != action);
+  }
+
+  /// Replaces the bindings of `action` with `button` on `port`.
+  pub fn rebind(&mut self, action: Action, port: DevicePort, button: JoypadButton) {
+    self.unbind(&action);
+    self.bindings.push((action, port, button));
+  }
+
+  /// Returns the buttons `action` is bound to.
+  pub fn bindings<'a>(
+    &'a self,
+    action: &'a Action,
+  ) -> impl Iterator<Item = (DevicePort, JoypadButton)> + 'a {
+    self
+      .bindings
+      .iter()
+      .filter(move |(bound, _, _)| bound == action)
+      .map(|&(_, port, button)| (port, button))
+  }
+
+  /// Returns true if any of the buttons bound to `action` is pressed in
+  /// `snapshot`.
+  pub fn is_active(&self, action: Action, snapshot: &InputSnapshot) -> bool {
+    self
+      .bindings(&action)
+      .any(|(port, button)| snapshot.is_pressed(port, button))
+  }
+}
+
+/// Turbo (a.k.a. autofire) for a held button: while the button is held, it
+/// reads as pressed for the first `pressed_frames` frames of every `period`
+/// frames, and as released for the rest.
+///
+/// The phase is keyed on a frame counter kept by the core, so every turbo
+/// button pulses in sync.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let turbo = TurboButton::new(4);
+/// let pulses: Vec<_> = (0..8).map(|frame| turbo.is_pressed(true, frame)).collect();
+/// assert_eq!(pulses, [true, true, false, false, true, true, false, false]);
+/// assert!(!turbo.is_pressed(false, 0));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TurboButton {
+  period: u32,
+  pressed_frames: u32,
+}
+
+impl TurboButton {
+  /// Creates a turbo button that's pressed for half of every `period` frames
+  /// (rounded up). A period of 0 is treated as 1, i.e. no turbo.
+  pub fn new(period: u32) -> Self {
+    let period = period.max(1);
+    Self::with_duty_cycle(period, period.div_ceil(2))
+  }
+
+  /// Creates a turbo button that's pressed for `pressed_frames` of every
+  /// `period` frames. A period of 0 is treated as 1, and `pressed_frames` is
+  /// clamped to the period.
+  pub fn with_duty_cycle(period: u32, pressed_frames: u32) -> Self {
+    let period = period.max(1);
+    Self { period, pressed_frames: pressed_frames.min(period) }
+  }
+
+  /// Returns the number of frames in a press and release cycle.
+  pub fn period(&self) -> u32 {
+    self.period
+  }
+
+  /// Returns the number of frames per cycle the button reads as pressed.
+  pub fn pressed_frames(&self) -> u32 {
+    self.pressed_frames
+  }
+
+  /// Returns true if a button that is `held` should read as pressed on
+  /// `frame`.
+  pub fn is_pressed(&self, held: bool, frame: u64) -> bool {
+    held && frame % u64::from(self.period) < u64::from(self.pressed_frames)
+  }
+
+  /// Returns true if `button` on `port` is held in `snapshot` and should read
+  /// as pressed on `frame`.
+  pub fn is_pressed_in(
+    &self,
+    snapshot: &InputSnapshot,
+    port: DevicePort,
+    button: JoypadButton,
+    frame: u64,
+  ) -> bool {
+    self.is_pressed(snapshot.is_pressed(port, button), frame)
+  }
+}
+
+/// Shapes raw analog values (sticks and triggers, as read from
+/// `RETRO_DEVICE_ANALOG`) by ignoring small movements around the center and
+/// applying a response curve to the rest of the range.
+///
+/// Values inside the dead zone become 0, and the remaining range is rescaled so
+/// the output still spans `-i16::MAX..=i16::MAX` without a jump at the edge of
+/// the dead zone. The dead zone is usually configured through a core option,
+/// see [DeadZone::from_percent].
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let dead_zone = DeadZone::new(0.25);
+/// assert_eq!(dead_zone.apply(4000), 0);
+/// assert_eq!(dead_zone.apply(i16::MIN), -i16::MAX);
+/// assert_eq!(dead_zone.apply_radial(20000, 20000), (18943, 18943));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeadZone {
+  size: f32,
+  exponent: f32,
+}
+
+impl Default for DeadZone {
+  fn default() -> Self {
+    Self::new(0.0)
+  }
+}
+
+impl DeadZone {
+  /// Creates a linear dead zone covering `size` of the range, from 0.0 (none)
+  /// to 1.0 (everything). Out of range sizes are clamped.
+  pub fn new(size: f32) -> Self {
+    Self {
+      size: match size.is_nan() {
+        true => 0.0,
+        false => size.clamp(0.0, 1.0),
+      },
+      exponent: 1.0,
+    }
+  }
+
+  /// Creates a dead zone from a percentage, e.g. the value of an
+  /// [`IntOption`](crate::retro::core_options::IntOption) ranging from 0 to 100.
+  pub fn from_percent(percent: i64) -> Self {
+    Self::new(percent.clamp(0, 100) as f32 / 100.0)
+  }
+
+  /// Sets the exponent of the response curve outside of the dead zone. 1.0 is
+  /// linear; larger values give finer control near the center. Non-positive
+  /// exponents are treated as 1.0.
+  pub fn curve(mut self, exponent: f32) -> Self {
+    self.exponent = match exponent > 0.0 {
+      true => exponent,
+      false => 1.0,
+    };
+    self
+  }
+
+  /// Returns the size of the dead zone, from 0.0 to 1.0.
+  pub fn size(&self) -> f32 {
+    self.size
+  }
+
+  /// Shapes a single axis or trigger value.
+  pub fn apply(&self, value: i16) -> i16 {
+    let magnitude = self.shape(normalize(value).abs());
+    denormalize(magnitude.copysign(value as f32))
+  }
+
+  /// Shapes the X and Y axes of a stick together, so the dead zone is a circle
+  /// instead of a cross, and diagonals aren't affected more than the axes.
+  /// The stick's direction is kept, and its distance from the center is
+  /// clamped to the edge of the range.
+  pub fn apply_radial(&self, x: i16, y: i16) -> (i16, i16) {
+    let (x, y) = (normalize(x), normalize(y));
+    let magnitude = x.hypot(y);
+    if magnitude == 0.0 {
+      return (0, 0);
+    }
+    let scale = self.shape(magnitude.min(1.0)) / magnitude;
+    (denormalize(x * scale), denormalize(y * scale))
+  }
+
+  /// Shapes a normalized magnitude, from 0.0 to 1.0.
+  fn shape(&self, magnitude: f32) -> f32 {
+    if magnitude <= self.size {
+      return 0.0;
+    }
+    ((magnitude - self.size) / (1.0 - self.size)).powf(self.exponent)
+  }
+}
+
+/// Maps a raw analog value to `-1.0..=1.0`.
+fn normalize(value: i16) -> f32 {
+  (value as f32 / i16::MAX as f32).clamp(-1.0, 1.0)
+}
+
+/// Maps a value in `-1.0..=1.0` back to the raw analog range.
+fn denormalize(value: f32) -> i16 {
+  (value.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16
+}
@@ -1,10 +1,40 @@
 use crate::ffi::retro_log_level::*;
 use crate::ffi::*;
+use crate::retro::str::StackCUtf8;
 use c_utf8::*;
+use core::fmt::{self, Display};
+
+/// The severity of a log message, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RetroLogLevel {
+  #[default]
+  Debug,
+  Info,
+  Warn,
+  Error,
+}
+
+impl From<RetroLogLevel> for retro_log_level {
+  fn from(level: RetroLogLevel) -> Self {
+    match level {
+      RetroLogLevel::Debug => RETRO_LOG_DEBUG,
+      RetroLogLevel::Info => RETRO_LOG_INFO,
+      RetroLogLevel::Warn => RETRO_LOG_WARN,
+      RetroLogLevel::Error => RETRO_LOG_ERROR,
+    }
+  }
+}
 
 /// Trait for types that provide safe access to [`retro_log_printf_t`].
 pub trait LogInterface {
   fn log(&mut self, level: retro_log_level, message: &CUtf8);
+
+  /// Returns true if messages at `level` are logged. Loggers that filter
+  /// messages override this so [Logger::log_fmt] can skip formatting the ones
+  /// they would drop.
+  fn is_enabled(&self, _level: RetroLogLevel) -> bool {
+    true
+  }
 }
 
 /// Trait for types that offer idiomatic logging methods.
@@ -17,6 +47,13 @@ pub trait Logger {
   fn warn(&mut self, message: &CUtf8);
   /// Logs an error message.
   fn error(&mut self, message: &CUtf8);
+  /// Logs a formatted message at `level`. The message is only formatted if
+  /// the logger doesn't drop it, and short messages are formatted into a
+  /// [StackCUtf8] so they don't allocate. Messages containing a nul byte
+  /// can't be passed to the frontend, and are dropped. Usually called through
+  /// the [debug!](crate::debug!), [info!](crate::info!),
+  /// [warn!](crate::warn!) and [error!](crate::error!) macros.
+  fn log_fmt(&mut self, level: RetroLogLevel, args: fmt::Arguments);
 }
 
 impl<T> Logger for T
@@ -38,6 +75,70 @@ where
   fn error(&mut self, message: &CUtf8) {
     self.log(RETRO_LOG_ERROR, message);
   }
+
+  fn log_fmt(&mut self, level: RetroLogLevel, args: fmt::Arguments) {
+    if self.is_enabled(level) {
+      let message = StackCUtf8::<256>::from_fmt(args);
+      if !message.truncated() {
+        self.log(level.into(), &message);
+      }
+    }
+  }
+}
+
+/// Logs a formatted debugging message through a [Logger], e.g. the libretro
+/// log interface.
+///
+/// The first argument is the logger, either by value or as a mutable
+/// reference, and the rest are [format_args] arguments. Nothing is formatted
+/// if the logger drops the message, and messages containing a nul byte are
+/// dropped instead of panicking. See [Logger::log_fmt].
+///
+/// # Examples
+/// ```
+/// use libretro_rs::debug;
+/// use libretro_rs::prelude::*;
+/// let mut logger = FallbackLogger::new(Some(NullLogger));
+/// debug!(logger, "frame {}", 42);
+/// ```
+#[macro_export]
+macro_rules! debug {
+  ($logger:expr, $($arg:tt)+) => {
+    $crate::__log!($logger, Debug, $($arg)+)
+  };
+}
+
+/// Logs a formatted informational message through a [Logger]. See [debug!](crate::debug!).
+#[macro_export]
+macro_rules! info {
+  ($logger:expr, $($arg:tt)+) => {
+    $crate::__log!($logger, Info, $($arg)+)
+  };
+}
+
+/// Logs a formatted warning message through a [Logger]. See [debug!](crate::debug!).
+#[macro_export]
+macro_rules! warn {
+  ($logger:expr, $($arg:tt)+) => {
+    $crate::__log!($logger, Warn, $($arg)+)
+  };
+}
+
+/// Logs a formatted error message through a [Logger]. See [debug!](crate::debug!).
+#[macro_export]
+macro_rules! error {
+  ($logger:expr, $($arg:tt)+) => {
+    $crate::__log!($logger, Error, $($arg)+)
+  };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log {
+  ($logger:expr, $level:ident, $($arg:tt)+) => {{
+    use $crate::retro::log::Logger as _;
+    $logger.log_fmt($crate::retro::log::RetroLogLevel::$level, format_args!($($arg)+))
+  }};
 }
 
 pub type RetroPrintF = unsafe extern "C" fn(level: retro_log_level, fmt: *const crate::ffi::c_char, ...);
@@ -78,10 +179,14 @@ impl LogInterface for StderrLogger {
 }
 
 /// A [Logger] that uses [StderrLogger] if no [PlatformLogger] is available.
+///
+/// Messages below the minimum level (by default [RetroLogLevel::Debug], i.e.
+/// everything is logged) are dropped.
 #[derive(Clone, Copy)]
 pub struct FallbackLogger<T> {
   callback: fn(Option<&mut T>, retro_log_level, &CUtf8),
   logger: Option<T>,
+  min_level: RetroLogLevel,
 }
 
 impl<T> FallbackLogger<T>
@@ -93,13 +198,45 @@ where
       Some(_) => Self {
         callback: log_to_logger,
         logger,
+        min_level: RetroLogLevel::Debug,
       },
       None => Self {
         callback: log_to_stderr,
         logger,
+        min_level: RetroLogLevel::Debug,
       },
     }
   }
+
+  /// Sets the minimum level of the messages that are logged.
+  pub fn with_min_level(mut self, level: RetroLogLevel) -> Self {
+    self.min_level = level;
+    self
+  }
+
+  /// Returns the minimum level of the messages that are logged.
+  pub fn min_level(&self) -> RetroLogLevel {
+    self.min_level
+  }
+
+  /// Sets the minimum level of the messages that are logged.
+  pub fn set_min_level(&mut self, level: RetroLogLevel) {
+    self.min_level = level;
+  }
+
+  /// Logs `message` at `level` through [Logger::log_fmt], so it's only
+  /// formatted if `level` is enabled: dropped messages (e.g. `format_args!`
+  /// with expensive arguments) cost nothing beyond the level check.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let mut logger = FallbackLogger::new(Some(NullLogger)).with_min_level(RetroLogLevel::Info);
+  /// logger.log_display(RetroLogLevel::Debug, format_args!("frame {}", 42)); // Never formatted.
+  /// ```
+  pub fn log_display(&mut self, level: RetroLogLevel, message: impl Display) {
+    self.log_fmt(level, format_args!("{message}"))
+  }
 }
 
 impl<T> From<Option<T>> for FallbackLogger<T>
@@ -116,7 +253,13 @@ where
   T: LogInterface,
 {
   fn log(&mut self, level: retro_log_level, message: &CUtf8) {
-    (self.callback)(self.logger.as_mut(), level, message);
+    if level as u32 >= retro_log_level::from(self.min_level) as u32 {
+      (self.callback)(self.logger.as_mut(), level, message);
+    }
+  }
+
+  fn is_enabled(&self, level: RetroLogLevel) -> bool {
+    level >= self.min_level
   }
 }
 
@@ -1,7 +1,12 @@
+use crate::ffi::*;
+use crate::retro::hash::{Crc32, Hasher};
 use ::core::convert::Infallible;
 use ::core::fmt::{Debug, Display, Formatter};
 use std::error::Error;
-use std::ffi::c_uint;
+use std::ffi::{c_uint, CStr};
+use std::marker::PhantomData;
+use std::ops::{BitOr, BitOrAssign};
+use std::ptr::{self, NonNull};
 
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -29,6 +34,383 @@ impl From<MemoryType> for c_uint {
   }
 }
 
+/// Flags describing limitations of a core's save states. Set with
+/// [`Init::set_serialization_quirks`](crate::retro::env::Init::set_serialization_quirks).
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let quirks = SerializationQuirks::INCOMPLETE | SerializationQuirks::ENDIAN_DEPENDENT;
+/// assert!(quirks.contains(SerializationQuirks::INCOMPLETE));
+/// assert!(!quirks.contains(SerializationQuirks::SINGLE_SESSION));
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SerializationQuirks(u64);
+
+impl SerializationQuirks {
+  /// No quirks; save states work everywhere.
+  pub const NONE: Self = Self(0);
+  /// Save states are usable, but not reliable enough for frame-sensitive
+  /// features such as netplay or rerecording.
+  pub const INCOMPLETE: Self = Self(RETRO_SERIALIZATION_QUIRK_INCOMPLETE as u64);
+  /// Serialization fails until the core has spent some time initializing.
+  pub const MUST_INITIALIZE: Self = Self(RETRO_SERIALIZATION_QUIRK_MUST_INITIALIZE as u64);
+  /// The size of a save state may change within a session.
+  pub const CORE_VARIABLE_SIZE: Self = Self(RETRO_SERIALIZATION_QUIRK_CORE_VARIABLE_SIZE as u64);
+  /// Set by the frontend if it supports variable-sized states.
+  pub const FRONT_VARIABLE_SIZE: Self = Self(RETRO_SERIALIZATION_QUIRK_FRONT_VARIABLE_SIZE as u64);
+  /// Save states can only be loaded during the session they were saved in.
+  pub const SINGLE_SESSION: Self = Self(RETRO_SERIALIZATION_QUIRK_SINGLE_SESSION as u64);
+  /// Save states can't be loaded on a system of a different endianness.
+  pub const ENDIAN_DEPENDENT: Self = Self(RETRO_SERIALIZATION_QUIRK_ENDIAN_DEPENDENT as u64);
+  /// Save states can't be loaded on a different platform, e.g. because they
+  /// depend on the word size.
+  pub const PLATFORM_DEPENDENT: Self = Self(RETRO_SERIALIZATION_QUIRK_PLATFORM_DEPENDENT as u64);
+
+  pub fn new(bits: u64) -> Self {
+    Self(bits)
+  }
+
+  pub fn into_inner(self) -> u64 {
+    self.0
+  }
+
+  /// Returns true if all of the quirks in `other` are set.
+  pub fn contains(self, other: Self) -> bool {
+    self.0 & other.0 == other.0
+  }
+}
+
+impl BitOr for SerializationQuirks {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self {
+    Self(self.0 | rhs.0)
+  }
+}
+
+impl BitOrAssign for SerializationQuirks {
+  fn bitor_assign(&mut self, rhs: Self) {
+    self.0 |= rhs.0
+  }
+}
+
+impl From<u64> for SerializationQuirks {
+  fn from(bits: u64) -> Self {
+    Self(bits)
+  }
+}
+
+impl From<SerializationQuirks> for u64 {
+  fn from(quirks: SerializationQuirks) -> Self {
+    quirks.into_inner()
+  }
+}
+
+/// Detects changes to a memory region between frames, so tools that poll
+/// memory every frame (e.g. achievement or cheat engines) can skip regions that
+/// haven't changed.
+///
+/// Call [MemoryWatch::update] once per frame with the region's current
+/// contents, e.g. from [`Core::get_memory_region`](crate::retro::Core::get_memory_region).
+/// Only a checksum of the region is kept, so watching a region doesn't copy it.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let mut ram = [0u8; 16];
+/// let mut watch = MemoryWatch::new(StandardMemoryType::SystemRam.into());
+/// assert!(watch.update(&ram)); // The first update is always dirty.
+/// assert!(!watch.update(&ram));
+/// ram[3] = 1;
+/// assert!(watch.update(&ram));
+/// assert!(watch.is_dirty());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MemoryWatch {
+  id: MemoryType,
+  checksum: Option<(usize, u32)>,
+  dirty: bool,
+}
+
+impl MemoryWatch {
+  /// Creates a watch for the region identified by `id`. The region is
+  /// considered dirty until the first call to [MemoryWatch::update].
+  pub fn new(id: MemoryType) -> Self {
+    Self { id, checksum: None, dirty: true }
+  }
+
+  /// Returns the memory region being watched.
+  pub fn id(&self) -> MemoryType {
+    self.id
+  }
+
+  /// Checksums `region` and returns true if it changed since the previous
+  /// update (including changes in size).
+  pub fn update(&mut self, region: &[u8]) -> bool {
+    let checksum = Some((region.len(), Crc32::hash(region)));
+    self.dirty = checksum != self.checksum;
+    self.checksum = checksum;
+    self.dirty
+  }
+
+  /// Returns the result of the latest [MemoryWatch::update].
+  pub fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
+  /// Forgets the last checksum, so the next update is always dirty (e.g.
+  /// after loading a save state).
+  pub fn reset(&mut self) {
+    self.checksum = None;
+    self.dirty = true;
+  }
+}
+
+/// Flags describing the memory of a [MemoryDescriptor].
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let flags = MemoryDescriptorFlags::SYSTEM_RAM | MemoryDescriptorFlags::BIG_ENDIAN;
+/// assert!(flags.contains(MemoryDescriptorFlags::SYSTEM_RAM));
+/// assert!(!flags.contains(MemoryDescriptorFlags::CONST));
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MemoryDescriptorFlags(u64);
+
+impl MemoryDescriptorFlags {
+  pub const NONE: Self = Self(0);
+  /// The memory never changes once the game is loaded, e.g. ROM.
+  pub const CONST: Self = Self(RETRO_MEMDESC_CONST as u64);
+  /// The memory holds big-endian data, rather than little-endian.
+  pub const BIG_ENDIAN: Self = Self(RETRO_MEMDESC_BIGENDIAN as u64);
+  /// The memory is the main RAM of the emulated system.
+  pub const SYSTEM_RAM: Self = Self(RETRO_MEMDESC_SYSTEM_RAM as u64);
+  /// The memory is battery-backed save RAM, usually on a cartridge.
+  pub const SAVE_RAM: Self = Self(RETRO_MEMDESC_SAVE_RAM as u64);
+  /// The memory is video RAM.
+  pub const VIDEO_RAM: Self = Self(RETRO_MEMDESC_VIDEO_RAM as u64);
+  /// Accesses are aligned to their own size, or 2 bytes if that's smaller.
+  pub const ALIGN_2: Self = Self(RETRO_MEMDESC_ALIGN_2 as u64);
+  pub const ALIGN_4: Self = Self(RETRO_MEMDESC_ALIGN_4 as u64);
+  pub const ALIGN_8: Self = Self(RETRO_MEMDESC_ALIGN_8 as u64);
+  /// The memory is always accessed at least 2 bytes at a time.
+  pub const MIN_SIZE_2: Self = Self(RETRO_MEMDESC_MINSIZE_2 as u64);
+  pub const MIN_SIZE_4: Self = Self(RETRO_MEMDESC_MINSIZE_4 as u64);
+  pub const MIN_SIZE_8: Self = Self(RETRO_MEMDESC_MINSIZE_8 as u64);
+
+  pub fn new(bits: u64) -> Self {
+    Self(bits)
+  }
+
+  pub fn into_inner(self) -> u64 {
+    self.0
+  }
+
+  /// Returns true if all of the flags in `other` are set.
+  pub fn contains(self, other: Self) -> bool {
+    self.0 & other.0 == other.0
+  }
+}
+
+impl BitOr for MemoryDescriptorFlags {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self {
+    Self(self.0 | rhs.0)
+  }
+}
+
+impl BitOrAssign for MemoryDescriptorFlags {
+  fn bitor_assign(&mut self, rhs: Self) {
+    self.0 |= rhs.0
+  }
+}
+
+impl From<u64> for MemoryDescriptorFlags {
+  fn from(bits: u64) -> Self {
+    Self(bits)
+  }
+}
+
+impl From<MemoryDescriptorFlags> for u64 {
+  fn from(flags: MemoryDescriptorFlags) -> Self {
+    flags.into_inner()
+  }
+}
+
+/// Rust interface for [`retro_memory_descriptor`]: maps a region of the
+/// core's memory into the emulated address space, so achievement and cheat
+/// engines can find it. Registered with
+/// [`Init::set_memory_maps`](crate::retro::env::Init::set_memory_maps).
+///
+/// To go from an emulated address to a byte of the memory, the frontend
+/// subtracts [MemoryDescriptor::start], removes the
+/// [disconnected](MemoryDescriptor::with_disconnect) bits, wraps the result to
+/// [MemoryDescriptor::len] and adds [MemoryDescriptor::offset].
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// # use std::ptr::NonNull;
+/// let mut wram = vec![0u8; 0x2000];
+/// let ptr = NonNull::new(wram.as_mut_ptr()).unwrap();
+/// // 8 KiB of work RAM at 0xC000, mirrored at 0xE000.
+/// let descriptor = MemoryDescriptor::new(ptr, wram.len(), 0xC000)
+///   .with_flags(MemoryDescriptorFlags::SYSTEM_RAM)
+///   .with_select(0xC000)
+///   .with_addrspace(c_utf8!("WRAM"));
+/// assert_eq!(descriptor.len(), 0x2000);
+/// ```
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct MemoryDescriptor<'a>(retro_memory_descriptor, PhantomData<&'a CStr>);
+
+impl<'a> MemoryDescriptor<'a> {
+  /// Maps the `len` bytes at `memory` at address `start` of the emulated
+  /// address space.
+  ///
+  /// The memory isn't borrowed, so several descriptors can map the same
+  /// memory, e.g. for mirrors or banks. It must be valid for reads and writes
+  /// of `len` bytes for as long as the descriptor is registered; see
+  /// [`Init::set_memory_maps`](crate::retro::env::Init::set_memory_maps).
+  pub fn new(memory: NonNull<u8>, len: usize, start: usize) -> Self {
+    Self(
+      retro_memory_descriptor {
+        flags: 0,
+        ptr: memory.as_ptr().cast(),
+        offset: 0,
+        start,
+        select: 0,
+        disconnect: 0,
+        len,
+        addrspace: ptr::null(),
+      },
+      PhantomData,
+    )
+  }
+
+  /// Describes `len` bytes at address `start` that aren't backed by memory,
+  /// such as hardware registers or open bus. Such regions shouldn't have any
+  /// flags.
+  pub fn unmapped(start: usize, len: usize) -> Self {
+    Self(
+      retro_memory_descriptor {
+        flags: 0,
+        ptr: ptr::null_mut(),
+        offset: 0,
+        start,
+        select: 0,
+        disconnect: 0,
+        len,
+        addrspace: ptr::null(),
+      },
+      PhantomData,
+    )
+  }
+
+  pub unsafe fn from_raw(descriptor: retro_memory_descriptor) -> Self {
+    Self(descriptor, PhantomData)
+  }
+
+  pub fn with_flags(mut self, flags: MemoryDescriptorFlags) -> Self {
+    self.0.flags = flags.into_inner();
+    self
+  }
+
+  /// Skips the first `offset` bytes of the memory, shortening the mapping
+  /// accordingly. When several descriptors map parts of the same memory, they
+  /// should all be created from the same pointer and use this, rather than
+  /// pointers into the middle of it.
+  ///
+  /// # Panics
+  /// Panics if `offset` is larger than the memory.
+  pub fn with_offset(mut self, offset: usize) -> Self {
+    let size = self.0.offset + self.0.len;
+    assert!(offset <= size, "offset {offset} is outside of the memory");
+    self.0.offset = offset;
+    self.0.len = size - offset;
+    self
+  }
+
+  /// Sets the address bits that must match [MemoryDescriptor::start] for this
+  /// mapping to apply; addresses claimed by an earlier descriptor are skipped.
+  /// If 0, the default, the mapping covers [MemoryDescriptor::len] bytes from
+  /// `start`, and the length must be a power of two.
+  pub fn with_select(mut self, select: usize) -> Self {
+    self.0.select = select;
+    self
+  }
+
+  /// Sets the address bits that aren't connected to the memory, i.e. that are
+  /// ignored when addressing it.
+  pub fn with_disconnect(mut self, disconnect: usize) -> Self {
+    self.0.disconnect = disconnect;
+    self
+  }
+
+  /// Names the address space of the mapping. Names may only contain
+  /// `a-zA-Z0-9_-`, should be at most 8 characters long, and can't be another
+  /// address space's name followed by hexadecimal digits.
+  pub fn with_addrspace<T>(mut self, addrspace: &'a T) -> Self
+  where
+    T: AsRef<CStr> + ?Sized,
+  {
+    self.0.addrspace = addrspace.as_ref().as_ptr();
+    self
+  }
+
+  pub fn flags(&self) -> MemoryDescriptorFlags {
+    MemoryDescriptorFlags::new(self.0.flags)
+  }
+
+  pub fn start(&self) -> usize {
+    self.0.start
+  }
+
+  pub fn offset(&self) -> usize {
+    self.0.offset
+  }
+
+  pub fn select(&self) -> usize {
+    self.0.select
+  }
+
+  pub fn disconnect(&self) -> usize {
+    self.0.disconnect
+  }
+
+  /// The number of bytes mapped. For unmapped regions, 0 means the region is
+  /// only bounded by [MemoryDescriptor::select].
+  #[allow(clippy::len_without_is_empty)]
+  pub fn len(&self) -> usize {
+    self.0.len
+  }
+
+  /// Returns true if the descriptor isn't backed by memory. See
+  /// [MemoryDescriptor::unmapped].
+  pub fn is_unmapped(&self) -> bool {
+    self.0.ptr.is_null()
+  }
+
+  pub fn addrspace(&self) -> Option<&'a CStr> {
+    unsafe {
+      self
+        .0
+        .addrspace
+        .as_ref()
+        .map(|addrspace| CStr::from_ptr(addrspace))
+    }
+  }
+
+  pub fn as_raw(&self) -> &retro_memory_descriptor {
+    &self.0
+  }
+}
+
 trait TypeId: Sized {
   fn into_discriminant(self) -> u8;
   fn from_discriminant(id: u8) -> Option<Self>;
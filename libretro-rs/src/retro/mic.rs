@@ -0,0 +1,111 @@
+//! Microphone input through the interface returned by
+//! [`Environment::get_microphone_interface`](crate::retro::env::Environment::get_microphone_interface).
+//!
+//! Microphones are opened with [MicrophoneInterface::open_mic], and start out
+//! inactive: the frontend only records while they're
+//! [active](Microphone::set_active). Samples are mono, signed 16-bit PCM at
+//! the microphone's [rate](Microphone::rate).
+
+use crate::ffi::*;
+use core::ffi::*;
+use std::io;
+
+/// The frontend's microphone interface.
+///
+/// The frontend fills in the interface's functions, which remain valid for the
+/// lifetime of the core, so this can be kept for as long as the core needs to
+/// open microphones.
+#[derive(Clone, Copy, Debug)]
+pub struct MicrophoneInterface(retro_microphone_interface);
+
+impl MicrophoneInterface {
+  /// Wraps an interface filled in by the frontend.
+  ///
+  /// # Safety
+  /// The functions of `iface` must remain valid for the lifetime of the core.
+  pub unsafe fn from_raw(iface: retro_microphone_interface) -> Self {
+    Self(iface)
+  }
+
+  /// Returns the version of the interface implemented by the frontend.
+  pub fn version(&self) -> c_uint {
+    self.0.interface_version
+  }
+
+  /// Opens a microphone recording at `rate` Hz, or at the frontend's default
+  /// rate if [None]. The frontend may pick another rate; see
+  /// [Microphone::rate].
+  pub fn open_mic(&self, rate: Option<c_uint>) -> io::Result<Microphone> {
+    let open_mic = require(self.0.open_mic)?;
+    let params = rate.map(|rate| retro_microphone_params { rate });
+    let params = params
+      .as_ref()
+      .map_or(core::ptr::null(), |params| params as *const _);
+    let handle = unsafe { open_mic(params) };
+    match handle.is_null() {
+      true => Err(io::Error::other("no microphone available")),
+      false => Ok(Microphone { iface: *self, handle }),
+    }
+  }
+}
+
+fn require<F>(function: Option<F>) -> io::Result<F> {
+  function.ok_or_else(|| io::ErrorKind::Unsupported.into())
+}
+
+/// A microphone opened through the [MicrophoneInterface]. Closed when dropped.
+#[derive(Debug)]
+pub struct Microphone {
+  iface: MicrophoneInterface,
+  handle: *mut retro_microphone_t,
+}
+
+impl Microphone {
+  /// Returns the sample rate the microphone records at, in Hz.
+  pub fn rate(&self) -> io::Result<c_uint> {
+    let get_params = require(self.iface.0.get_params)?;
+    let mut params = retro_microphone_params::default();
+    match unsafe { get_params(self.handle, &mut params) } {
+      true => Ok(params.rate),
+      false => Err(io::Error::other("microphone get_params failed")),
+    }
+  }
+
+  /// Starts or stops recording. Samples recorded while the microphone is
+  /// inactive are discarded.
+  pub fn set_active(&mut self, active: bool) -> io::Result<()> {
+    let set_mic_state = require(self.iface.0.set_mic_state)?;
+    match unsafe { set_mic_state(self.handle, active) } {
+      true => Ok(()),
+      false => Err(io::Error::other("microphone set_mic_state failed")),
+    }
+  }
+
+  /// Returns true if the microphone is recording.
+  pub fn is_active(&self) -> bool {
+    match self.iface.0.get_mic_state {
+      Some(get_mic_state) => unsafe { get_mic_state(self.handle) },
+      None => false,
+    }
+  }
+
+  /// Reads recorded samples into `samples`, returning how many were read.
+  /// Frontends may block until `samples` is full, so it should hold no more
+  /// than a frame's worth of samples. Fails if the microphone is inactive.
+  pub fn read(&mut self, samples: &mut [i16]) -> io::Result<usize> {
+    let read_mic = require(self.iface.0.read_mic)?;
+    let len = unsafe { read_mic(self.handle, samples.as_mut_ptr(), samples.len()) };
+    usize::try_from(len).map_err(|_| io::Error::other("microphone read_mic failed"))
+  }
+
+  /// Closes the microphone. Equivalent to dropping it.
+  pub fn close(self) {}
+}
+
+impl Drop for Microphone {
+  fn drop(&mut self) {
+    if let Some(close_mic) = self.iface.0.close_mic {
+      unsafe { close_mic(self.handle) };
+    }
+  }
+}
@@ -1,14 +1,26 @@
+pub mod audio;
 pub mod av;
+pub mod core_options;
 mod cores;
 pub use cores::*;
+mod crash;
+pub mod deferred;
 pub mod device;
 pub mod env;
 pub mod error;
 pub mod fs;
 pub mod game;
+pub mod hash;
 pub mod hw_render;
+pub mod input;
 pub mod log;
 pub mod mem;
+pub mod mic;
 pub mod pixel;
+#[cfg(feature = "screenshot")]
+pub mod screenshot;
+pub mod state;
 pub mod str;
+pub mod timing;
+pub mod vfs;
 pub mod video;
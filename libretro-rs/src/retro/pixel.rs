@@ -4,12 +4,62 @@ pub mod format {
   #[derive(Debug, PartialEq, Eq, Hash)]
   pub struct ActiveFormat<P>(pub(crate) PhantomData<P>);
 
-  mod private {
-    pub trait Sealed {}
+  pub(crate) mod private {
+    pub trait Sealed: Sized {
+      /// Converts a pixel, stored in native byte order, to 8-bit RGB.
+      fn to_rgb888(bytes: &[u8]) -> [u8; 3];
+
+      /// Converts the pixel to 8-bit RGB, replicating the high bits of
+      /// narrower channels into the low bits.
+      fn into_rgb888(self) -> [u8; 3];
+
+      /// Converts 8-bit RGB to a pixel, truncating channels that are narrower.
+      fn from_rgb888(rgb: [u8; 3]) -> Self;
+    }
   }
 
   pub trait Format: private::Sealed {}
 
+  /// A pixel format negotiated at runtime, for cores that can't pick one at
+  /// compile time. Each variant holds the [ActiveFormat] returned when the
+  /// format was set, so it can be used to upload an
+  /// [`AnyFrameBuffer`](crate::retro::video::AnyFrameBuffer).
+  #[derive(Debug, PartialEq, Eq, Hash)]
+  pub enum AnyFormat {
+    ORGB1555(ActiveFormat<ORGB1555>),
+    RGB565(ActiveFormat<RGB565>),
+    XRGB8888(ActiveFormat<XRGB8888>),
+  }
+
+  impl AnyFormat {
+    /// Returns the size of a single pixel in this format, in bytes.
+    pub fn bytes_per_pixel(&self) -> usize {
+      match self {
+        Self::ORGB1555(_) => std::mem::size_of::<ORGB1555>(),
+        Self::RGB565(_) => std::mem::size_of::<RGB565>(),
+        Self::XRGB8888(_) => std::mem::size_of::<XRGB8888>(),
+      }
+    }
+  }
+
+  impl From<ActiveFormat<ORGB1555>> for AnyFormat {
+    fn from(format: ActiveFormat<ORGB1555>) -> Self {
+      Self::ORGB1555(format)
+    }
+  }
+
+  impl From<ActiveFormat<RGB565>> for AnyFormat {
+    fn from(format: ActiveFormat<RGB565>) -> Self {
+      Self::RGB565(format)
+    }
+  }
+
+  impl From<ActiveFormat<XRGB8888>> for AnyFormat {
+    fn from(format: ActiveFormat<XRGB8888>) -> Self {
+      Self::XRGB8888(format)
+    }
+  }
+
   pub use orgb1555::*;
   mod orgb1555 {
     use super::private::Sealed;
@@ -28,7 +78,26 @@ pub mod format {
       b: u5,
     }
 
-    impl Sealed for ORGB1555 {}
+    impl Sealed for ORGB1555 {
+      fn to_rgb888(bytes: &[u8]) -> [u8; 3] {
+        let pixel = u16::from_ne_bytes([bytes[0], bytes[1]]);
+        let expand = |c: u16| ((c << 3) | (c >> 2)) as u8;
+        [
+          expand(pixel >> 10 & 0x1F),
+          expand(pixel >> 5 & 0x1F),
+          expand(pixel & 0x1F),
+        ]
+      }
+
+      fn into_rgb888(self) -> [u8; 3] {
+        Self::to_rgb888(&self.raw_value().to_ne_bytes())
+      }
+
+      fn from_rgb888([r, g, b]: [u8; 3]) -> Self {
+        let (r, g, b) = (u16::from(r >> 3), u16::from(g >> 3), u16::from(b >> 3));
+        Self::new_with_raw_value(r << 10 | g << 5 | b)
+      }
+    }
     impl Format for ORGB1555 {}
   }
 
@@ -51,10 +120,105 @@ pub mod format {
       b: u8,
     }
 
-    impl Sealed for XRGB8888 {}
+    impl Sealed for XRGB8888 {
+      fn to_rgb888(bytes: &[u8]) -> [u8; 3] {
+        let [_, r, g, b] =
+          u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).to_be_bytes();
+        [r, g, b]
+      }
+
+      fn into_rgb888(self) -> [u8; 3] {
+        [self.r(), self.g(), self.b()]
+      }
+
+      fn from_rgb888([r, g, b]: [u8; 3]) -> Self {
+        Self::new_with_raw_value(u32::from_be_bytes([0, r, g, b]))
+      }
+    }
     impl Format for XRGB8888 {}
   }
 
+  pub use rgb888::*;
+  mod rgb888 {
+    use super::private::Sealed;
+    use crate::retro::pixel::format::Format;
+
+    /// A tightly-packed 24-bit pixel, stored as red, green and blue bytes.
+    ///
+    /// `libretro` frontends don't accept this format, so frame buffers of
+    /// [RGB888] pixels can't be uploaded; they're meant for exchanging frames
+    /// with tools that expect packed RGB (e.g. image encoders). Since a pixel
+    /// is 3 bytes, a row's pitch is `width * 3`, which isn't necessarily
+    /// aligned to 4 bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// use libretro_rs::prelude::*;
+    /// let orange = RGB888::new_with_raw_value(0x00FF8000);
+    /// assert_eq!((orange.r(), orange.g(), orange.b()), (0xFF, 0x80, 0x00));
+    /// assert_eq!(orange.with_b(0x40).raw_value(), 0x00FF8040);
+    /// assert_eq!(std::mem::size_of::<RGB888>(), 3);
+    /// ```
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct RGB888([u8; 3]);
+
+    impl RGB888 {
+      pub const DEFAULT: Self = Self([0; 3]);
+
+      /// Creates a pixel from a `0x00RRGGBB` value. The top byte is ignored.
+      pub const fn new_with_raw_value(value: u32) -> Self {
+        let [_, r, g, b] = value.to_be_bytes();
+        Self([r, g, b])
+      }
+
+      /// Returns the pixel as a `0x00RRGGBB` value.
+      pub const fn raw_value(&self) -> u32 {
+        let [r, g, b] = self.0;
+        u32::from_be_bytes([0, r, g, b])
+      }
+
+      pub const fn r(&self) -> u8 {
+        self.0[0]
+      }
+
+      pub const fn g(&self) -> u8 {
+        self.0[1]
+      }
+
+      pub const fn b(&self) -> u8 {
+        self.0[2]
+      }
+
+      pub const fn with_r(self, r: u8) -> Self {
+        Self([r, self.0[1], self.0[2]])
+      }
+
+      pub const fn with_g(self, g: u8) -> Self {
+        Self([self.0[0], g, self.0[2]])
+      }
+
+      pub const fn with_b(self, b: u8) -> Self {
+        Self([self.0[0], self.0[1], b])
+      }
+    }
+
+    impl Sealed for RGB888 {
+      fn to_rgb888(bytes: &[u8]) -> [u8; 3] {
+        [bytes[0], bytes[1], bytes[2]]
+      }
+
+      fn into_rgb888(self) -> [u8; 3] {
+        self.0
+      }
+
+      fn from_rgb888(rgb: [u8; 3]) -> Self {
+        Self(rgb)
+      }
+    }
+    impl Format for RGB888 {}
+  }
+
   pub use rgb565::*;
   mod rgb565 {
     use super::private::Sealed;
@@ -73,7 +237,59 @@ pub mod format {
       b: u5,
     }
 
-    impl Sealed for RGB565 {}
+    impl Sealed for RGB565 {
+      fn to_rgb888(bytes: &[u8]) -> [u8; 3] {
+        let pixel = u16::from_ne_bytes([bytes[0], bytes[1]]);
+        let r = (pixel >> 11 & 0x1F) as u8;
+        let g = (pixel >> 5 & 0x3F) as u8;
+        let b = (pixel & 0x1F) as u8;
+        [
+          (r << 3) | (r >> 2),
+          (g << 2) | (g >> 4),
+          (b << 3) | (b >> 2),
+        ]
+      }
+
+      fn into_rgb888(self) -> [u8; 3] {
+        Self::to_rgb888(&self.raw_value().to_ne_bytes())
+      }
+
+      fn from_rgb888([r, g, b]: [u8; 3]) -> Self {
+        let (r, g, b) = (u16::from(r >> 3), u16::from(g >> 2), u16::from(b >> 3));
+        Self::new_with_raw_value(r << 11 | g << 5 | b)
+      }
+    }
     impl Format for RGB565 {}
   }
 }
+
+/// Converts a pixel to another [Format], e.g. for cores that render in one
+/// format but upload in whichever one the frontend accepted.
+///
+/// Channels are converted through 8-bit RGB: narrower channels are expanded by
+/// replicating their high bits into the low bits (so full intensity stays full
+/// intensity), and wider channels are truncated. Converting to a wider format
+/// and back is lossless, and converting to the same format is the identity,
+/// except that the unused bits of [XRGB8888](format::XRGB8888) are cleared.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let white = RGB565::new_with_raw_value(0xFFFF);
+/// let xrgb: XRGB8888 = white.convert();
+/// assert_eq!(xrgb.raw_value(), 0x00FFFFFF);
+/// assert_eq!(ConvertPixel::<RGB565>::convert(xrgb), white);
+/// ```
+pub trait ConvertPixel<To: format::Format> {
+  fn convert(self) -> To;
+}
+
+impl<From, To> ConvertPixel<To> for From
+where
+  From: format::Format,
+  To: format::Format,
+{
+  fn convert(self) -> To {
+    To::from_rgb888(self.into_rgb888())
+  }
+}
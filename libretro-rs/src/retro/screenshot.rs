@@ -0,0 +1,124 @@
+//! Lets tools and tests grab PNG screenshots of the last software-rendered
+//! frame through the `get_proc_address` interface, without going through a
+//! frontend's own screenshot feature.
+//!
+//! When the `screenshot` feature is enabled, every frame passed to
+//! [`Callbacks::upload_video_frame`](crate::retro::Callbacks::upload_video_frame)
+//! is copied into a buffer that's reused between frames. It's only converted
+//! to RGB when a screenshot is taken.
+//!
+//! The interface isn't registered automatically, since a core can only have
+//! one. Register [PROC_ADDRESS_INTERFACE] from
+//! [`Core::set_environment`](crate::retro::Core::set_environment) with
+//! [`SetEnvironment::set_proc_address_callback`](crate::retro::env::SetEnvironment::set_proc_address_callback),
+//! or call [get_proc_address] from the core's own interface. Looking up
+//! [SCREENSHOT_SYMBOL] returns a [ScreenshotFn], which writes the last frame
+//! to the given path.
+
+use crate::ffi::*;
+use crate::retro::pixel::format::private::Sealed;
+use crate::retro::video::FrameBuffer;
+use core::ffi::*;
+use std::mem;
+use std::sync::Mutex;
+
+/// The name of the screenshot function, for `get_proc_address`.
+pub const SCREENSHOT_SYMBOL: &str = "libretro_rs_screenshot";
+
+/// The signature of the screenshot function. Writes the last uploaded frame
+/// to `path` as a PNG, and returns true if successful.
+pub type ScreenshotFn = unsafe extern "C" fn(path: *const c_char) -> bool;
+
+/// A `get_proc_address` interface that only knows [SCREENSHOT_SYMBOL].
+pub const PROC_ADDRESS_INTERFACE: retro_get_proc_address_interface =
+  retro_get_proc_address_interface { get_proc_address: Some(get_proc_address) };
+
+/// The last uploaded frame, with its rows tightly packed.
+struct Frame {
+  width: u32,
+  height: u32,
+  pixel_size: usize,
+  to_rgb888: fn(&[u8]) -> [u8; 3],
+  data: Vec<u8>,
+}
+
+impl Frame {
+  fn rgb888(&self) -> Vec<u8> {
+    self
+      .data
+      .chunks_exact(self.pixel_size)
+      .flat_map(self.to_rgb888)
+      .collect()
+  }
+}
+
+static LAST_FRAME: Mutex<Option<Frame>> = Mutex::new(None);
+
+/// Copies `framebuffer` for the next screenshot, reusing the previous frame's
+/// allocation.
+pub(crate) fn capture<F: FrameBuffer>(framebuffer: &F) {
+  let Ok(mut frame) = LAST_FRAME.lock() else {
+    return;
+  };
+  let frame = frame.get_or_insert_with(|| Frame {
+    width: 0,
+    height: 0,
+    pixel_size: 0,
+    to_rgb888: F::Pixel::to_rgb888,
+    data: Vec::new(),
+  });
+  let pixel_size = mem::size_of::<F::Pixel>();
+  let row_size = framebuffer.width() as usize * pixel_size;
+  frame.width = framebuffer.width() as u32;
+  frame.height = framebuffer.height() as u32;
+  frame.pixel_size = pixel_size;
+  frame.to_rgb888 = F::Pixel::to_rgb888;
+  frame.data.clear();
+  for row in framebuffer
+    .data()
+    .chunks(framebuffer.pitch())
+    .take(framebuffer.height() as usize)
+  {
+    frame.data.extend_from_slice(&row[..row_size]);
+  }
+}
+
+/// Returns the screenshot function if `sym` is [SCREENSHOT_SYMBOL], and
+/// `None` otherwise. Cores with their own `get_proc_address` interface can
+/// fall back to this one.
+///
+/// # Safety
+/// `sym` must be null or point to a nul-terminated string.
+pub unsafe extern "C" fn get_proc_address(sym: *const c_char) -> retro_proc_address_t {
+  if sym.is_null() || CStr::from_ptr(sym).to_bytes() != SCREENSHOT_SYMBOL.as_bytes() {
+    return None;
+  }
+  let screenshot: ScreenshotFn = screenshot;
+  Some(mem::transmute::<ScreenshotFn, unsafe extern "C" fn()>(
+    screenshot,
+  ))
+}
+
+unsafe extern "C" fn screenshot(path: *const c_char) -> bool {
+  let Some(path) = path
+    .as_ref()
+    .and_then(|path| CStr::from_ptr(path).to_str().ok())
+  else {
+    return false;
+  };
+  let Ok(frame) = LAST_FRAME.lock() else {
+    return false;
+  };
+  let Some(frame) = frame.as_ref() else {
+    return false;
+  };
+  image::save_buffer_with_format(
+    path,
+    &frame.rgb888(),
+    frame.width,
+    frame.height,
+    image::ColorType::Rgb8,
+    image::ImageFormat::Png,
+  )
+  .is_ok()
+}
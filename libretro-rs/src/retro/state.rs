@@ -0,0 +1,237 @@
+//! Writing save states directly into the frontend's buffer.
+//!
+//! The frontend passes a single buffer of [`SaveStateCore::serialize_size`]
+//! bytes to `serialize`, so the usual approach of serializing into a `Vec` and
+//! copying it over holds two copies of the state at once. [StateWriter] writes
+//! into the frontend's buffer instead, and can also run without a buffer to
+//! compute the exact size of the state with the same code that saves it.
+//!
+//! # Examples
+//! ```
+//! use libretro_rs::prelude::*;
+//!
+//! struct Machine {
+//!   ram: Vec<u8>,
+//!   pc: u16,
+//! }
+//!
+//! impl Machine {
+//!   fn save(&self, state: &mut StateWriter) -> Result<(), CoreError> {
+//!     state.write_u16(self.pc)?;
+//!     state.write_bytes(&self.ram)
+//!   }
+//!
+//!   fn load(&mut self, state: &mut StateReader) -> Result<(), CoreError> {
+//!     self.pc = state.read_u16()?;
+//!     state.read_bytes(&mut self.ram)
+//!   }
+//! }
+//!
+//! let mut machine = Machine { ram: vec![7; 64], pc: 0x200 };
+//! let size = StateWriter::measure(|state| machine.save(state)).unwrap();
+//! assert_eq!(size, 66);
+//!
+//! let mut buffer = vec![0; size];
+//! let mut state = StateWriter::new(&mut buffer);
+//! machine.save(&mut state).unwrap();
+//! assert_eq!(state.finish(), 66);
+//!
+//! machine.pc = 0;
+//! machine.load(&mut StateReader::new(&buffer)).unwrap();
+//! assert_eq!(machine.pc, 0x200);
+//! ```
+//!
+//! [`SaveStateCore::serialize_size`]: crate::retro::SaveStateCore::serialize_size
+
+use crate::retro::error::CoreError;
+use std::io;
+
+/// Writes a save state into a buffer, or measures its size. See the
+/// [module documentation](self).
+#[derive(Debug)]
+pub struct StateWriter<'a> {
+  /// The buffer being written, or [None] when measuring.
+  buffer: Option<&'a mut [u8]>,
+  position: usize,
+}
+
+impl<'a> StateWriter<'a> {
+  /// Creates a writer that writes into `buffer`, from the start.
+  pub fn new(buffer: &'a mut [u8]) -> Self {
+    Self { buffer: Some(buffer), position: 0 }
+  }
+
+  /// Creates a writer that only counts the bytes written to it, without
+  /// storing them.
+  pub fn measuring() -> Self {
+    Self { buffer: None, position: 0 }
+  }
+
+  /// Returns the number of bytes `save` writes, e.g. to implement
+  /// [`SaveStateCore::serialize_size`](crate::retro::SaveStateCore::serialize_size)
+  /// with the same function that implements `serialize`.
+  pub fn measure(
+    save: impl FnOnce(&mut StateWriter<'_>) -> Result<(), CoreError>,
+  ) -> Result<usize, CoreError> {
+    let mut writer = StateWriter::measuring();
+    save(&mut writer)?;
+    Ok(writer.position)
+  }
+
+  /// Returns true if the writer only counts the bytes written to it.
+  pub fn is_measuring(&self) -> bool {
+    self.buffer.is_none()
+  }
+
+  /// Returns the number of bytes written so far.
+  pub fn position(&self) -> usize {
+    self.position
+  }
+
+  /// Returns the number of bytes that can still be written, or [None] when
+  /// measuring.
+  pub fn remaining(&self) -> Option<usize> {
+    let buffer = self.buffer.as_deref()?;
+    Some(buffer.len() - self.position)
+  }
+
+  /// Writes `bytes`, or fails without writing anything if they don't fit in
+  /// the buffer.
+  pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), CoreError> {
+    let end = self.position + bytes.len();
+    if let Some(buffer) = self.buffer.as_deref_mut() {
+      let target = buffer
+        .get_mut(self.position..end)
+        .ok_or_else(|| CoreError::with_source(io::Error::from(io::ErrorKind::WriteZero)))?;
+      target.copy_from_slice(bytes);
+    }
+    self.position = end;
+    Ok(())
+  }
+
+  pub fn write_bool(&mut self, value: bool) -> Result<(), CoreError> {
+    self.write_u8(value as u8)
+  }
+
+  pub fn write_u8(&mut self, value: u8) -> Result<(), CoreError> {
+    self.write_bytes(&[value])
+  }
+
+  /// Writes `value` in little-endian order, like the other integer methods.
+  pub fn write_u16(&mut self, value: u16) -> Result<(), CoreError> {
+    self.write_bytes(&value.to_le_bytes())
+  }
+
+  pub fn write_u32(&mut self, value: u32) -> Result<(), CoreError> {
+    self.write_bytes(&value.to_le_bytes())
+  }
+
+  pub fn write_u64(&mut self, value: u64) -> Result<(), CoreError> {
+    self.write_bytes(&value.to_le_bytes())
+  }
+
+  /// Zeroes the rest of the buffer, so states saved by identical cores are
+  /// byte for byte identical, and returns the number of bytes written.
+  pub fn finish(self) -> usize {
+    if let Some(buffer) = self.buffer {
+      buffer[self.position..].fill(0);
+    }
+    self.position
+  }
+}
+
+/// Writes as much as fits in the buffer, so [io::Write::write_all] fails with
+/// [io::ErrorKind::WriteZero] once it's full.
+impl io::Write for StateWriter<'_> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let len = self
+      .remaining()
+      .map_or(buf.len(), |remaining| buf.len().min(remaining));
+    self
+      .write_bytes(&buf[..len])
+      .map_err(|_| io::ErrorKind::WriteZero)?;
+    Ok(len)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+/// Reads a save state written by a [StateWriter].
+#[derive(Clone, Debug)]
+pub struct StateReader<'a> {
+  buffer: &'a [u8],
+  position: usize,
+}
+
+impl<'a> StateReader<'a> {
+  /// Creates a reader that reads `buffer`, from the start.
+  pub fn new(buffer: &'a [u8]) -> Self {
+    Self { buffer, position: 0 }
+  }
+
+  /// Returns the number of bytes read so far.
+  pub fn position(&self) -> usize {
+    self.position
+  }
+
+  /// Returns the number of bytes left to read.
+  pub fn remaining(&self) -> usize {
+    self.buffer.len() - self.position
+  }
+
+  /// Fills `bytes`, or fails without reading anything if the state is too
+  /// short.
+  pub fn read_bytes(&mut self, bytes: &mut [u8]) -> Result<(), CoreError> {
+    bytes.copy_from_slice(self.take(bytes.len())?);
+    Ok(())
+  }
+
+  /// Returns the next `len` bytes without copying them.
+  pub fn take(&mut self, len: usize) -> Result<&'a [u8], CoreError> {
+    let end = self.position + len;
+    let bytes = self
+      .buffer
+      .get(self.position..end)
+      .ok_or_else(|| CoreError::with_source(io::Error::from(io::ErrorKind::UnexpectedEof)))?;
+    self.position = end;
+    Ok(bytes)
+  }
+
+  pub fn read_bool(&mut self) -> Result<bool, CoreError> {
+    Ok(self.read_u8()? != 0)
+  }
+
+  pub fn read_u8(&mut self) -> Result<u8, CoreError> {
+    Ok(self.read_array::<1>()?[0])
+  }
+
+  /// Reads a little-endian value, like the other integer methods.
+  pub fn read_u16(&mut self) -> Result<u16, CoreError> {
+    self.read_array().map(u16::from_le_bytes)
+  }
+
+  pub fn read_u32(&mut self) -> Result<u32, CoreError> {
+    self.read_array().map(u32::from_le_bytes)
+  }
+
+  pub fn read_u64(&mut self) -> Result<u64, CoreError> {
+    self.read_array().map(u64::from_le_bytes)
+  }
+
+  fn read_array<const N: usize>(&mut self) -> Result<[u8; N], CoreError> {
+    let mut bytes = [0; N];
+    self.read_bytes(&mut bytes)?;
+    Ok(bytes)
+  }
+}
+
+impl io::Read for StateReader<'_> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let len = buf.len().min(self.remaining());
+    buf[..len].copy_from_slice(&self.buffer[self.position..self.position + len]);
+    self.position += len;
+    Ok(len)
+  }
+}
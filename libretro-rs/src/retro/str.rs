@@ -1,4 +1,7 @@
-use c_utf8::CUtf8;
+use c_utf8::{CUtf8, CUtf8Buf};
+use core::fmt;
+use core::ops::Deref;
+use core::str;
 use std::ffi::CStr;
 
 pub trait IntoCUtf8<'a> {
@@ -20,3 +23,126 @@ impl<'a> IntoStr<'a> for Option<&'a CStr> {
     self.and_then(|x| x.to_str().ok())
   }
 }
+
+/// A [CUtf8] string formatted into an inline buffer of `N` bytes (including
+/// the nul terminator), which only allocates if the formatted string doesn't
+/// fit. This avoids allocating for short runtime messages, e.g. when logging
+/// every frame.
+///
+/// C strings can't contain nul bytes, so the string is truncated at the first
+/// one that's written.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::prelude::*;
+/// let message = StackCUtf8::<32>::from_fmt(format_args!("frame {}", 42));
+/// assert_eq!(message.as_str(), "frame 42");
+/// assert!(!message.spilled());
+/// ```
+#[derive(Clone)]
+pub struct StackCUtf8<const N: usize> {
+  buf: [u8; N],
+  len: usize,
+  heap: Option<CUtf8Buf>,
+  truncated: bool,
+}
+
+impl<const N: usize> StackCUtf8<N> {
+  /// Creates an empty string.
+  pub const fn new() -> Self {
+    Self { buf: [0; N], len: 0, heap: None, truncated: false }
+  }
+
+  /// Formats `args` into a new string.
+  pub fn from_fmt(args: fmt::Arguments) -> Self {
+    let mut s = Self::new();
+    // Writing to a StackCUtf8 never fails.
+    let _ = fmt::Write::write_fmt(&mut s, args);
+    s
+  }
+
+  /// Returns true if a nul byte was written, so the string was truncated at
+  /// it.
+  pub fn truncated(&self) -> bool {
+    self.truncated
+  }
+
+  /// Returns true if the string didn't fit in the inline buffer, and was moved
+  /// to the heap.
+  pub fn spilled(&self) -> bool {
+    self.heap.is_some()
+  }
+
+  /// Returns the formatted string.
+  pub fn as_c_utf8(&self) -> &CUtf8 {
+    match &self.heap {
+      Some(heap) => heap,
+      // The buffer holds UTF-8 written by `write_str`, followed by a nul.
+      None if N > 0 => unsafe {
+        CUtf8::from_str_unchecked(str::from_utf8_unchecked(&self.buf[..=self.len]))
+      },
+      None => CUtf8::EMPTY,
+    }
+  }
+}
+
+impl<const N: usize> Default for StackCUtf8<N> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<const N: usize> fmt::Write for StackCUtf8<N> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    if self.truncated {
+      return Ok(());
+    }
+    let s = match s.find('\0') {
+      Some(nul) => {
+        self.truncated = true;
+        &s[..nul]
+      }
+      None => s,
+    };
+    match &mut self.heap {
+      Some(heap) => heap.push_str(s),
+      None if self.len + s.len() < N => {
+        self.buf[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        self.buf[self.len] = 0;
+      }
+      None => {
+        let mut heap = CUtf8Buf::from_string(self.as_c_utf8().as_str().to_owned());
+        heap.push_str(s);
+        self.heap = Some(heap);
+      }
+    }
+    Ok(())
+  }
+}
+
+impl<const N: usize> Deref for StackCUtf8<N> {
+  type Target = CUtf8;
+
+  fn deref(&self) -> &Self::Target {
+    self.as_c_utf8()
+  }
+}
+
+impl<const N: usize> AsRef<CUtf8> for StackCUtf8<N> {
+  fn as_ref(&self) -> &CUtf8 {
+    self.as_c_utf8()
+  }
+}
+
+impl<const N: usize> fmt::Debug for StackCUtf8<N> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Debug::fmt(self.as_c_utf8(), f)
+  }
+}
+
+impl<const N: usize> fmt::Display for StackCUtf8<N> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(self.as_c_utf8(), f)
+  }
+}
@@ -0,0 +1,402 @@
+//! Frame pacing utilities for cores that generate audio on their own schedule.
+
+use crate::retro::audio::FrameAudio;
+use crate::retro::av::SystemTiming;
+use crate::retro::env::Environment;
+use crate::retro::hw_render::SoftwareRenderEnabled;
+use crate::retro::log::{FallbackLogger, LogInterface, RetroLogLevel};
+use crate::retro::pixel::format::ActiveFormat;
+use crate::retro::video::FrameBuffer;
+use crate::retro::Callbacks;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// Decides how many audio frames (i.e. stereo sample pairs) to emit on each
+/// call to `run`, so that audio stays in sync with video.
+///
+/// When the sample rate isn't a multiple of the frame rate (e.g. 44.1kHz at
+/// 60fps is 735 samples per frame, but at 59.94fps it's ~735.7), emitting a
+/// rounded count every frame slowly drifts. Instead, the count varies between
+/// frames so that the total number of samples after `n` frames is always the
+/// nearest integer to `n * sample_rate / fps`.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::retro::timing::FrameClock;
+/// let mut clock = FrameClock::new(60.0, 44_100.0);
+/// assert_eq!(clock.next_frame(), 735);
+///
+/// let mut clock = FrameClock::new(3.0, 10.0);
+/// let samples: Vec<_> = (0..6).map(|_| clock.next_frame()).collect();
+/// assert_eq!(samples, [3, 4, 3, 3, 4, 3]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameClock {
+  samples_per_frame: f64,
+  frames: u64,
+  samples: u64,
+}
+
+impl FrameClock {
+  /// Creates a clock for the given video frame rate and audio sample rate.
+  pub fn new(fps: f64, sample_rate: f64) -> Self {
+    Self {
+      samples_per_frame: sample_rate / fps,
+      frames: 0,
+      samples: 0,
+    }
+  }
+
+  /// Creates a clock matching the timing reported to the frontend.
+  pub fn from_timing(timing: &SystemTiming) -> Self {
+    Self::new(timing.fps(), timing.sample_rate())
+  }
+
+  /// Advances the clock by one video frame and returns the number of audio
+  /// frames to emit for it.
+  pub fn next_frame(&mut self) -> usize {
+    self.frames += 1;
+    // Computed from the totals rather than accumulated, so rounding errors
+    // can't build up over time.
+    let total = (self.frames as f64 * self.samples_per_frame).round() as u64;
+    let samples = total.saturating_sub(self.samples);
+    self.samples = total;
+    samples as usize
+  }
+
+  /// Returns the number of video frames the clock has advanced by.
+  pub fn frames(&self) -> u64 {
+    self.frames
+  }
+
+  /// Returns the total number of audio frames returned by [FrameClock::next_frame].
+  pub fn samples(&self) -> u64 {
+    self.samples
+  }
+
+  /// Restarts the clock, e.g. when the core is reset.
+  pub fn reset(&mut self) {
+    self.frames = 0;
+    self.samples = 0;
+  }
+}
+
+/// Tracks when `run` was last called, so a core can tell that the frontend
+/// stopped running it for a while (e.g. because it's paused or in a menu) and
+/// resynchronize anything driven by wall clock time. See also
+/// [`Run::is_paused`](crate::retro::env::Run::is_paused).
+///
+/// # Examples
+/// ```
+/// use libretro_rs::retro::timing::RunTracker;
+/// use std::time::{Duration, Instant};
+/// let start = Instant::now();
+/// let mut tracker = RunTracker::new();
+/// tracker.record_run_at(start);
+/// let later = start + Duration::from_secs(2);
+/// assert!(tracker.is_idle_at(later, Duration::from_secs(1)));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunTracker {
+  last_run: Option<Instant>,
+}
+
+impl RunTracker {
+  /// Creates a tracker for a core that hasn't run yet.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Records that `run` was called now. Should be called at the start of
+  /// each `run`, after checking whether the core was idle.
+  pub fn record_run(&mut self) {
+    self.record_run_at(Instant::now());
+  }
+
+  /// Records that `run` was called at `now`.
+  pub fn record_run_at(&mut self, now: Instant) {
+    self.last_run = Some(now);
+  }
+
+  /// Returns the time elapsed since `run` was last called, or [None] if it
+  /// hasn't been called yet.
+  pub fn since_last_run(&self) -> Option<Duration> {
+    self.since_last_run_at(Instant::now())
+  }
+
+  /// Returns the time between the last call to `run` and `now`.
+  pub fn since_last_run_at(&self, now: Instant) -> Option<Duration> {
+    self
+      .last_run
+      .map(|last_run| now.saturating_duration_since(last_run))
+  }
+
+  /// Returns true if `run` hasn't been called for longer than `threshold`.
+  /// A core that hasn't run yet isn't idle.
+  pub fn is_idle(&self, threshold: Duration) -> bool {
+    self.is_idle_at(Instant::now(), threshold)
+  }
+
+  /// Returns true if `run` hadn't been called for longer than `threshold`
+  /// at `now`.
+  pub fn is_idle_at(&self, now: Instant, threshold: Duration) -> bool {
+    self
+      .since_last_run_at(now)
+      .is_some_and(|elapsed| elapsed > threshold)
+  }
+}
+
+/// Merges the output of several internal frames emulated in a single call to
+/// `run` (e.g. when fast-forwarding by running the machine 3 times per frame)
+/// into one upload.
+///
+/// The audio of every sub-frame is concatenated, so none of it is dropped and
+/// audio doesn't drift, while only the latest video frame is kept, so the
+/// frontend doesn't present (and flicker between) intermediate frames.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::retro::timing::TurboFrameAccumulator;
+/// let mut turbo = TurboFrameAccumulator::new(2);
+/// turbo.push_audio(&[1, 2]);
+/// turbo.push_video("first");
+/// assert!(!turbo.finish_sub_frame());
+/// turbo.push_audio(&[3, 4]);
+/// turbo.push_video("second");
+/// assert!(turbo.finish_sub_frame());
+/// assert_eq!(turbo.audio(), &[1, 2, 3, 4]);
+/// assert_eq!(turbo.video(), Some(&"second"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TurboFrameAccumulator<F> {
+  sub_frames: usize,
+  finished: usize,
+  audio: FrameAudio,
+  video: Option<F>,
+}
+
+impl<F> TurboFrameAccumulator<F> {
+  /// Creates an accumulator for `sub_frames` internal frames per call to
+  /// `run`. A count of 0 is treated as 1.
+  pub fn new(sub_frames: usize) -> Self {
+    Self {
+      sub_frames: sub_frames.max(1),
+      finished: 0,
+      audio: FrameAudio::new(),
+      video: None,
+    }
+  }
+
+  /// Returns the number of internal frames per call to `run`.
+  pub fn sub_frames(&self) -> usize {
+    self.sub_frames
+  }
+
+  /// Appends interleaved stereo samples generated by the current sub-frame.
+  pub fn push_audio(&mut self, samples: &[i16]) {
+    self.audio.push_samples(samples)
+  }
+
+  /// Replaces the video frame with the one rendered by the current sub-frame.
+  pub fn push_video(&mut self, frame: F) {
+    self.video = Some(frame)
+  }
+
+  /// Marks the current sub-frame as finished, and returns true once all of
+  /// the sub-frames are, i.e. when it's time to [flush](Self::flush).
+  pub fn finish_sub_frame(&mut self) -> bool {
+    self.finished += 1;
+    self.is_complete()
+  }
+
+  /// Returns the number of sub-frames finished since the last flush.
+  pub fn finished_sub_frames(&self) -> usize {
+    self.finished
+  }
+
+  /// Returns true if all of the sub-frames have been finished.
+  pub fn is_complete(&self) -> bool {
+    self.finished >= self.sub_frames
+  }
+
+  /// Returns the interleaved samples accumulated so far.
+  pub fn audio(&self) -> &[i16] {
+    self.audio.samples()
+  }
+
+  /// Returns the latest video frame, if any sub-frame rendered one.
+  pub fn video(&self) -> Option<&F> {
+    self.video.as_ref()
+  }
+
+  /// Discards everything accumulated so far, keeping the allocated capacity.
+  pub fn clear(&mut self) {
+    self.finished = 0;
+    self.audio.clear();
+    self.video = None;
+  }
+}
+
+impl<F: FrameBuffer> TurboFrameAccumulator<F> {
+  /// Uploads the accumulated audio in a single batch and the latest video
+  /// frame, then clears the accumulator for the next call to `run`. Can be
+  /// called before all of the sub-frames are finished, e.g. if emulation
+  /// stopped early.
+  ///
+  /// If no sub-frame rendered a video frame, only audio is uploaded; the core
+  /// should then repeat the previous frame, e.g. with
+  /// [`Callbacks::dup_video_frame`].
+  pub fn flush<P>(
+    &mut self,
+    callbacks: &mut impl Callbacks,
+    enabled: &SoftwareRenderEnabled,
+    pixel_format: &ActiveFormat<P>,
+  ) {
+    if !self.audio.is_empty() {
+      callbacks.upload_audio_frames(self.audio.samples());
+    }
+    if let Some(frame) = &self.video {
+      callbacks.upload_video_frame(enabled, pixel_format, frame);
+    }
+    self.clear();
+  }
+}
+
+/// Measures the time between audio uploads with the frontend's microsecond
+/// clock, and summarizes it every `period`, to help diagnose audio glitches
+/// caused by uneven pacing.
+///
+/// Recording an upload only does a few integer operations and never
+/// allocates, so it's cheap enough to leave in release builds, e.g. behind a
+/// core option.
+///
+/// # Examples
+/// ```
+/// use libretro_rs::retro::timing::AudioJitter;
+/// use std::time::Duration;
+/// let mut jitter = AudioJitter::new(Duration::from_millis(50));
+/// for time in [0, 16_000, 33_000] {
+///   assert_eq!(jitter.record_upload_at(time), None);
+/// }
+/// let stats = jitter.record_upload_at(50_000).unwrap();
+/// assert_eq!((stats.count, stats.min_usec, stats.max_usec), (3, 16_000, 17_000));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioJitter {
+  period_usec: u64,
+  last_upload: Option<u64>,
+  period_start: Option<u64>,
+  stats: Option<AudioJitterStats>,
+}
+
+/// The intervals between the audio uploads of a period, as measured by
+/// [AudioJitter], in microseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AudioJitterStats {
+  /// The number of intervals measured.
+  pub count: u64,
+  pub min_usec: u64,
+  pub max_usec: u64,
+  /// The sum of the intervals, i.e. the length of the period.
+  pub total_usec: u64,
+}
+
+impl AudioJitterStats {
+  fn new(interval: u64) -> Self {
+    Self {
+      count: 1,
+      min_usec: interval,
+      max_usec: interval,
+      total_usec: interval,
+    }
+  }
+
+  fn record(&mut self, interval: u64) {
+    self.count += 1;
+    self.min_usec = self.min_usec.min(interval);
+    self.max_usec = self.max_usec.max(interval);
+    self.total_usec += interval;
+  }
+
+  /// Returns the average interval, rounded down.
+  pub fn avg_usec(&self) -> u64 {
+    self.total_usec / self.count
+  }
+
+  /// Returns the difference between the longest and shortest intervals.
+  pub fn jitter_usec(&self) -> u64 {
+    self.max_usec - self.min_usec
+  }
+}
+
+impl Display for AudioJitterStats {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "audio upload intervals over {} uploads: min {} us, max {} us, avg {} us, jitter {} us",
+      self.count,
+      self.min_usec,
+      self.max_usec,
+      self.avg_usec(),
+      self.jitter_usec()
+    )
+  }
+}
+
+impl AudioJitter {
+  /// Creates a meter that summarizes the intervals every `period`.
+  pub fn new(period: Duration) -> Self {
+    Self {
+      period_usec: period.as_micros().try_into().unwrap_or(u64::MAX),
+      ..Self::default()
+    }
+  }
+
+  /// Records an audio upload at the time reported by
+  /// [Environment::get_time_usec]. Does nothing if the frontend doesn't
+  /// provide a clock. See [AudioJitter::record_upload_at].
+  pub fn record_upload(&mut self, env: &impl Environment) -> Option<AudioJitterStats> {
+    self.record_upload_at(env.get_time_usec()?)
+  }
+
+  /// Records an audio upload at `time_usec`, and returns the statistics of the
+  /// period that just ended, if any, before starting a new one.
+  pub fn record_upload_at(&mut self, time_usec: u64) -> Option<AudioJitterStats> {
+    let last_upload = self.last_upload.replace(time_usec);
+    let period_start = *self.period_start.get_or_insert(time_usec);
+    // The frontend's clock is monotonic, but don't rely on it.
+    let interval = time_usec.checked_sub(last_upload?)?;
+    match &mut self.stats {
+      Some(stats) => stats.record(interval),
+      None => self.stats = Some(AudioJitterStats::new(interval)),
+    }
+    if time_usec.saturating_sub(period_start) < self.period_usec {
+      return None;
+    }
+    self.period_start = Some(time_usec);
+    self.stats.take()
+  }
+
+  /// Like [AudioJitter::record_upload], but logs the statistics at
+  /// [RetroLogLevel::Debug] at the end of each period.
+  pub fn record_upload_and_log<T: LogInterface>(
+    &mut self,
+    env: &impl Environment,
+    logger: &mut FallbackLogger<T>,
+  ) {
+    if let Some(stats) = self.record_upload(env) {
+      logger.log_display(RetroLogLevel::Debug, stats);
+    }
+  }
+
+  /// Returns the statistics of the current period so far.
+  pub fn current(&self) -> Option<AudioJitterStats> {
+    self.stats
+  }
+
+  /// Forgets the previous upload, e.g. after the frontend paused the core, so
+  /// the pause isn't measured as an interval.
+  pub fn reset(&mut self) {
+    *self = Self { period_usec: self.period_usec, ..Self::default() };
+  }
+}
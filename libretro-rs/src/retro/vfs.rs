@@ -0,0 +1,244 @@
+//! Access to the frontend's virtual file system. See
+//! [`Environment::get_vfs_interface`](crate::retro::env::Environment::get_vfs_interface).
+//!
+//! The VFS interface is versioned, and each version adds operations:
+//! * Version 1 can open, read, write, seek and close files, and remove or
+//!   rename them.
+//! * Version 2 adds [VfsFile::truncate].
+//! * Version 3 adds directories ([VfsInterface::opendir], [VfsInterface::mkdir])
+//!   and [VfsInterface::stat].
+//!
+//! Operations that the granted version doesn't support fail with
+//! [`io::ErrorKind::Unsupported`], so cores should request the lowest version
+//! that provides what they need.
+
+use crate::ffi::*;
+use core::ffi::*;
+use std::ffi::CString;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+/// The frontend's VFS interface, along with the version it implements.
+#[derive(Clone, Copy, Debug)]
+pub struct VfsInterface {
+  iface: &'static retro_vfs_interface,
+  version: u32,
+}
+
+impl VfsInterface {
+  /// Wraps an interface returned by the frontend.
+  ///
+  /// # Safety
+  /// `iface` must implement at least `version` of the VFS interface, and
+  /// remain valid for the lifetime of the core.
+  pub unsafe fn from_raw(iface: &'static retro_vfs_interface, version: u32) -> Self {
+    Self { iface, version }
+  }
+
+  /// Returns the version of the interface granted by the frontend.
+  pub fn version(&self) -> u32 {
+    self.version
+  }
+
+  /// Opens the file at `path`. `mode` is a combination of the
+  /// `RETRO_VFS_FILE_ACCESS_*` flags.
+  pub fn open(&self, path: &CStr, mode: c_uint) -> io::Result<VfsFile> {
+    let open = self.require(1, self.iface.open)?;
+    let handle = unsafe { open(path.as_ptr(), mode, RETRO_VFS_FILE_ACCESS_HINT_NONE) };
+    match handle.is_null() {
+      true => Err(io::ErrorKind::NotFound.into()),
+      false => Ok(VfsFile { vfs: *self, handle }),
+    }
+  }
+
+  /// Deletes the file at `path`.
+  pub fn remove(&self, path: &CStr) -> io::Result<()> {
+    let remove = self.require(1, self.iface.remove)?;
+    check(unsafe { remove(path.as_ptr()) })
+  }
+
+  /// Renames the file at `old_path` to `new_path`.
+  pub fn rename(&self, old_path: &CStr, new_path: &CStr) -> io::Result<()> {
+    let rename = self.require(1, self.iface.rename)?;
+    check(unsafe { rename(old_path.as_ptr(), new_path.as_ptr()) })
+  }
+
+  /// Returns the `RETRO_VFS_STAT_*` flags and size of the file at `path`.
+  /// Requires version 3.
+  pub fn stat(&self, path: &CStr) -> io::Result<(c_uint, i32)> {
+    let stat = self.require(3, self.iface.stat)?;
+    let mut size = 0;
+    let flags = unsafe { stat(path.as_ptr(), &mut size) } as c_uint;
+    match flags & RETRO_VFS_STAT_IS_VALID {
+      0 => Err(io::ErrorKind::NotFound.into()),
+      _ => Ok((flags, size)),
+    }
+  }
+
+  /// Creates the directory at `path`. Requires version 3.
+  pub fn mkdir(&self, path: &CStr) -> io::Result<()> {
+    let mkdir = self.require(3, self.iface.mkdir)?;
+    match unsafe { mkdir(path.as_ptr()) } {
+      0 => Ok(()),
+      -2 => Err(io::ErrorKind::AlreadyExists.into()),
+      _ => Err(io::Error::other("VFS mkdir failed")),
+    }
+  }
+
+  /// Opens the directory at `path` for listing. Requires version 3.
+  pub fn opendir(&self, path: &CStr, include_hidden: bool) -> io::Result<VfsDir> {
+    let opendir = self.require(3, self.iface.opendir)?;
+    let handle = unsafe { opendir(path.as_ptr(), include_hidden) };
+    match handle.is_null() {
+      true => Err(io::ErrorKind::NotFound.into()),
+      false => Ok(VfsDir { vfs: *self, handle }),
+    }
+  }
+
+  /// Returns `function` if the frontend provided it and the granted version is
+  /// at least `version`, the version that introduced it.
+  fn require<F>(&self, version: u32, function: Option<F>) -> io::Result<F> {
+    match function {
+      Some(function) if self.version >= version => Ok(function),
+      _ => Err(io::ErrorKind::Unsupported.into()),
+    }
+  }
+}
+
+fn check(result: c_int) -> io::Result<()> {
+  match result {
+    0 => Ok(()),
+    _ => Err(io::Error::other("VFS operation failed")),
+  }
+}
+
+fn check_len(result: i64) -> io::Result<u64> {
+  u64::try_from(result).map_err(|_| io::Error::other("VFS operation failed"))
+}
+
+/// A file opened through the VFS interface. Closed when dropped.
+#[derive(Debug)]
+pub struct VfsFile {
+  vfs: VfsInterface,
+  handle: *mut retro_vfs_file_handle,
+}
+
+impl VfsFile {
+  /// Returns the path the file was opened with.
+  pub fn path(&self) -> io::Result<&CStr> {
+    let get_path = self.vfs.require(1, self.vfs.iface.get_path)?;
+    let path = unsafe { get_path(self.handle) };
+    match path.is_null() {
+      true => Err(io::Error::other("VFS get_path failed")),
+      false => Ok(unsafe { CStr::from_ptr(path) }),
+    }
+  }
+
+  /// Returns the size of the file, in bytes.
+  pub fn size(&self) -> io::Result<u64> {
+    let size = self.vfs.require(1, self.vfs.iface.size)?;
+    check_len(unsafe { size(self.handle) })
+  }
+
+  /// Truncates or extends the file to `len` bytes. Requires version 2.
+  pub fn truncate(&mut self, len: u64) -> io::Result<()> {
+    let truncate = self.vfs.require(2, self.vfs.iface.truncate)?;
+    let len = i64::try_from(len).map_err(|_| io::ErrorKind::InvalidInput)?;
+    check_len(unsafe { truncate(self.handle, len) }).map(|_| ())
+  }
+
+  /// Closes the file, reporting errors that dropping it would ignore, such as
+  /// failing to write buffered data.
+  pub fn close(self) -> io::Result<()> {
+    let file = core::mem::ManuallyDrop::new(self);
+    let close = file.vfs.require(1, file.vfs.iface.close)?;
+    check(unsafe { close(file.handle) })
+  }
+}
+
+impl Read for VfsFile {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let read = self.vfs.require(1, self.vfs.iface.read)?;
+    let len = unsafe { read(self.handle, buf.as_mut_ptr().cast(), buf.len() as u64) };
+    check_len(len).map(|len| len as usize)
+  }
+}
+
+impl Write for VfsFile {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let write = self.vfs.require(1, self.vfs.iface.write)?;
+    let len = unsafe { write(self.handle, buf.as_ptr().cast(), buf.len() as u64) };
+    check_len(len).map(|len| len as usize)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    let flush = self.vfs.require(1, self.vfs.iface.flush)?;
+    check(unsafe { flush(self.handle) })
+  }
+}
+
+impl Seek for VfsFile {
+  fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+    let seek = self.vfs.require(1, self.vfs.iface.seek)?;
+    let (offset, position) = match pos {
+      SeekFrom::Start(offset) => (
+        i64::try_from(offset).map_err(|_| io::ErrorKind::InvalidInput)?,
+        RETRO_VFS_SEEK_POSITION_START,
+      ),
+      SeekFrom::Current(offset) => (offset, RETRO_VFS_SEEK_POSITION_CURRENT),
+      SeekFrom::End(offset) => (offset, RETRO_VFS_SEEK_POSITION_END),
+    };
+    check_len(unsafe { seek(self.handle, offset, position as c_int) })
+  }
+}
+
+impl Drop for VfsFile {
+  fn drop(&mut self) {
+    if let Some(close) = self.vfs.iface.close {
+      unsafe { close(self.handle) };
+    }
+  }
+}
+
+/// A directory opened through the VFS interface, which iterates over its
+/// entries. Closed when dropped.
+#[derive(Debug)]
+pub struct VfsDir {
+  vfs: VfsInterface,
+  handle: *mut retro_vfs_dir_handle,
+}
+
+/// An entry of a [VfsDir].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VfsDirEntry {
+  pub name: CString,
+  pub is_dir: bool,
+}
+
+impl Iterator for VfsDir {
+  type Item = VfsDirEntry;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let iface = self.vfs.iface;
+    let (readdir, get_name, is_dir) =
+      (iface.readdir?, iface.dirent_get_name?, iface.dirent_is_dir?);
+    unsafe {
+      if !readdir(self.handle) {
+        return None;
+      }
+      let name = get_name(self.handle);
+      let name = match name.is_null() {
+        true => CString::default(),
+        false => CStr::from_ptr(name).to_owned(),
+      };
+      Some(VfsDirEntry { name, is_dir: is_dir(self.handle) })
+    }
+  }
+}
+
+impl Drop for VfsDir {
+  fn drop(&mut self) {
+    if let Some(closedir) = self.vfs.iface.closedir {
+      unsafe { closedir(self.handle) };
+    }
+  }
+}
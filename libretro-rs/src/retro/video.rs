@@ -1,4 +1,6 @@
 use crate::retro;
+use crate::retro::pixel::ConvertPixel;
+use std::io;
 use std::mem;
 use std::slice::{ChunksExact, ChunksExactMut};
 
@@ -57,7 +59,71 @@ pub unsafe trait PackedFrameBuffer: FrameBuffer + AsRef<[Self::Pixel]> {
   /// Read-only iterator over the rows of pixels in the buffer. The slices are
   /// guaranteed to have the same length as the framebuffer's width.
   fn rows(&self) -> ChunksExact<'_, Self::Pixel> {
-    self.as_ref().chunks_exact(self.pitch())
+    self.as_ref().chunks_exact(self.width() as usize)
+  }
+
+  /// Writes the frame buffer to `w` as a binary (P6) PPM image, converting the
+  /// pixels to 8-bit RGB. This is a dependency-free way to dump frames while
+  /// debugging; most image viewers can open PPM files.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let buf = ArrayFrameBuffer::<XRGB8888, {2*1}, 2>::default();
+  /// let mut ppm = Vec::new();
+  /// buf.write_ppm(&mut ppm).unwrap();
+  /// assert!(ppm.starts_with(b"P6\n2 1\n255\n"));
+  /// ```
+  fn write_ppm(&self, w: &mut impl io::Write) -> io::Result<()> {
+    use retro::pixel::format::private::Sealed;
+    write!(w, "P6\n{} {}\n255\n", self.width(), self.height())?;
+    let rgb: Vec<u8> = self
+      .data()
+      .chunks_exact(mem::size_of::<Self::Pixel>())
+      .flat_map(Self::Pixel::to_rgb888)
+      .collect();
+    w.write_all(&rgb)
+  }
+
+  /// Returns a copy of the frame buffer with every pixel converted to the
+  /// format `To`. See [ConvertPixel] for how channels are converted.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let buf = ArrayFrameBuffer::<RGB565, {2*1}, 2>::new([RGB565::new_with_raw_value(0xF800); 2]);
+  /// let converted = buf.convert_to::<XRGB8888>();
+  /// assert_eq!(converted.width(), 2);
+  /// assert_eq!(converted[1].raw_value(), 0x00FF0000);
+  /// ```
+  fn convert_to<To>(&self) -> SliceFrameBuffer<Vec<To>>
+  where
+    Self::Pixel: Copy,
+    To: retro::pixel::format::Format,
+  {
+    let pixels = self.pixels().iter().map(|&pixel| pixel.convert()).collect();
+    // Safety: the converted buffer has the same dimensions as this one.
+    unsafe { SliceFrameBuffer::with_width_unchecked(pixels, self.width()) }
+  }
+
+  /// Returns a copy of the frame buffer as an [`image::RgbaImage`], e.g. to
+  /// save it with the `image` crate. Every pixel is opaque.
+  #[cfg(feature = "image")]
+  fn to_rgba_image(&self) -> image::RgbaImage
+  where
+    Self::Pixel: Copy,
+  {
+    use retro::pixel::format::private::Sealed;
+    let rgba = self
+      .pixels()
+      .iter()
+      .flat_map(|&pixel| {
+        let [r, g, b] = pixel.into_rgb888();
+        [r, g, b, u8::MAX]
+      })
+      .collect();
+    // The image has exactly enough room for the pixels of this buffer.
+    image::RgbaImage::from_raw(self.width().into(), self.height().into(), rgba).unwrap()
   }
 }
 
@@ -71,8 +137,549 @@ pub unsafe trait PackedFrameBufferMut: PackedFrameBuffer + AsMut<[Self::Pixel]>
   /// Mutable iterator over the rows of pixels in the buffer. The slices are
   /// guaranteed to have the same length as the framebuffer's width.
   fn rows_mut(&mut self) -> ChunksExactMut<'_, Self::Pixel> {
-    let pitch = self.pitch();
-    self.as_mut().chunks_exact_mut(pitch)
+    let width = self.width() as usize;
+    self.as_mut().chunks_exact_mut(width)
+  }
+
+  /// Sets every pixel in the buffer to `color`.
+  fn fill(&mut self, color: Self::Pixel)
+  where
+    Self::Pixel: Copy,
+  {
+    self.pixels_mut().fill(color)
+  }
+
+  /// Fills the `width` by `height` rectangle whose top left corner is at
+  /// (`x`, `y`) with `color`. The rectangle is clipped to the buffer, so it may
+  /// be partially (or entirely) outside of it.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let mut buf = ArrayFrameBuffer::<XRGB8888, {4*4}, 4>::default();
+  /// let red = XRGB8888::new_with_raw_value(0x00FF0000);
+  /// buf.fill_rect(-1, 2, 2, 8, red);
+  /// assert_eq!(buf[4 * 1], XRGB8888::DEFAULT);
+  /// assert_eq!(buf[4 * 2], red);
+  /// assert_eq!(buf[4 * 2 + 1], XRGB8888::DEFAULT);
+  /// assert_eq!(buf[4 * 3], red);
+  /// ```
+  fn fill_rect(&mut self, x: i32, y: i32, width: u16, height: u16, color: Self::Pixel)
+  where
+    Self::Pixel: Copy,
+  {
+    let (buf_width, buf_height) = (self.width() as i32, self.height() as i32);
+    let (left, right) = (
+      x.clamp(0, buf_width),
+      (x + width as i32).clamp(0, buf_width),
+    );
+    let (top, bottom) = (
+      y.clamp(0, buf_height),
+      (y + height as i32).clamp(0, buf_height),
+    );
+    if left == right {
+      return;
+    }
+    let rows = self.pixels_mut().chunks_exact_mut(buf_width as usize);
+    for row in rows.take(bottom as usize).skip(top as usize) {
+      row[left as usize..right as usize].fill(color);
+    }
+  }
+
+  /// Copies the row-major pixels in `src`, whose rows are `src_width` pixels
+  /// wide, into the top left corner of the buffer. Only the region that
+  /// overlaps the buffer is copied: columns past either width and rows past
+  /// either height are skipped, as is a trailing partial row of `src`.
+  ///
+  /// Returns the number of rows copied, which is less than the height of `src`
+  /// if it was truncated.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let mut buf = ArrayFrameBuffer::<XRGB8888, {2*2}, 2>::default();
+  /// let src = [1, 2, 3, 4, 5, 6, 7, 8, 9].map(XRGB8888::new_with_raw_value);
+  /// assert_eq!(buf.blit_from(&src, 3), 2);
+  /// assert_eq!(buf.map(|pixel| pixel.raw_value()), [1, 2, 4, 5]);
+  /// ```
+  fn blit_from(&mut self, src: &[Self::Pixel], src_width: u16) -> u16
+  where
+    Self::Pixel: Copy,
+  {
+    if src_width == 0 || self.width() == 0 {
+      return 0;
+    }
+    let width = self.width().min(src_width) as usize;
+    let dst_width = self.width() as usize;
+    let src_rows = src.chunks_exact(src_width as usize);
+    let dst_rows = self.pixels_mut().chunks_exact_mut(dst_width);
+    let mut rows = 0;
+    for (dst, src) in dst_rows.zip(src_rows) {
+      dst[..width].copy_from_slice(&src[..width]);
+      rows += 1;
+    }
+    rows
+  }
+
+  /// Copies the `width` by `height` rectangle of `src` whose top left corner
+  /// is at (`x`, `y`) to `dst` in this buffer, skipping the pixels equal to
+  /// `color_key` so they act as transparent, e.g. to draw sprites. The
+  /// rectangle is clipped to both buffers, so it may be partially (or
+  /// entirely) outside of either.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let mut buf = ArrayFrameBuffer::<XRGB8888, {3*2}, 3>::default();
+  /// let sprite = ArrayFrameBuffer::<XRGB8888, {2*2}, 2>::new([1, 0, 0, 2].map(XRGB8888::new_with_raw_value));
+  /// buf.fill(XRGB8888::new_with_raw_value(9));
+  /// buf.blit_keyed(&sprite, (0, 0, 2, 2), (1, 0), XRGB8888::new_with_raw_value(0));
+  /// assert_eq!(buf.map(|pixel| pixel.raw_value()), [9, 1, 9, 9, 9, 2]);
+  /// ```
+  fn blit_keyed<S>(
+    &mut self,
+    src: &S,
+    (x, y, width, height): (i32, i32, u16, u16),
+    dst: (i32, i32),
+    color_key: Self::Pixel,
+  ) where
+    S: PackedFrameBuffer<Pixel = Self::Pixel> + ?Sized,
+    Self::Pixel: Copy + PartialEq,
+  {
+    let (src_width, src_height) = (src.width() as i64, src.height() as i64);
+    let (dst_width, dst_height) = (self.width() as i64, self.height() as i64);
+    let (x, y, dst_x, dst_y) = (x as i64, y as i64, dst.0 as i64, dst.1 as i64);
+    // The offsets within the rectangle that are inside both buffers.
+    let left = 0.max(-x).max(-dst_x);
+    let right = (width as i64).min(src_width - x).min(dst_width - dst_x);
+    let top = 0.max(-y).max(-dst_y);
+    let bottom = (height as i64).min(src_height - y).min(dst_height - dst_y);
+    if left >= right || top >= bottom {
+      return;
+    }
+    let pixels = self.pixels_mut();
+    for row in top..bottom {
+      let src_start = ((y + row) * src_width + x + left) as usize;
+      let dst_start = ((dst_y + row) * dst_width + dst_x + left) as usize;
+      let len = (right - left) as usize;
+      let src_row = &src.pixels()[src_start..src_start + len];
+      let dst_row = &mut pixels[dst_start..dst_start + len];
+      for (dst, &src) in dst_row.iter_mut().zip(src_row) {
+        if src != color_key {
+          *dst = src;
+        }
+      }
+    }
+  }
+
+  /// Draws a line from `from` to `to` (both inclusive) with `color`, using
+  /// Bresenham's algorithm. The line is clipped to the buffer, so either end
+  /// may be outside of it; only the visible part of the line is walked.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let mut buf = ArrayFrameBuffer::<XRGB8888, {4*4}, 4>::default();
+  /// let red = XRGB8888::new_with_raw_value(0x00FF0000);
+  /// buf.draw_line((-2, -2), (1, 1), red);
+  /// assert_eq!(buf[0], red);
+  /// assert_eq!(buf[4 + 1], red);
+  /// assert_eq!(buf[4 * 2 + 2], XRGB8888::DEFAULT);
+  /// ```
+  fn draw_line(&mut self, from: (i32, i32), to: (i32, i32), color: Self::Pixel)
+  where
+    Self::Pixel: Copy,
+  {
+    let (width, height) = (self.width() as i64, self.height() as i64);
+    let pixels = self.pixels_mut();
+    let (dx, dy) = (to.0 as i64 - from.0 as i64, to.1 as i64 - from.1 as i64);
+    // Step along the major axis, one pixel at a time, and derive the minor
+    // coordinate of each step. This picks the same pixels as the usual error
+    // accumulation, but allows skipping the steps outside of the buffer.
+    let steep = dy.abs() > dx.abs();
+    let (major, minor, d_major, d_minor, major_len) = match steep {
+      true => (from.1 as i64, from.0 as i64, dy, dx, height),
+      false => (from.0 as i64, from.1 as i64, dx, dy, width),
+    };
+    let start = major.min(major + d_major).max(0);
+    let end = major.max(major + d_major).min(major_len - 1);
+    for step in start..=end {
+      let offset = match d_major {
+        0 => 0,
+        _ => round_div((step - major) as i128 * d_minor as i128, d_major as i128),
+      };
+      let (x, y) = match steep {
+        true => (minor + offset, step),
+        false => (step, minor + offset),
+      };
+      if (0..width).contains(&x) && (0..height).contains(&y) {
+        pixels[(y * width + x) as usize] = color;
+      }
+    }
+  }
+
+  /// Draws the outline of a circle centered at `center` with `color`, using
+  /// the midpoint circle algorithm. The circle is clipped to the buffer, so it
+  /// may be partially (or entirely) outside of it. A `radius` of 0 draws a
+  /// single pixel.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let mut buf = ArrayFrameBuffer::<XRGB8888, {5*5}, 5>::default();
+  /// let red = XRGB8888::new_with_raw_value(0x00FF0000);
+  /// buf.draw_circle((2, 2), 2, red);
+  /// assert_eq!(buf[2], red);
+  /// assert_eq!(buf[5 * 2], red);
+  /// assert_eq!(buf[5 * 2 + 2], XRGB8888::DEFAULT);
+  /// ```
+  fn draw_circle(&mut self, center: (i32, i32), radius: u16, color: Self::Pixel)
+  where
+    Self::Pixel: Copy,
+  {
+    let (width, height) = (self.width() as i64, self.height() as i64);
+    let (cx, cy) = (center.0 as i64, center.1 as i64);
+    if !circle_overlaps(cx, cy, radius, width, height) {
+      return;
+    }
+    let pixels = self.pixels_mut();
+    for (x, y) in circle_octant(radius) {
+      let points = [
+        (x, y),
+        (y, x),
+        (-y, x),
+        (-x, y),
+        (-x, -y),
+        (-y, -x),
+        (y, -x),
+        (x, -y),
+      ];
+      for (dx, dy) in points {
+        let (px, py) = (cx + dx, cy + dy);
+        if (0..width).contains(&px) && (0..height).contains(&py) {
+          pixels[(py * width + px) as usize] = color;
+        }
+      }
+    }
+  }
+
+  /// Fills a circle centered at `center` with `color`, covering the same
+  /// pixels as [draw_circle](Self::draw_circle) and everything inside them.
+  /// The circle is clipped to the buffer, so it may be partially (or entirely)
+  /// outside of it. A `radius` of 0 fills a single pixel.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let mut buf = ArrayFrameBuffer::<XRGB8888, {5*5}, 5>::default();
+  /// let red = XRGB8888::new_with_raw_value(0x00FF0000);
+  /// buf.fill_circle((2, 2), 2, red);
+  /// assert_eq!(buf[5 * 2 + 2], red);
+  /// assert_eq!(buf[0], XRGB8888::DEFAULT);
+  /// ```
+  fn fill_circle(&mut self, center: (i32, i32), radius: u16, color: Self::Pixel)
+  where
+    Self::Pixel: Copy,
+  {
+    let (width, height) = (self.width() as i64, self.height() as i64);
+    let (cx, cy) = (center.0 as i64, center.1 as i64);
+    if !circle_overlaps(cx, cy, radius, width, height) {
+      return;
+    }
+    let rows = self.pixels_mut().chunks_exact_mut(width as usize);
+    let mut spans = vec![0; height as usize];
+    for (x, y) in circle_octant(radius) {
+      for (dy, half_width) in [(y, x), (-y, x), (x, y), (-x, y)] {
+        if let Some(span) = usize::try_from(cy + dy)
+          .ok()
+          .and_then(|row| spans.get_mut(row))
+        {
+          *span = (*span).max(half_width + 1);
+        }
+      }
+    }
+    for (row, span) in rows.zip(spans) {
+      if span > 0 {
+        let left = (cx - span + 1).clamp(0, width) as usize;
+        let right = (cx + span).clamp(0, width) as usize;
+        row[left..right].fill(color);
+      }
+    }
+  }
+
+  /// Draws `text` using the built-in [8x8 font](FONT_8X8), with the top left
+  /// corner of the first glyph at (`x`, `y`). Only the glyphs' foreground
+  /// pixels are drawn, and anything outside the buffer is clipped, so it's
+  /// safe to draw text that doesn't fit. A `'\n'` moves to the start of the
+  /// next line; characters outside of printable ASCII are drawn as `'?'`.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let mut buf = ArrayFrameBuffer::<XRGB8888, {16*8}, 16>::default();
+  /// let white = XRGB8888::new_with_raw_value(0x00FFFFFF);
+  /// buf.draw_text(0, 0, "AB", white);
+  /// // The top row of 'A' is `..##....`
+  /// assert_eq!(buf[1], XRGB8888::DEFAULT);
+  /// assert_eq!(buf[2], white);
+  /// assert_eq!(buf[3], white);
+  /// // The top row of 'B' is `######..`
+  /// assert_eq!(buf[8], white);
+  /// assert_eq!(buf[13], white);
+  /// assert_eq!(buf[14], XRGB8888::DEFAULT);
+  /// // Glyphs that don't fit are clipped.
+  /// buf.draw_text(12, -4, "AB", white);
+  /// ```
+  fn draw_text(&mut self, x: i32, y: i32, text: &str, color: Self::Pixel)
+  where
+    Self::Pixel: Copy,
+  {
+    let (width, height) = (self.width() as i32, self.height() as i32);
+    let pixels = self.pixels_mut();
+    let (mut glyph_x, mut glyph_y) = (x, y);
+    for c in text.chars() {
+      if c == '\n' {
+        glyph_x = x;
+        glyph_y += GLYPH_HEIGHT as i32;
+        continue;
+      }
+      for (row, bits) in glyph(c).iter().enumerate() {
+        let py = glyph_y + row as i32;
+        if !(0..height).contains(&py) {
+          continue;
+        }
+        for col in 0..GLYPH_WIDTH as i32 {
+          let px = glyph_x + col;
+          if bits >> col & 1 != 0 && (0..width).contains(&px) {
+            pixels[(py * width + px) as usize] = color;
+          }
+        }
+      }
+      glyph_x += GLYPH_WIDTH as i32;
+    }
+  }
+
+  /// Applies gamma correction to every pixel: each channel `c`, scaled to
+  /// `0.0..=1.0`, becomes `c.powf(1.0 / gamma)`. Values above 1.0 brighten
+  /// the midtones, values below 1.0 darken them, and black and white are left
+  /// unchanged. A gamma of 1.0 leaves the buffer untouched.
+  ///
+  /// Channels narrower than 8 bits are expanded to 8 bits, corrected through
+  /// a 256-entry lookup table, and truncated back.
+  ///
+  /// # Panics
+  /// Panics if `gamma` isn't positive and finite.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let mut buf = ArrayFrameBuffer::<XRGB8888, {1*1}, 1>::new([XRGB8888::new_with_raw_value(0x00004080)]);
+  /// buf.apply_gamma(2.0);
+  /// assert_eq!(buf[0].raw_value(), 0x000080B5);
+  /// ```
+  fn apply_gamma(&mut self, gamma: f32)
+  where
+    Self::Pixel: Copy,
+  {
+    assert!(
+      gamma > 0.0 && gamma.is_finite(),
+      "gamma must be positive and finite, got {gamma}"
+    );
+    let exponent = 1.0 / gamma;
+    let lut = channel_lut(|c| (255.0 * (c as f32 / 255.0).powf(exponent)).round() as u8);
+    map_channels(self.pixels_mut(), &lut);
+  }
+
+  /// Adds `delta` to every channel of every pixel, clamping the result to the
+  /// channel's range. Channels narrower than 8 bits are adjusted as 8-bit
+  /// values, so a `delta` of 8 raises a 5-bit channel by one step.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let mut buf = ArrayFrameBuffer::<XRGB8888, {1*1}, 1>::new([XRGB8888::new_with_raw_value(0x0010F080)]);
+  /// buf.apply_brightness(32);
+  /// assert_eq!(buf[0].raw_value(), 0x0030FFA0);
+  /// ```
+  fn apply_brightness(&mut self, delta: i16)
+  where
+    Self::Pixel: Copy,
+  {
+    let lut = channel_lut(|c| (c as i32 + delta as i32).clamp(0, u8::MAX as i32) as u8);
+    map_channels(self.pixels_mut(), &lut);
+  }
+}
+
+/// Builds a lookup table mapping every 8-bit channel value through `f`.
+fn channel_lut(f: impl Fn(u8) -> u8) -> [u8; 256] {
+  core::array::from_fn(|c| f(c as u8))
+}
+
+/// Maps each channel of `pixels` through `lut`, as 8-bit values. Does nothing
+/// if `lut` is the identity, so pixels aren't needlessly round-tripped through
+/// 8-bit RGB (which would clear the unused bits of some formats).
+fn map_channels<P: retro::pixel::format::Format + Copy>(pixels: &mut [P], lut: &[u8; 256]) {
+  if lut
+    .iter()
+    .enumerate()
+    .all(|(c, &mapped)| c == mapped as usize)
+  {
+    return;
+  }
+  for pixel in pixels {
+    *pixel = P::from_rgb888(pixel.into_rgb888().map(|c| lut[c as usize]));
+  }
+}
+
+/// Divides `n` by `d`, rounding to the nearest integer (halves round up).
+fn round_div(n: i128, d: i128) -> i64 {
+  let (n, d) = if d < 0 { (-n, -d) } else { (n, d) };
+  (2 * n + d).div_euclid(2 * d) as i64
+}
+
+/// Returns true if the bounding box of a circle overlaps a `width` by `height`
+/// buffer.
+fn circle_overlaps(cx: i64, cy: i64, radius: u16, width: i64, height: i64) -> bool {
+  let radius = radius as i64;
+  cx + radius >= 0 && cx - radius < width && cy + radius >= 0 && cy - radius < height
+}
+
+/// Returns the points of the first octant of a circle centered at the origin,
+/// from `(radius, 0)` to the diagonal, using the midpoint circle algorithm.
+fn circle_octant(radius: u16) -> impl Iterator<Item = (i64, i64)> {
+  let (mut x, mut y, mut error) = (radius as i64, 0, 1 - radius as i64);
+  core::iter::from_fn(move || {
+    if x < y {
+      return None;
+    }
+    let point = (x, y);
+    y += 1;
+    if error < 0 {
+      error += 2 * y + 1;
+    } else {
+      x -= 1;
+      error += 2 * (y - x) + 1;
+    }
+    Some(point)
+  })
+}
+
+pub use font::*;
+mod font {
+  /// The width of a glyph in [FONT_8X8], in pixels.
+  pub const GLYPH_WIDTH: usize = 8;
+
+  /// The height of a glyph in [FONT_8X8], in pixels.
+  pub const GLYPH_HEIGHT: usize = 8;
+
+  /// A public domain 8x8 bitmap font covering printable ASCII (`' '..='~'`),
+  /// based on the IBM PC BIOS font. Each glyph is 8 rows from top to bottom;
+  /// in each row, the least significant bit is the leftmost pixel.
+  pub const FONT_8X8: [[u8; GLYPH_HEIGHT]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x18, 0x3C, 0x3C, 0x18, 0x18, 0x00, 0x18, 0x00], // '!'
+    [0x36, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '"'
+    [0x36, 0x36, 0x7F, 0x36, 0x7F, 0x36, 0x36, 0x00], // '#'
+    [0x0C, 0x3E, 0x03, 0x1E, 0x30, 0x1F, 0x0C, 0x00], // '$'
+    [0x00, 0x63, 0x33, 0x18, 0x0C, 0x66, 0x63, 0x00], // '%'
+    [0x1C, 0x36, 0x1C, 0x6E, 0x3B, 0x33, 0x6E, 0x00], // '&'
+    [0x06, 0x06, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00], // "'"
+    [0x18, 0x0C, 0x06, 0x06, 0x06, 0x0C, 0x18, 0x00], // '('
+    [0x06, 0x0C, 0x18, 0x18, 0x18, 0x0C, 0x06, 0x00], // ')'
+    [0x00, 0x66, 0x3C, 0xFF, 0x3C, 0x66, 0x00, 0x00], // '*'
+    [0x00, 0x0C, 0x0C, 0x3F, 0x0C, 0x0C, 0x00, 0x00], // '+'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x06], // ','
+    [0x00, 0x00, 0x00, 0x3F, 0x00, 0x00, 0x00, 0x00], // '-'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x00], // '.'
+    [0x60, 0x30, 0x18, 0x0C, 0x06, 0x03, 0x01, 0x00], // '/'
+    [0x3E, 0x63, 0x73, 0x7B, 0x6F, 0x67, 0x3E, 0x00], // '0'
+    [0x0C, 0x0E, 0x0C, 0x0C, 0x0C, 0x0C, 0x3F, 0x00], // '1'
+    [0x1E, 0x33, 0x30, 0x1C, 0x06, 0x33, 0x3F, 0x00], // '2'
+    [0x1E, 0x33, 0x30, 0x1C, 0x30, 0x33, 0x1E, 0x00], // '3'
+    [0x38, 0x3C, 0x36, 0x33, 0x7F, 0x30, 0x78, 0x00], // '4'
+    [0x3F, 0x03, 0x1F, 0x30, 0x30, 0x33, 0x1E, 0x00], // '5'
+    [0x1C, 0x06, 0x03, 0x1F, 0x33, 0x33, 0x1E, 0x00], // '6'
+    [0x3F, 0x33, 0x30, 0x18, 0x0C, 0x0C, 0x0C, 0x00], // '7'
+    [0x1E, 0x33, 0x33, 0x1E, 0x33, 0x33, 0x1E, 0x00], // '8'
+    [0x1E, 0x33, 0x33, 0x3E, 0x30, 0x18, 0x0E, 0x00], // '9'
+    [0x00, 0x0C, 0x0C, 0x00, 0x00, 0x0C, 0x0C, 0x00], // ':'
+    [0x00, 0x0C, 0x0C, 0x00, 0x00, 0x0C, 0x0C, 0x06], // ';'
+    [0x18, 0x0C, 0x06, 0x03, 0x06, 0x0C, 0x18, 0x00], // '<'
+    [0x00, 0x00, 0x3F, 0x00, 0x00, 0x3F, 0x00, 0x00], // '='
+    [0x06, 0x0C, 0x18, 0x30, 0x18, 0x0C, 0x06, 0x00], // '>'
+    [0x1E, 0x33, 0x30, 0x18, 0x0C, 0x00, 0x0C, 0x00], // '?'
+    [0x3E, 0x63, 0x7B, 0x7B, 0x7B, 0x03, 0x1E, 0x00], // '@'
+    [0x0C, 0x1E, 0x33, 0x33, 0x3F, 0x33, 0x33, 0x00], // 'A'
+    [0x3F, 0x66, 0x66, 0x3E, 0x66, 0x66, 0x3F, 0x00], // 'B'
+    [0x3C, 0x66, 0x03, 0x03, 0x03, 0x66, 0x3C, 0x00], // 'C'
+    [0x1F, 0x36, 0x66, 0x66, 0x66, 0x36, 0x1F, 0x00], // 'D'
+    [0x7F, 0x46, 0x16, 0x1E, 0x16, 0x46, 0x7F, 0x00], // 'E'
+    [0x7F, 0x46, 0x16, 0x1E, 0x16, 0x06, 0x0F, 0x00], // 'F'
+    [0x3C, 0x66, 0x03, 0x03, 0x73, 0x66, 0x7C, 0x00], // 'G'
+    [0x33, 0x33, 0x33, 0x3F, 0x33, 0x33, 0x33, 0x00], // 'H'
+    [0x1E, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // 'I'
+    [0x78, 0x30, 0x30, 0x30, 0x33, 0x33, 0x1E, 0x00], // 'J'
+    [0x67, 0x66, 0x36, 0x1E, 0x36, 0x66, 0x67, 0x00], // 'K'
+    [0x0F, 0x06, 0x06, 0x06, 0x46, 0x66, 0x7F, 0x00], // 'L'
+    [0x63, 0x77, 0x7F, 0x7F, 0x6B, 0x63, 0x63, 0x00], // 'M'
+    [0x63, 0x67, 0x6F, 0x7B, 0x73, 0x63, 0x63, 0x00], // 'N'
+    [0x1C, 0x36, 0x63, 0x63, 0x63, 0x36, 0x1C, 0x00], // 'O'
+    [0x3F, 0x66, 0x66, 0x3E, 0x06, 0x06, 0x0F, 0x00], // 'P'
+    [0x1E, 0x33, 0x33, 0x33, 0x3B, 0x1E, 0x38, 0x00], // 'Q'
+    [0x3F, 0x66, 0x66, 0x3E, 0x36, 0x66, 0x67, 0x00], // 'R'
+    [0x1E, 0x33, 0x07, 0x0E, 0x38, 0x33, 0x1E, 0x00], // 'S'
+    [0x3F, 0x2D, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // 'T'
+    [0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x3F, 0x00], // 'U'
+    [0x33, 0x33, 0x33, 0x33, 0x33, 0x1E, 0x0C, 0x00], // 'V'
+    [0x63, 0x63, 0x63, 0x6B, 0x7F, 0x77, 0x63, 0x00], // 'W'
+    [0x63, 0x63, 0x36, 0x1C, 0x1C, 0x36, 0x63, 0x00], // 'X'
+    [0x33, 0x33, 0x33, 0x1E, 0x0C, 0x0C, 0x1E, 0x00], // 'Y'
+    [0x7F, 0x63, 0x31, 0x18, 0x4C, 0x66, 0x7F, 0x00], // 'Z'
+    [0x1E, 0x06, 0x06, 0x06, 0x06, 0x06, 0x1E, 0x00], // '['
+    [0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x40, 0x00], // '\\'
+    [0x1E, 0x18, 0x18, 0x18, 0x18, 0x18, 0x1E, 0x00], // ']'
+    [0x08, 0x1C, 0x36, 0x63, 0x00, 0x00, 0x00, 0x00], // '^'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF], // '_'
+    [0x0C, 0x0C, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00], // '`'
+    [0x00, 0x00, 0x1E, 0x30, 0x3E, 0x33, 0x6E, 0x00], // 'a'
+    [0x07, 0x06, 0x06, 0x3E, 0x66, 0x66, 0x3B, 0x00], // 'b'
+    [0x00, 0x00, 0x1E, 0x33, 0x03, 0x33, 0x1E, 0x00], // 'c'
+    [0x38, 0x30, 0x30, 0x3E, 0x33, 0x33, 0x6E, 0x00], // 'd'
+    [0x00, 0x00, 0x1E, 0x33, 0x3F, 0x03, 0x1E, 0x00], // 'e'
+    [0x1C, 0x36, 0x06, 0x0F, 0x06, 0x06, 0x0F, 0x00], // 'f'
+    [0x00, 0x00, 0x6E, 0x33, 0x33, 0x3E, 0x30, 0x1F], // 'g'
+    [0x07, 0x06, 0x36, 0x6E, 0x66, 0x66, 0x67, 0x00], // 'h'
+    [0x0C, 0x00, 0x0E, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // 'i'
+    [0x30, 0x00, 0x30, 0x30, 0x30, 0x33, 0x33, 0x1E], // 'j'
+    [0x07, 0x06, 0x66, 0x36, 0x1E, 0x36, 0x67, 0x00], // 'k'
+    [0x0E, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // 'l'
+    [0x00, 0x00, 0x33, 0x7F, 0x7F, 0x6B, 0x63, 0x00], // 'm'
+    [0x00, 0x00, 0x1F, 0x33, 0x33, 0x33, 0x33, 0x00], // 'n'
+    [0x00, 0x00, 0x1E, 0x33, 0x33, 0x33, 0x1E, 0x00], // 'o'
+    [0x00, 0x00, 0x3B, 0x66, 0x66, 0x3E, 0x06, 0x0F], // 'p'
+    [0x00, 0x00, 0x6E, 0x33, 0x33, 0x3E, 0x30, 0x78], // 'q'
+    [0x00, 0x00, 0x3B, 0x6E, 0x66, 0x06, 0x0F, 0x00], // 'r'
+    [0x00, 0x00, 0x3E, 0x03, 0x1E, 0x30, 0x1F, 0x00], // 's'
+    [0x08, 0x0C, 0x3E, 0x0C, 0x0C, 0x2C, 0x18, 0x00], // 't'
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x33, 0x6E, 0x00], // 'u'
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x1E, 0x0C, 0x00], // 'v'
+    [0x00, 0x00, 0x63, 0x6B, 0x7F, 0x7F, 0x36, 0x00], // 'w'
+    [0x00, 0x00, 0x63, 0x36, 0x1C, 0x36, 0x63, 0x00], // 'x'
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x3E, 0x30, 0x1F], // 'y'
+    [0x00, 0x00, 0x3F, 0x19, 0x0C, 0x26, 0x3F, 0x00], // 'z'
+    [0x38, 0x0C, 0x0C, 0x07, 0x0C, 0x0C, 0x38, 0x00], // '{'
+    [0x18, 0x18, 0x18, 0x00, 0x18, 0x18, 0x18, 0x00], // '|'
+    [0x07, 0x0C, 0x0C, 0x38, 0x0C, 0x0C, 0x07, 0x00], // '}'
+    [0x6E, 0x3B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '~'
+  ];
+
+  /// Returns the [FONT_8X8] glyph for `c`, or the glyph for `'?'` if `c` isn't
+  /// printable ASCII.
+  pub fn glyph(c: char) -> &'static [u8; GLYPH_HEIGHT] {
+    match c {
+      ' '..='~' => &FONT_8X8[c as usize - ' ' as usize],
+      _ => &FONT_8X8['?' as usize - ' ' as usize],
+    }
   }
 }
 
@@ -86,8 +693,8 @@ mod err {
   pub struct FrameBufferError(pub(crate) ());
 
   impl From<FrameBufferError> for CoreError {
-    fn from(_: FrameBufferError) -> Self {
-      CoreError::new()
+    fn from(value: FrameBufferError) -> Self {
+      CoreError::with_source(value)
     }
   }
 }
@@ -281,6 +888,19 @@ mod packed {
       Ok(Self { buffer, width })
     }
 
+    /// Like [SliceFrameBuffer::with_width], but doesn't check the dimensions.
+    ///
+    /// # Safety
+    /// The buffer length must be a multiple of `width`, and the height must
+    /// fit in a `u16`.
+    pub(crate) unsafe fn with_width_unchecked(buffer: T, width: u16) -> Self {
+      debug_assert!(
+        width == 0 || buffer.as_ref().len() % width as usize == 0,
+        "buffer doesn't have a whole number of rows"
+      );
+      Self { buffer, width }
+    }
+
     /// Returns a shared reference to the underlying pixel buffer.
     pub fn buffer(&self) -> &T {
       &self.buffer
@@ -292,6 +912,29 @@ mod packed {
     pub fn into_inner(self) -> T {
       self.buffer
     }
+
+    /// Iterates over the rows of pixels in the order they should be uploaded:
+    /// top to bottom, or bottom to top if `bottom_left` is true (i.e. the
+    /// frame has a bottom-left origin, as in OpenGL). Unlike flipping the
+    /// buffer, this doesn't allocate.
+    ///
+    /// # Examples
+    /// ```
+    /// use libretro_rs::prelude::*;
+    /// let pixels = [1, 2, 3, 4].map(XRGB8888::new_with_raw_value);
+    /// let buffer = SliceFrameBuffer::with_width(&pixels[..], 2).unwrap();
+    /// let rows: Vec<_> = buffer.upload_rows(true).collect();
+    /// assert_eq!(rows, [&pixels[2..], &pixels[..2]]);
+    /// ```
+    pub fn upload_rows(&self, bottom_left: bool) -> impl Iterator<Item = &[T::Pixel]> {
+      let pixels = self.buffer.as_ref();
+      let width = self.width as usize;
+      let height = self.height() as usize;
+      (0..height).map(move |i| {
+        let y = if bottom_left { height - 1 - i } else { i };
+        &pixels[y * width..(y + 1) * width]
+      })
+    }
   }
 
   unsafe impl<T> FrameBuffer for SliceFrameBuffer<T>
@@ -361,6 +1004,125 @@ mod packed {
   }
 }
 
+#[cfg(feature = "image")]
+mod rgba_image {
+  use super::{FrameBufferError, SliceFrameBuffer};
+  use crate::retro::pixel::format::Format;
+
+  impl<P: Format> SliceFrameBuffer<Vec<P>> {
+    /// Creates a frame buffer from an [`image::RgbaImage`], converting its
+    /// pixels to `P`, so rendering code can draw on images loaded by tools
+    /// built on the `image` crate. Alpha is discarded. Returns an error if the
+    /// image is empty or doesn't fit in a frame buffer.
+    ///
+    /// See [`PackedFrameBuffer::to_rgba_image`](super::PackedFrameBuffer::to_rgba_image)
+    /// for the reverse conversion.
+    pub fn from_rgba_image(image: &image::RgbaImage) -> Result<Self, FrameBufferError> {
+      let width = match u16::try_from(image.width()) {
+        Ok(width) if width > 0 => width,
+        _ => return Err(FrameBufferError(())),
+      };
+      let pixels = image
+        .pixels()
+        .map(|&image::Rgba([r, g, b, _])| P::from_rgb888([r, g, b]))
+        .collect();
+      Self::with_width(pixels, width)
+    }
+  }
+}
+
+pub use any::AnyFrameBuffer;
+mod any {
+  use super::{FrameBuffer, FrameBufferError};
+  use crate::retro::pixel::format::{AnyFormat, Format};
+  use std::marker::PhantomData;
+
+  /// A frame buffer whose pixel format is only known at runtime, described by
+  /// an [AnyFormat]. Can be uploaded with
+  /// [`Callbacks::upload_video_frame_any`](crate::retro::Callbacks::upload_video_frame_any)
+  /// without making the core generic over the pixel type.
+  #[derive(Clone, Copy, Debug)]
+  pub struct AnyFrameBuffer<'a> {
+    format: &'a AnyFormat,
+    data: &'a [u8],
+    width: u16,
+    height: u16,
+    pitch: usize,
+  }
+
+  impl<'a> AnyFrameBuffer<'a> {
+    /// Wraps `data`, which holds `height` rows of `pitch` bytes, each starting
+    /// with `width` pixels in the given format. Returns an error if `data`
+    /// doesn't have exactly that shape.
+    pub fn new(
+      format: &'a AnyFormat,
+      data: &'a [u8],
+      width: u16,
+      height: u16,
+      pitch: usize,
+    ) -> Result<Self, FrameBufferError> {
+      let row_len = width as usize * format.bytes_per_pixel();
+      if row_len > pitch || pitch.checked_mul(height as usize) != Some(data.len()) {
+        return Err(FrameBufferError(()));
+      }
+      Ok(Self { format, data, width, height, pitch })
+    }
+
+    /// Returns the pixel format of the buffer.
+    pub fn format(&self) -> &'a AnyFormat {
+      self.format
+    }
+
+    /// Returns the frame buffer data.
+    pub fn data(&self) -> &'a [u8] {
+      self.data
+    }
+
+    /// Returns the width of the frame buffer, in pixels.
+    pub fn width(&self) -> u16 {
+      self.width
+    }
+
+    /// Returns the height of the frame buffer, in pixels.
+    pub fn height(&self) -> u16 {
+      self.height
+    }
+
+    /// Returns the width of the frame buffer, in bytes.
+    pub fn pitch(&self) -> usize {
+      self.pitch
+    }
+
+    /// Views the buffer as a [FrameBuffer] of `P` pixels. `P` must match
+    /// [`AnyFrameBuffer::format`].
+    pub(crate) fn typed<P: Format>(&self) -> TypedFrameBuffer<'_, P> {
+      TypedFrameBuffer(self, PhantomData)
+    }
+  }
+
+  pub(crate) struct TypedFrameBuffer<'a, P>(&'a AnyFrameBuffer<'a>, PhantomData<P>);
+
+  unsafe impl<'a, P: Format> FrameBuffer for TypedFrameBuffer<'a, P> {
+    type Pixel = P;
+
+    fn data(&self) -> &[u8] {
+      self.0.data
+    }
+
+    fn width(&self) -> u16 {
+      self.0.width
+    }
+
+    fn height(&self) -> u16 {
+      self.0.height
+    }
+
+    fn pitch(&self) -> usize {
+      self.0.pitch
+    }
+  }
+}
+
 fn as_bytes<T>(slice: &[T]) -> &[u8] {
   // Safety: Aligning to u8 will always succeed since the size of a type is
   // always a multiple of its alignment. u8 having a size of 1 byte implies an
@@ -431,3 +1193,171 @@ mod pixel_buffer {
     type Pixel = P;
   }
 }
+
+pub use dirty::DirtyTracked;
+mod dirty {
+  use std::ops::{Deref, DerefMut};
+
+  /// Wraps a value (typically a frame buffer) and tracks whether it has been
+  /// mutably accessed since it was last marked clean. Cores can use this to
+  /// skip uploading unchanged frames, and call
+  /// [`Callbacks::repeat_video_frame`](crate::retro::Callbacks::repeat_video_frame)
+  /// instead when the frontend supports it
+  /// (see [`Environment::get_can_dupe`](crate::retro::env::Environment::get_can_dupe)).
+  ///
+  /// Any mutable access counts as a change, even if no data is actually
+  /// modified. New values start out dirty.
+  ///
+  /// # Examples
+  /// ```
+  /// use libretro_rs::prelude::*;
+  /// let mut buf = DirtyTracked::new(ArrayFrameBuffer::<XRGB8888, 4, 2>::default());
+  /// assert!(buf.take_dirty());
+  /// assert!(!buf.is_dirty());
+  /// buf[0] = XRGB8888::new_with_raw_value(0x00FF0000);
+  /// assert!(buf.take_dirty());
+  /// ```
+  #[derive(Clone, Debug)]
+  pub struct DirtyTracked<T> {
+    value: T,
+    dirty: bool,
+  }
+
+  impl<T> DirtyTracked<T> {
+    pub fn new(value: T) -> Self {
+      Self { value, dirty: true }
+    }
+
+    /// Returns true if the value may have changed since it was last marked
+    /// clean.
+    pub fn is_dirty(&self) -> bool {
+      self.dirty
+    }
+
+    /// Marks the value as changed without accessing it.
+    pub fn mark_dirty(&mut self) {
+      self.dirty = true
+    }
+
+    /// Returns whether the value is dirty and marks it clean.
+    pub fn take_dirty(&mut self) -> bool {
+      std::mem::take(&mut self.dirty)
+    }
+
+    /// Consumes this wrapper and returns the value.
+    pub fn into_inner(self) -> T {
+      self.value
+    }
+  }
+
+  impl<T> Deref for DirtyTracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+      &self.value
+    }
+  }
+
+  impl<T> DerefMut for DirtyTracked<T> {
+    fn deref_mut(&mut self) -> &mut T {
+      self.dirty = true;
+      &mut self.value
+    }
+  }
+
+  impl<T> From<T> for DirtyTracked<T> {
+    fn from(value: T) -> Self {
+      Self::new(value)
+    }
+  }
+}
+
+pub(crate) use composite::CompositeFrame;
+mod composite {
+  use super::FrameBuffer;
+  use crate::retro::pixel::format::Format;
+  use std::marker::PhantomData;
+  use std::mem;
+
+  /// A tightly packed copy of the last frame uploaded to the frontend, which
+  /// subframes are composited into, since `retro_video_refresh_t` can only
+  /// upload whole frames.
+  #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+  pub(crate) struct CompositeFrame {
+    data: Vec<u8>,
+    width: u16,
+    height: u16,
+    bytes_per_pixel: usize,
+  }
+
+  impl CompositeFrame {
+    /// Resizes the frame to `width` by `height` pixels of `P`. The frame is
+    /// cleared if its shape changed.
+    pub fn resize<P: Format>(&mut self, width: u16, height: u16) {
+      let bytes_per_pixel = mem::size_of::<P>();
+      if (self.width, self.height, self.bytes_per_pixel) != (width, height, bytes_per_pixel) {
+        let len = width as usize * height as usize * bytes_per_pixel;
+        *self = Self { data: vec![0; len], width, height, bytes_per_pixel };
+      }
+    }
+
+    /// Copies `framebuffer` into the frame with its top left corner at
+    /// (`x`, `y`). Pixels outside of the frame are skipped.
+    pub fn blit<F: FrameBuffer>(&mut self, framebuffer: &F, (x, y): (u16, u16)) {
+      debug_assert_eq!(mem::size_of::<F::Pixel>(), self.bytes_per_pixel);
+      let row_len =
+        framebuffer.width().min(self.width.saturating_sub(x)) as usize * self.bytes_per_pixel;
+      let rows = framebuffer.height().min(self.height.saturating_sub(y)) as usize;
+      if row_len == 0 || rows == 0 {
+        return;
+      }
+      let pitch = self.pitch();
+      let offset = x as usize * self.bytes_per_pixel;
+      let src_rows = framebuffer.data().chunks(framebuffer.pitch());
+      let dst_rows = self.data.chunks_exact_mut(pitch).skip(y as usize);
+      for (dst, src) in dst_rows.zip(src_rows).take(rows) {
+        dst[offset..offset + row_len].copy_from_slice(&src[..row_len]);
+      }
+    }
+
+    /// Sets every pixel of the frame, which must have been resized for `P`, to
+    /// `color`.
+    pub fn fill<P: Format>(&mut self, color: &P) {
+      debug_assert_eq!(mem::size_of::<P>(), self.bytes_per_pixel);
+      let color = super::as_bytes(core::slice::from_ref(color));
+      for pixel in self.data.chunks_exact_mut(self.bytes_per_pixel) {
+        pixel.copy_from_slice(color);
+      }
+    }
+
+    /// The pitch of the frame, which is always `width * size_of::<P>()`,
+    /// whatever the pitch of the frame buffers that were copied into it.
+    pub fn pitch(&self) -> usize {
+      self.width as usize * self.bytes_per_pixel
+    }
+
+    /// Views the frame as a [FrameBuffer] of `P` pixels, which must be the
+    /// type it was last resized for.
+    pub fn typed<P: Format>(&self) -> TypedCompositeFrame<'_, P> {
+      TypedCompositeFrame(self, PhantomData)
+    }
+  }
+
+  pub(crate) struct TypedCompositeFrame<'a, P>(&'a CompositeFrame, PhantomData<P>);
+
+  unsafe impl<'a, P: Format> FrameBuffer for TypedCompositeFrame<'a, P> {
+    type Pixel = P;
+
+    fn data(&self) -> &[u8] {
+      &self.0.data
+    }
+
+    fn width(&self) -> u16 {
+      self.0.width
+    }
+
+    fn height(&self) -> u16 {
+      self.0.height
+    }
+  }
+}
@@ -0,0 +1,121 @@
+//! Reads the sticks and triggers of the analog device from the `run` of a core
+//! defined in this test, through the symbols exported by [`libretro_core`].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// Every `(port, device, index, id)` queried by the core, in order.
+static QUERIES: Mutex<Vec<(c_uint, c_uint, c_uint, c_uint)>> = Mutex::new(Vec::new());
+
+/// The values read by the core, in order.
+static VALUES: Mutex<Vec<i16>> = Mutex::new(Vec::new());
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("analog test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let inputs_polled = callbacks.poll_inputs();
+    let port = DevicePort::new(1);
+    let mut values = VALUES.lock().unwrap();
+    for axis in [
+      AnalogAxis::LeftX,
+      AnalogAxis::LeftY,
+      AnalogAxis::RightX,
+      AnalogAxis::RightY,
+    ] {
+      values.push(callbacks.analog_state(port, axis));
+    }
+    values.push(callbacks.analog_button_state(port, JoypadButton::L2));
+    values.push(callbacks.analog_button_state(port, JoypadButton::R2));
+    inputs_polled
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn input_state(port: c_uint, device: c_uint, index: c_uint, id: c_uint) -> i16 {
+  QUERIES.lock().unwrap().push((port, device, index, id));
+  // The extremes of the range are passed through unchanged.
+  match (index, id) {
+    (RETRO_DEVICE_INDEX_ANALOG_LEFT, RETRO_DEVICE_ID_ANALOG_X) => i16::MIN,
+    (RETRO_DEVICE_INDEX_ANALOG_LEFT, RETRO_DEVICE_ID_ANALOG_Y) => i16::MAX,
+    (RETRO_DEVICE_INDEX_ANALOG_RIGHT, RETRO_DEVICE_ID_ANALOG_X) => -1,
+    (RETRO_DEVICE_INDEX_ANALOG_RIGHT, RETRO_DEVICE_ID_ANALOG_Y) => 1,
+    (RETRO_DEVICE_INDEX_ANALOG_BUTTON, RETRO_DEVICE_ID_JOYPAD_L2) => 0x4000,
+    _ => 0,
+  }
+}
+
+#[test]
+fn sticks_and_triggers_are_read_from_the_analog_device() {
+  unsafe {
+    start(env::null_environment);
+    retro_set_input_state(input_state);
+    retro_run();
+    stop();
+  }
+  assert_eq!(
+    *VALUES.lock().unwrap(),
+    [i16::MIN, i16::MAX, -1, 1, 0x4000, 0]
+  );
+  let analog = RETRO_DEVICE_ANALOG;
+  let button = RETRO_DEVICE_INDEX_ANALOG_BUTTON;
+  assert_eq!(
+    *QUERIES.lock().unwrap(),
+    [
+      (
+        1,
+        analog,
+        RETRO_DEVICE_INDEX_ANALOG_LEFT,
+        RETRO_DEVICE_ID_ANALOG_X
+      ),
+      (
+        1,
+        analog,
+        RETRO_DEVICE_INDEX_ANALOG_LEFT,
+        RETRO_DEVICE_ID_ANALOG_Y
+      ),
+      (
+        1,
+        analog,
+        RETRO_DEVICE_INDEX_ANALOG_RIGHT,
+        RETRO_DEVICE_ID_ANALOG_X
+      ),
+      (
+        1,
+        analog,
+        RETRO_DEVICE_INDEX_ANALOG_RIGHT,
+        RETRO_DEVICE_ID_ANALOG_Y
+      ),
+      (1, analog, button, RETRO_DEVICE_ID_JOYPAD_L2),
+      (1, analog, button, RETRO_DEVICE_ID_JOYPAD_R2),
+    ]
+  );
+}
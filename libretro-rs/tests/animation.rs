@@ -0,0 +1,44 @@
+//! Drives the `animation` example through the stub frontend and checks that
+//! its output changes from frame to frame.
+
+mod common;
+
+use common::*;
+use libloading::{Library, Symbol};
+
+const WIDTH: usize = 320;
+const RECT_SIZE: usize = 32;
+const RECT_COLOR: u32 = 0x00FFFFFF;
+
+/// Returns the top left corner of the (white) rectangle in the last frame. The
+/// frame counter may be drawn over the top of the rectangle, so its position is
+/// derived from the bottom right corner.
+fn rect_position() -> (usize, usize) {
+  let frame = last_frame();
+  assert_eq!((frame.width, frame.height), (WIDTH as u32, 240));
+  let index = frame
+    .xrgb8888()
+    .iter()
+    .rposition(|&pixel| pixel == RECT_COLOR)
+    .expect("no rectangle in the frame");
+  (index % WIDTH + 1 - RECT_SIZE, index / WIDTH + 1 - RECT_SIZE)
+}
+
+#[test]
+fn animation_core_moves_rectangle_between_frames() {
+  let path = build_example("animation");
+  unsafe {
+    let lib = Library::new(&path).expect("failed to load the example core");
+    start_example(&lib);
+
+    let run: Symbol<unsafe extern "C" fn()> = lib.get(b"retro_run").unwrap();
+    run();
+    assert_eq!(rect_position(), (0, 0));
+    run();
+    assert_eq!(rect_position(), (3, 2));
+    run();
+    assert_eq!(rect_position(), (6, 4));
+
+    stop_example(&lib);
+  }
+}
@@ -0,0 +1,128 @@
+//! Uploads frames in a pixel format picked at runtime, through the symbols
+//! exported by [`libretro_core`] for a core defined in this test.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Mutex;
+
+const WIDTH: u16 = 2;
+const HEIGHT: u16 = 3;
+
+/// The format the core will request, and the one the frontend accepted.
+static REQUESTED_FORMAT: AtomicI32 = AtomicI32::new(0);
+static ACTIVE_FORMAT: AtomicI32 = AtomicI32::new(-1);
+
+/// Every frame uploaded by the core, as `(data, width, height, pitch)`.
+static FRAMES: Mutex<Vec<(Vec<u8>, c_uint, c_uint, usize)>> = Mutex::new(Vec::new());
+
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  format: AnyFormat,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("any format test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let format = match REQUESTED_FORMAT.load(Ordering::SeqCst) {
+      1 => env
+        .set_pixel_format_xrgb8888(pixel_format)
+        .map_err(|_| CoreError::new())?
+        .into(),
+      2 => env
+        .set_pixel_format_rgb565(pixel_format)
+        .map_err(|_| CoreError::new())?
+        .into(),
+      _ => pixel_format.into(),
+    };
+    Ok(Core { rendering_mode, format })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(WIDTH, HEIGHT))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    // Pad each row by a byte to make sure the pitch is passed through.
+    let pitch = WIDTH as usize * self.format.bytes_per_pixel() + 1;
+    let data: Vec<u8> = (0..pitch * HEIGHT as usize).map(|i| i as u8).collect();
+    if let Ok(framebuffer) = AnyFrameBuffer::new(&self.format, &data, WIDTH, HEIGHT, pitch) {
+      callbacks.upload_video_frame_any(&self.rendering_mode, &framebuffer);
+    }
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_SET_PIXEL_FORMAT => {
+      ACTIVE_FORMAT.store(*(data as *const i32), Ordering::SeqCst);
+      true
+    }
+    _ => false,
+  }
+}
+
+unsafe extern "C" fn video_refresh(
+  data: *const c_void,
+  width: c_uint,
+  height: c_uint,
+  pitch: usize,
+) {
+  let data = core::slice::from_raw_parts(data as *const u8, pitch * height as usize);
+  FRAMES
+    .lock()
+    .unwrap()
+    .push((data.to_vec(), width, height, pitch));
+}
+
+#[test]
+fn frames_are_uploaded_in_each_runtime_format() {
+  use retro_pixel_format::*;
+  let cases = [
+    (0, None, 2),
+    (1, Some(RETRO_PIXEL_FORMAT_XRGB8888), 4),
+    (2, Some(RETRO_PIXEL_FORMAT_RGB565), 2),
+  ];
+  unsafe {
+    init(environment);
+    retro_set_video_refresh(video_refresh);
+    for (requested, active, bytes_per_pixel) in cases {
+      REQUESTED_FORMAT.store(requested, Ordering::SeqCst);
+      ACTIVE_FORMAT.store(-1, Ordering::SeqCst);
+      assert!(retro_load_game(core::ptr::null()));
+      retro_run();
+      retro_unload_game();
+
+      assert_eq!(
+        ACTIVE_FORMAT.load(Ordering::SeqCst),
+        active.map_or(-1, |format| format as i32)
+      );
+      let pitch = WIDTH as usize * bytes_per_pixel + 1;
+      let (data, width, height, frame_pitch) = FRAMES.lock().unwrap().pop().unwrap();
+      assert_eq!((width, height, frame_pitch), (2, 3, pitch));
+      assert_eq!(data, (0..pitch * 3).map(|i| i as u8).collect::<Vec<_>>());
+    }
+    retro_deinit();
+  }
+}
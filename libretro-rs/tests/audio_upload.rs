@@ -0,0 +1,80 @@
+//! Uploads audio directly from the `run` of a core defined in this test,
+//! through the symbols exported by [`libretro_core`].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// Every batch of samples received by the frontend, in order.
+static BATCHES: Mutex<Vec<Vec<i16>>> = Mutex::new(Vec::new());
+
+/// Every single frame received by the frontend, in order.
+static SAMPLES: Mutex<Vec<(i16, i16)>> = Mutex::new(Vec::new());
+
+/// The number of frames consumed by each call to `upload_audio_frames`.
+static CONSUMED: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("audio upload test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let mut consumed = CONSUMED.lock().unwrap();
+    consumed.push(callbacks.upload_audio_frames(&[1, -1, 2, -2]));
+    // The trailing sample doesn't make a frame, so it's ignored.
+    consumed.push(callbacks.upload_audio_frames(&[3, -3, 4]));
+    callbacks.upload_audio_sample(5, -5);
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn audio_sample(left: i16, right: i16) {
+  SAMPLES.lock().unwrap().push((left, right));
+}
+
+unsafe extern "C" fn audio_sample_batch(data: *const i16, frames: usize) -> usize {
+  let samples = core::slice::from_raw_parts(data, frames * 2);
+  BATCHES.lock().unwrap().push(samples.to_vec());
+  frames
+}
+
+#[test]
+fn interleaved_samples_are_uploaded_in_frames() {
+  unsafe {
+    start(env::null_environment);
+    retro_set_audio_sample(audio_sample);
+    retro_set_audio_sample_batch(audio_sample_batch);
+    retro_run();
+    stop();
+  }
+  assert_eq!(*BATCHES.lock().unwrap(), [vec![1, -1, 2, -2], vec![3, -3]]);
+  assert_eq!(*CONSUMED.lock().unwrap(), [2, 1]);
+  assert_eq!(*SAMPLES.lock().unwrap(), [(5, -5)]);
+}
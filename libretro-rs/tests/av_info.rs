@@ -0,0 +1,100 @@
+//! Tests for the validation done by [`SystemAVInfoBuilder`], and for building
+//! [`GameGeometry`].
+
+use libretro_rs::prelude::*;
+
+fn builder() -> SystemAVInfoBuilder {
+  SystemAVInfo::builder(GameGeometry::fixed(256, 224))
+}
+
+#[test]
+fn valid_av_info_is_built() {
+  let av_info = builder().fps(50.0).sample_rate(32_000.0).build().unwrap();
+  assert_eq!(av_info.timing().fps(), 50.0);
+  assert_eq!(av_info.timing().sample_rate(), 32_000.0);
+  assert_eq!(av_info.geometry().base_width(), 256);
+  // The default timing is valid too.
+  assert!(builder().build().is_ok());
+}
+
+#[test]
+fn zero_fps_is_rejected() {
+  assert_eq!(
+    builder().fps(0.0).build().unwrap_err(),
+    SystemAVInfoError::InvalidFps(0.0)
+  );
+  assert_eq!(
+    builder().fps(-60.0).build().unwrap_err(),
+    SystemAVInfoError::InvalidFps(-60.0)
+  );
+  assert!(matches!(
+    builder().fps(f64::NAN).build(),
+    Err(SystemAVInfoError::InvalidFps(_))
+  ));
+}
+
+#[test]
+fn zero_sample_rate_is_rejected() {
+  let err = builder()
+    .timing(SystemTiming::new(60.0, 0.0))
+    .build()
+    .unwrap_err();
+  assert_eq!(err, SystemAVInfoError::InvalidSampleRate(0.0));
+  let err = builder().sample_rate(f64::INFINITY).build().unwrap_err();
+  assert_eq!(err, SystemAVInfoError::InvalidSampleRate(f64::INFINITY));
+}
+
+#[test]
+fn insane_geometry_is_rejected() {
+  let build = |geometry| SystemAVInfo::builder(geometry).build().unwrap_err();
+  assert_eq!(
+    build(GameGeometry::fixed(0, 224)),
+    SystemAVInfoError::EmptyGeometry
+  );
+  assert_eq!(
+    build(GameGeometry::variable(320..=256, 224..=224)),
+    SystemAVInfoError::BaseExceedsMax
+  );
+  assert!(matches!(
+    build(GameGeometry::new(256..=256, 224..=224, f32::NAN)),
+    SystemAVInfoError::InvalidAspectRatio(_)
+  ));
+  // Non-positive aspect ratios ask the frontend to derive it.
+  let geometry = GameGeometry::new(256..=256, 224..=224, -1.0);
+  assert!(SystemAVInfo::builder(geometry).build().is_ok());
+}
+
+#[test]
+fn errors_convert_to_core_error() {
+  use std::error::Error;
+  let err = CoreError::from(builder().fps(0.0).build().unwrap_err());
+  assert!(err.source().unwrap().is::<SystemAVInfoError>());
+}
+
+#[test]
+fn geometry_is_built_fluently() {
+  let geometry = GameGeometry::fixed(320, 224)
+    .with_aspect_ratio(4.0 / 3.0)
+    .with_max_size(640, 480)
+    .into_inner();
+  assert_eq!((geometry.base_width, geometry.base_height), (320, 224));
+  assert_eq!((geometry.max_width, geometry.max_height), (640, 480));
+  assert_eq!(geometry.aspect_ratio, 4.0 / 3.0);
+
+  let geometry = GameGeometry::new(320..=640, 224..=480, 4.0 / 3.0).into_inner();
+  assert_eq!(
+    (geometry.base_width, geometry.max_width, geometry.max_height),
+    (320, 640, 480)
+  );
+}
+
+#[test]
+fn aspect_ratio_falls_back_to_base_size() {
+  let geometry = GameGeometry::fixed(256, 224).with_max_size(512, 448);
+  assert_eq!(geometry.aspect_ratio(), 0.0);
+  assert_eq!(geometry.effective_aspect_ratio(), 256.0 / 224.0);
+  let geometry = geometry.with_aspect_ratio(-1.0);
+  assert_eq!(geometry.effective_aspect_ratio(), 256.0 / 224.0);
+  let geometry = geometry.with_aspect_ratio(8.0 / 7.0);
+  assert_eq!(geometry.effective_aspect_ratio(), 8.0 / 7.0);
+}
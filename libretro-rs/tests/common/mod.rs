@@ -0,0 +1,254 @@
+//! A minimal stub frontend shared by the tests, which drives either an example
+//! core loaded with [start_example], or the core a test defines and exports
+//! with [test_core!].
+#![allow(dead_code)]
+
+use libloading::{Library, Symbol};
+use libretro_rs::ffi::*;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+pub static PIXEL_FORMAT: AtomicU32 = AtomicU32::new(u32::MAX);
+pub static SUPPORT_NO_GAME: AtomicBool = AtomicBool::new(false);
+pub static CAN_DUPE: AtomicBool = AtomicBool::new(false);
+pub static FRAMES: AtomicUsize = AtomicUsize::new(0);
+pub static DUPED_FRAMES: AtomicUsize = AtomicUsize::new(0);
+pub static JOYPAD_BUTTONS: AtomicU32 = AtomicU32::new(0);
+pub static INPUT_POLLS: AtomicUsize = AtomicUsize::new(0);
+pub static LAST_FRAME: Mutex<Option<Frame>> = Mutex::new(None);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+  pub width: c_uint,
+  pub height: c_uint,
+  pub pitch: usize,
+  pub data: Vec<u8>,
+}
+
+/// Stub implementation of [`retro_environment_t`]. Only the commands used by
+/// the examples are acknowledged; everything else is reported as unsupported.
+pub unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME => {
+      SUPPORT_NO_GAME.store(*(data as *const bool), Ordering::SeqCst);
+      true
+    }
+    RETRO_ENVIRONMENT_GET_CAN_DUPE => {
+      *(data as *mut bool) = CAN_DUPE.load(Ordering::SeqCst);
+      true
+    }
+    RETRO_ENVIRONMENT_SET_PIXEL_FORMAT => {
+      PIXEL_FORMAT.store(*(data as *const c_uint), Ordering::SeqCst);
+      true
+    }
+    _ => false,
+  }
+}
+
+/// Stub implementation of [`retro_video_refresh_t`]. Duped frames (i.e. with
+/// null data) are counted, but don't replace the last frame.
+pub unsafe extern "C" fn video_refresh(
+  data: *const c_void,
+  width: c_uint,
+  height: c_uint,
+  pitch: usize,
+) {
+  FRAMES.fetch_add(1, Ordering::SeqCst);
+  if data.is_null() {
+    DUPED_FRAMES.fetch_add(1, Ordering::SeqCst);
+    return;
+  }
+  let data = std::slice::from_raw_parts(data as *const u8, pitch * height as usize).to_vec();
+  *LAST_FRAME.lock().unwrap() = Some(Frame { width, height, pitch, data });
+}
+
+pub unsafe extern "C" fn audio_sample(_left: i16, _right: i16) {}
+
+pub unsafe extern "C" fn audio_sample_batch(_data: *const i16, frames: usize) -> usize {
+  frames
+}
+
+pub unsafe extern "C" fn input_poll() {
+  INPUT_POLLS.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Stub implementation of [`retro_input_state_t`]. Reports the joypad buttons
+/// in [JOYPAD_BUTTONS] (a bitmask of `RETRO_DEVICE_ID_JOYPAD_*`) as pressed on
+/// port 0; everything else is released.
+pub unsafe extern "C" fn input_state(
+  port: c_uint,
+  device: c_uint,
+  _index: c_uint,
+  id: c_uint,
+) -> i16 {
+  let pressed = port == 0
+    && device == RETRO_DEVICE_JOYPAD
+    && id < 32
+    && JOYPAD_BUTTONS.load(Ordering::SeqCst) & (1 << id) != 0;
+  pressed as i16
+}
+
+/// Builds the example with the same profile as this test and returns the path
+/// to the resulting dynamic library.
+pub fn build_example(example: &str) -> PathBuf {
+  let mut cmd = Command::new(env!("CARGO"));
+  cmd
+    .current_dir(env!("CARGO_MANIFEST_DIR"))
+    .args(["build", "--example", example]);
+  if !cfg!(debug_assertions) {
+    cmd.arg("--release");
+  }
+  let status = cmd.status().expect("failed to run cargo");
+  assert!(status.success(), "failed to build the {example} example");
+
+  // The test binary lives in `<target>/<profile>/deps`.
+  let exe = std::env::current_exe().unwrap();
+  let profile_dir = exe.parent().and_then(|deps| deps.parent()).unwrap();
+  let file_name = format!(
+    "{}{}{}",
+    std::env::consts::DLL_PREFIX,
+    example.replace('-', "_"),
+    std::env::consts::DLL_SUFFIX
+  );
+  profile_dir.join("examples").join(file_name)
+}
+
+impl Frame {
+  /// Returns the frame's pixels, assuming the XRGB8888 format.
+  pub fn xrgb8888(&self) -> Vec<u32> {
+    self
+      .data
+      .chunks_exact(4)
+      .map(|px| u32::from_ne_bytes(px.try_into().unwrap()))
+      .collect()
+  }
+}
+
+/// Returns the last frame passed to [video_refresh].
+pub fn last_frame() -> Frame {
+  LAST_FRAME
+    .lock()
+    .unwrap()
+    .clone()
+    .expect("no video frame was emitted")
+}
+
+/// Registers the stub callbacks, then initializes the core and loads it
+/// without content.
+///
+/// # Safety
+/// `lib` must be a libretro core.
+pub unsafe fn start_example(lib: &Library) {
+  let set_environment: Symbol<unsafe extern "C" fn(non_null_retro_environment_t)> =
+    lib.get(b"retro_set_environment").unwrap();
+  let set_video_refresh: Symbol<unsafe extern "C" fn(non_null_retro_video_refresh_t)> =
+    lib.get(b"retro_set_video_refresh").unwrap();
+  let set_audio_sample: Symbol<unsafe extern "C" fn(non_null_retro_audio_sample_t)> =
+    lib.get(b"retro_set_audio_sample").unwrap();
+  let set_audio_sample_batch: Symbol<unsafe extern "C" fn(non_null_retro_audio_sample_batch_t)> =
+    lib.get(b"retro_set_audio_sample_batch").unwrap();
+  let set_input_poll: Symbol<unsafe extern "C" fn(non_null_retro_input_poll_t)> =
+    lib.get(b"retro_set_input_poll").unwrap();
+  let set_input_state: Symbol<unsafe extern "C" fn(non_null_retro_input_state_t)> =
+    lib.get(b"retro_set_input_state").unwrap();
+
+  set_environment(environment);
+  assert!(SUPPORT_NO_GAME.load(Ordering::SeqCst));
+  set_video_refresh(video_refresh);
+  set_audio_sample(audio_sample);
+  set_audio_sample_batch(audio_sample_batch);
+  set_input_poll(input_poll);
+  set_input_state(input_state);
+
+  let init: Symbol<unsafe extern "C" fn()> = lib.get(b"retro_init").unwrap();
+  init();
+
+  let load_game: Symbol<unsafe extern "C" fn(*const retro_game_info) -> bool> =
+    lib.get(b"retro_load_game").unwrap();
+  assert!(load_game(core::ptr::null()));
+  assert_eq!(
+    PIXEL_FORMAT.load(Ordering::SeqCst),
+    retro_pixel_format::RETRO_PIXEL_FORMAT_XRGB8888 as c_uint
+  );
+}
+
+/// Unloads the game and deinitializes the core.
+///
+/// # Safety
+/// `lib` must be a libretro core that was started with [start_example].
+pub unsafe fn stop_example(lib: &Library) {
+  let unload_game: Symbol<unsafe extern "C" fn()> = lib.get(b"retro_unload_game").unwrap();
+  unload_game();
+  let deinit: Symbol<unsafe extern "C" fn()> = lib.get(b"retro_deinit").unwrap();
+  deinit();
+}
+
+/// Exports the `libretro` API for the `Core` defined at the root of the test,
+/// and defines `init`, `start` and `stop` to drive it with the stub callbacks.
+///
+/// The exported symbols are declared here rather than in this module, since
+/// the tests of the example cores don't define them.
+#[macro_export]
+macro_rules! test_core {
+  () => {
+    libretro_rs::libretro_core!(crate::Core);
+
+    #[allow(dead_code)]
+    extern "C" {
+      fn retro_set_environment(cb: libretro_rs::ffi::non_null_retro_environment_t);
+      fn retro_set_video_refresh(cb: libretro_rs::ffi::non_null_retro_video_refresh_t);
+      fn retro_set_audio_sample(cb: libretro_rs::ffi::non_null_retro_audio_sample_t);
+      fn retro_set_audio_sample_batch(cb: libretro_rs::ffi::non_null_retro_audio_sample_batch_t);
+      fn retro_set_input_poll(cb: libretro_rs::ffi::non_null_retro_input_poll_t);
+      fn retro_set_input_state(cb: libretro_rs::ffi::non_null_retro_input_state_t);
+      fn retro_init();
+      fn retro_deinit();
+      fn retro_get_system_info(info: *mut libretro_rs::ffi::retro_system_info);
+      fn retro_get_system_av_info(info: *mut libretro_rs::ffi::retro_system_av_info);
+      fn retro_load_game(game: *const libretro_rs::ffi::retro_game_info) -> bool;
+      fn retro_load_game_special(
+        game_type: ::core::ffi::c_uint,
+        info: *const libretro_rs::ffi::retro_game_info,
+        num_info: usize,
+      ) -> bool;
+      fn retro_unload_game();
+      fn retro_run();
+      fn retro_reset();
+      fn retro_serialize_size() -> usize;
+      fn retro_serialize(data: *mut (), size: usize) -> bool;
+      fn retro_unserialize(data: *const (), size: usize) -> bool;
+      fn retro_get_memory_data(id: ::core::ffi::c_uint) -> *mut ::core::ffi::c_void;
+      fn retro_get_memory_size(id: ::core::ffi::c_uint) -> usize;
+    }
+
+    /// Registers `environment` and the stub callbacks with the core, and
+    /// initializes it. Callbacks can be replaced afterwards, e.g. with
+    /// `retro_set_video_refresh`.
+    #[allow(dead_code)]
+    unsafe fn init(environment: libretro_rs::ffi::non_null_retro_environment_t) {
+      retro_set_environment(environment);
+      retro_set_video_refresh($crate::common::video_refresh);
+      retro_set_audio_sample($crate::common::audio_sample);
+      retro_set_audio_sample_batch($crate::common::audio_sample_batch);
+      retro_set_input_poll($crate::common::input_poll);
+      retro_set_input_state($crate::common::input_state);
+      retro_init();
+    }
+
+    /// Initializes the core, and loads it without content.
+    #[allow(dead_code)]
+    unsafe fn start(environment: libretro_rs::ffi::non_null_retro_environment_t) {
+      init(environment);
+      assert!(retro_load_game(::core::ptr::null()));
+    }
+
+    /// Unloads the game and deinitializes the core.
+    #[allow(dead_code)]
+    unsafe fn stop() {
+      retro_unload_game();
+      retro_deinit();
+    }
+  };
+}
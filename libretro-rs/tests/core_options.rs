@@ -0,0 +1,213 @@
+//! Tests for the value strings generated and parsed by [`IntOption`] and
+//! [`EnumOption`].
+
+use libretro_rs::prelude::*;
+
+fn frameskip() -> IntOption {
+  IntOption::new("test_frameskip", "Frameskip", 0, 5).default(1)
+}
+
+#[test]
+fn int_option_lists_default_value_first() {
+  assert_eq!(frameskip().values(), ["1", "0", "2", "3", "4", "5"]);
+  assert_eq!(
+    frameskip().to_value_string().to_str(),
+    Ok("Frameskip; 1|0|2|3|4|5")
+  );
+}
+
+#[test]
+fn int_option_includes_max_when_step_overshoots() {
+  let option = IntOption::new("test_scale", "Scale", 25, 100)
+    .step(30)
+    .units("%");
+  assert_eq!(option.values(), ["25 %", "55 %", "85 %", "100 %"]);
+}
+
+#[test]
+fn int_option_values_are_capped() {
+  let option = IntOption::new("test_delay", "Delay", 0, 1_000_000).default(7);
+  let values = option.values();
+  // The step is widened to 8000: 126 steps up to `max`, and the default.
+  assert_eq!(values.len(), 127);
+  assert_eq!(values[..3], ["7", "0", "8000"]);
+  assert_eq!(values.last().unwrap(), "1000000");
+
+  let option = IntOption::new("test_seed", "Seed", i64::MIN, i64::MAX);
+  let values = option.values();
+  assert!(values.len() <= 128);
+  assert_eq!(values[0], i64::MIN.to_string());
+  assert_eq!(values.last().unwrap(), &i64::MAX.to_string());
+
+  // Small ranges keep their step.
+  let option = IntOption::new("test_small", "Small", 0, 125);
+  assert_eq!(option.values().len(), 126);
+}
+
+#[test]
+#[should_panic]
+fn int_option_rejects_nul_in_key() {
+  IntOption::new("test\0key", "Key", 0, 1);
+}
+
+#[test]
+fn int_option_parses_and_clamps() {
+  let option = IntOption::new("test_offset", "Offset", -10, 10).units("px");
+  let parse = |value: &str| option.parse(&std::ffi::CString::new(value).unwrap());
+  assert_eq!(parse("3 px"), 3);
+  assert_eq!(parse("-7 px"), -7);
+  assert_eq!(parse("+4"), 4);
+  assert_eq!(parse("42"), 10);
+  assert_eq!(parse("-42 px"), -10);
+  assert_eq!(parse("99999999999999999999"), option.default_value());
+  assert_eq!(parse("px"), option.default_value());
+  assert_eq!(parse(""), option.default_value());
+}
+
+enum_option! {
+  #[option(key = "test_renderer", desc = "Renderer")]
+  enum Renderer {
+    #[option(label = "Software")]
+    Software,
+    #[option(label = "OpenGL", default)]
+    OpenGl,
+    #[option(label = "Vulkan (experimental)")]
+    Vulkan,
+  }
+}
+
+#[test]
+fn enum_option_values_match_variants() {
+  let values: Vec<_> = Renderer::values()
+    .iter()
+    .map(|value| value.as_str())
+    .collect();
+  assert_eq!(values, ["Software", "OpenGl", "Vulkan"]);
+  assert_eq!(
+    Renderer::VARIANTS,
+    [Renderer::Software, Renderer::OpenGl, Renderer::Vulkan]
+  );
+  assert_eq!(Renderer::default_variant(), Renderer::OpenGl);
+  assert_eq!(Renderer::Vulkan.label().as_str(), "Vulkan (experimental)");
+}
+
+#[test]
+fn enum_option_parsing_round_trips() {
+  for &variant in Renderer::VARIANTS {
+    assert_eq!(Renderer::parse(variant.value().as_c_str()), variant);
+  }
+  let parse = |value: &str| Renderer::parse(&std::ffi::CString::new(value).unwrap());
+  assert_eq!(parse("Software"), Renderer::Software);
+  assert_eq!(parse("software"), Renderer::OpenGl);
+  assert_eq!(parse(""), Renderer::OpenGl);
+}
+
+#[test]
+fn enum_option_v2_definition_points_at_static_strings() {
+  let string =
+    |ptr: *const core::ffi::c_char| unsafe { std::ffi::CStr::from_ptr(ptr) }.to_str().unwrap();
+  let definition = Renderer::to_v2_definition();
+  assert_eq!(string(definition.key), "test_renderer");
+  assert_eq!(string(definition.desc), "Renderer");
+  assert_eq!(string(definition.default_value), "OpenGl");
+  let values: Vec<_> = definition
+    .values
+    .iter()
+    .take_while(|value| !value.value.is_null())
+    .map(|value| (string(value.value), string(value.label)))
+    .collect();
+  assert_eq!(
+    values,
+    [
+      ("Software", "Software"),
+      ("OpenGl", "OpenGL"),
+      ("Vulkan", "Vulkan (experimental)")
+    ]
+  );
+}
+
+/// Reads a string of a raw core option, or [None] if it's null.
+fn raw_string(ptr: *const core::ffi::c_char) -> Option<&'static str> {
+  match ptr.is_null() {
+    true => None,
+    false => Some(unsafe { std::ffi::CStr::from_ptr(ptr) }.to_str().unwrap()),
+  }
+}
+
+#[test]
+fn core_options_build_null_terminated_v2_arrays() {
+  let options = CoreOptions::new()
+    .category("video", "Video", "")
+    .option(
+      CoreOption::new("test_palette", "Video > Palette")
+        .category("video", "Palette")
+        .info("Colors used for the screen.")
+        .labeled_value("grey", "Greyscale")
+        .value("green")
+        .default("green"),
+    )
+    .enum_option::<Renderer>();
+  assert_eq!(options.len(), 2);
+  let raw = options.as_raw();
+
+  let categories = unsafe { core::slice::from_raw_parts(raw.categories, 2) };
+  assert_eq!(raw_string(categories[0].key), Some("video"));
+  assert_eq!(raw_string(categories[0].desc), Some("Video"));
+  assert_eq!(raw_string(categories[0].info), Some(""));
+  assert!(categories[1].key.is_null());
+
+  let definitions = unsafe { core::slice::from_raw_parts(raw.definitions, 3) };
+  let palette = &definitions[0];
+  assert_eq!(raw_string(palette.key), Some("test_palette"));
+  assert_eq!(raw_string(palette.desc), Some("Video > Palette"));
+  assert_eq!(raw_string(palette.desc_categorized), Some("Palette"));
+  assert_eq!(
+    raw_string(palette.info),
+    Some("Colors used for the screen.")
+  );
+  assert_eq!(raw_string(palette.info_categorized), None);
+  assert_eq!(raw_string(palette.category_key), Some("video"));
+  assert_eq!(raw_string(palette.default_value), Some("green"));
+  let values: Vec<_> = palette
+    .values
+    .iter()
+    .take_while(|value| !value.value.is_null())
+    .map(|value| (raw_string(value.value), raw_string(value.label)))
+    .collect();
+  assert_eq!(
+    values,
+    [(Some("grey"), Some("Greyscale")), (Some("green"), None)]
+  );
+  assert_eq!(raw_string(definitions[1].key), Some("test_renderer"));
+  assert!(definitions[2].key.is_null());
+}
+
+#[test]
+fn core_options_keep_at_most_127_values() {
+  let values: Vec<String> = (0..200).map(|n| n.to_string()).collect();
+  let option = values
+    .iter()
+    .fold(CoreOption::new("test_many", "Many"), |option, value| {
+      option.value(value)
+    });
+  let options = CoreOptions::new().option(option);
+  let definition = unsafe { &*options.as_raw().definitions };
+  assert_eq!(raw_string(definition.values[126].value), Some("126"));
+  assert!(definition.values[127].value.is_null());
+}
+
+#[test]
+fn restart_required_options_are_labeled() {
+  let options = CoreOptions::new().option(
+    CoreOption::new("test_bios", "System > BIOS")
+      .category("system", "BIOS")
+      .value("auto")
+      .restart_required(),
+  );
+  let definition = unsafe { &*options.as_raw().definitions };
+  assert_eq!(raw_string(definition.desc), Some("System > BIOS (Restart)"));
+  assert_eq!(
+    raw_string(definition.desc_categorized),
+    Some("BIOS (Restart)")
+  );
+}
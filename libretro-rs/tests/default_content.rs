@@ -0,0 +1,81 @@
+//! Loads a core defined in this test with and without content, relying on
+//! [`Core::DEFAULT_CONTENT`] to load built-in content when there's none.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static SUPPORT_NO_GAME: AtomicBool = AtomicBool::new(false);
+
+/// The content of every game the core loaded.
+static LOADED: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const DEFAULT_CONTENT: Option<&'static [u8]> = Some(b"demo");
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("default content test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_game<E: env::LoadGame>(
+    game: &GameInfo,
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let data = game.as_data().ok_or_else(CoreError::new)?;
+    LOADED.lock().unwrap().push(data.data().to_vec());
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME => {
+      SUPPORT_NO_GAME.store(*(data as *const bool), Ordering::SeqCst);
+      true
+    }
+    _ => false,
+  }
+}
+
+#[test]
+fn default_content_is_loaded_without_a_game() {
+  unsafe {
+    init(environment);
+    assert!(SUPPORT_NO_GAME.load(Ordering::SeqCst));
+
+    let content = *b"player content";
+    let game: retro_game_info = GameData::new(&content, None, None).into();
+    assert!(retro_load_game(&game));
+    retro_unload_game();
+
+    assert!(retro_load_game(core::ptr::null()));
+    stop();
+  }
+  assert_eq!(
+    *LOADED.lock().unwrap(),
+    [b"player content".to_vec(), b"demo".to_vec()]
+  );
+}
@@ -0,0 +1,57 @@
+//! Tests for [`DeferredInit`].
+
+use libretro_rs::retro::deferred::DeferredInit;
+use std::cell::Cell;
+
+/// Mimics a core whose `run` finishes initialization on its first call.
+struct Core<'a> {
+  state: DeferredInit<u32, Box<dyn FnOnce() -> u32 + 'a>>,
+  loading_frames: u32,
+}
+
+impl<'a> Core<'a> {
+  fn load_game(inits: &'a Cell<u32>) -> Self {
+    let init = move || {
+      inits.set(inits.get() + 1);
+      0
+    };
+    Self {
+      state: DeferredInit::new(Box::new(init)),
+      loading_frames: 0,
+    }
+  }
+
+  fn run(&mut self) {
+    if self.state.is_pending() {
+      self.loading_frames += 1;
+    }
+    *self.state.get_or_init() += 1;
+  }
+}
+
+#[test]
+fn first_run_completes_initialization() {
+  let inits = Cell::new(0);
+  let mut core = Core::load_game(&inits);
+  assert_eq!(inits.get(), 0);
+  assert!(core.state.is_pending());
+
+  core.run();
+  assert_eq!(inits.get(), 1);
+  assert!(core.state.is_ready());
+
+  for _ in 0..3 {
+    core.run();
+  }
+  assert_eq!(inits.get(), 1);
+  assert_eq!(core.loading_frames, 1);
+  assert_eq!(core.state.get(), Some(&4));
+}
+
+#[test]
+fn ready_value_is_never_initialized() {
+  let mut value = DeferredInit::<_, fn() -> u32>::ready(5);
+  assert!(value.is_ready());
+  assert_eq!(*value.get_or_init(), 5);
+  assert_eq!(value.into_inner(), Some(5));
+}
@@ -0,0 +1,65 @@
+//! Tests for the device state helpers.
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+
+fn gun(screen_x: i16, screen_y: i16) -> LightGunState {
+  LightGunState { screen_x, screen_y, ..LightGunState::default() }
+}
+
+#[test]
+fn lightgun_center_maps_to_center_pixel() {
+  assert_eq!(
+    gun(0, 0).screen_position(&GameGeometry::fixed(256, 224)),
+    Some((128, 112))
+  );
+  assert_eq!(
+    gun(0, 0).screen_position(&GameGeometry::fixed(255, 223)),
+    Some((127, 111))
+  );
+}
+
+#[test]
+fn lightgun_corners_map_to_edge_pixels() {
+  let geometry = GameGeometry::fixed(320, 240);
+  assert_eq!(
+    gun(-0x7FFF, -0x7FFF).screen_position(&geometry),
+    Some((0, 0))
+  );
+  assert_eq!(
+    gun(0x7FFF, -0x7FFF).screen_position(&geometry),
+    Some((319, 0))
+  );
+  assert_eq!(
+    gun(-0x7FFF, 0x7FFF).screen_position(&geometry),
+    Some((0, 239))
+  );
+  assert_eq!(
+    gun(0x7FFF, 0x7FFF).screen_position(&geometry),
+    Some((319, 239))
+  );
+}
+
+#[test]
+fn lightgun_offscreen_has_no_position() {
+  let geometry = GameGeometry::fixed(320, 240);
+  let offscreen = LightGunState { is_offscreen: true, ..gun(0, 0) };
+  assert_eq!(offscreen.screen_position(&geometry), None);
+  assert_eq!(gun(-0x8000, 0).screen_position(&geometry), None);
+  assert_eq!(gun(0, -0x8000).screen_position(&geometry), None);
+}
+
+#[test]
+fn keys_round_trip_through_retrok_values() {
+  let keys: Vec<_> = (0..retro_key::RETROK_LAST as c_uint)
+    .filter_map(|id| Key::try_from(id).ok().map(|key| (id, key)))
+    .collect();
+  assert_eq!(keys.len(), 141);
+  for (id, key) in keys {
+    assert_eq!(c_uint::from(key), id);
+  }
+  assert_eq!(Key::try_from(retro_key::RETROK_a as c_uint), Ok(Key::A));
+  assert_eq!(Key::try_from(retro_key::RETROK_F15 as c_uint), Ok(Key::F15));
+  assert_eq!(Key::try_from(1), Err(()));
+  assert_eq!(Key::try_from(retro_key::RETROK_LAST as c_uint), Err(()));
+}
@@ -0,0 +1,191 @@
+//! Reads disk image paths and labels through the extended disk control
+//! interface registered by [`libretro_core`], for a core defined in this test.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::ffi::{CStr, CString};
+use std::sync::Mutex;
+
+/// The callbacks registered with `RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE`.
+static CALLBACKS: Mutex<Option<retro_disk_control_ext_callback>> = Mutex::new(None);
+
+/// The disks of a playlist, as `(path, label)`.
+const DISKS: [(&str, &str); 2] = [
+  ("/games/Game (Disk 1).chd", "Disk 1"),
+  ("/games/Game (Disk 2).chd", "Disk 2"),
+];
+
+struct Core {
+  paths: Vec<CString>,
+  labels: Vec<CString>,
+  index: c_uint,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("disk control test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let string = |s: &str| CString::new(s).unwrap();
+    Ok(Core {
+      paths: DISKS.iter().map(|(path, _)| string(path)).collect(),
+      labels: DISKS.iter().map(|(_, label)| string(label)).collect(),
+      index: 0,
+    })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+impl<'a> DiskControlCore<'a> for Core {
+  fn get_eject_state(&self, _env: &mut impl env::DiskControl) -> bool {
+    false
+  }
+
+  fn set_eject_state(
+    &mut self,
+    _env: &mut impl env::DiskControl,
+    _ejected: bool,
+  ) -> Result<(), CoreError> {
+    Ok(())
+  }
+
+  fn get_image_index(&self, _env: &mut impl env::DiskControl) -> c_uint {
+    self.index
+  }
+
+  fn set_image_index(
+    &mut self,
+    _env: &mut impl env::DiskControl,
+    index: c_uint,
+  ) -> Result<(), CoreError> {
+    self.index = index;
+    Ok(())
+  }
+
+  fn get_num_images(&self, _env: &mut impl env::DiskControl) -> c_uint {
+    self.paths.len() as c_uint
+  }
+
+  fn replace_image_index(
+    &mut self,
+    _env: &mut impl env::DiskControl,
+    _index: c_uint,
+    _game: Option<&GameInfo>,
+  ) -> Result<(), CoreError> {
+    Err(CoreError::new())
+  }
+
+  fn add_image_index(&mut self, _env: &mut impl env::DiskControl) -> Result<(), CoreError> {
+    Err(CoreError::new())
+  }
+
+  fn get_image_path(&self, _env: &mut impl env::DiskControl, index: c_uint) -> Option<&CStr> {
+    self.paths.get(index as usize).map(CString::as_c_str)
+  }
+
+  fn get_image_label(&self, _env: &mut impl env::DiskControl, index: c_uint) -> Option<&CStr> {
+    self.labels.get(index as usize).map(CString::as_c_str)
+  }
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION => {
+      *(data as *mut c_uint) = 1;
+      true
+    }
+    RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE => {
+      *CALLBACKS.lock().unwrap() = Some(*(data as *const retro_disk_control_ext_callback));
+      true
+    }
+    _ => false,
+  }
+}
+
+/// Calls `get` like a frontend would, with a buffer of `len` bytes.
+unsafe fn read(
+  get: unsafe extern "C" fn(c_uint, *mut c_char, usize) -> bool,
+  index: c_uint,
+  len: usize,
+) -> Option<String> {
+  let mut buffer = vec![0 as c_char; len];
+  match get(index, buffer.as_mut_ptr(), len) {
+    true => Some(CStr::from_ptr(buffer.as_ptr()).to_str().unwrap().to_owned()),
+    false => None,
+  }
+}
+
+/// Checks that the callbacks don't reach the core while no game is loaded.
+unsafe fn assert_no_disks(callbacks: &retro_disk_control_ext_callback) {
+  assert!(!callbacks.get_eject_state.unwrap()());
+  assert!(!callbacks.set_eject_state.unwrap()(true));
+  assert_eq!(callbacks.get_image_index.unwrap()(), 0);
+  assert!(!callbacks.set_image_index.unwrap()(1));
+  assert_eq!(callbacks.get_num_images.unwrap()(), 0);
+  assert!(!callbacks.replace_image_index.unwrap()(
+    0,
+    core::ptr::null()
+  ));
+  assert!(!callbacks.add_image_index.unwrap()());
+}
+
+#[test]
+fn frontend_receives_image_paths_and_labels() {
+  unsafe {
+    init(environment);
+    let callbacks = CALLBACKS
+      .lock()
+      .unwrap()
+      .expect("extended interface not registered");
+    let (get_path, get_label) = (
+      callbacks.get_image_path.unwrap(),
+      callbacks.get_image_label.unwrap(),
+    );
+    // Nothing can be read before the playlist is loaded.
+    assert_eq!(read(get_path, 0, 256), None);
+    assert_no_disks(&callbacks);
+    // A null path is rejected rather than read.
+    assert!(!callbacks.set_initial_image.unwrap()(0, core::ptr::null()));
+
+    assert!(retro_load_game(core::ptr::null()));
+    for (index, (path, label)) in DISKS.iter().enumerate() {
+      assert_eq!(read(get_path, index as c_uint, 256).as_deref(), Some(*path));
+      assert_eq!(
+        read(get_label, index as c_uint, 256).as_deref(),
+        Some(*label)
+      );
+    }
+    assert_eq!(read(get_path, DISKS.len() as c_uint, 256), None);
+    // The nul terminator has to fit too.
+    let path = DISKS[1].0;
+    assert_eq!(read(get_path, 1, path.len()), None);
+    assert_eq!(read(get_path, 1, path.len() + 1).as_deref(), Some(path));
+
+    retro_unload_game();
+    assert_eq!(read(get_label, 0, 256), None);
+    assert_no_disks(&callbacks);
+    retro_deinit();
+  }
+}
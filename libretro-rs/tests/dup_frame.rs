@@ -0,0 +1,114 @@
+//! Dupes frames from a core defined in this test, through the symbols exported
+//! by [`libretro_core`], and checks what the frontend's refresh callback gets.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Whether the core dupes the frame on its next run instead of uploading it.
+static DUPE: AtomicBool = AtomicBool::new(false);
+
+/// Every call to the refresh callback, as `(is_null, width, height, pitch)`.
+static REFRESHES: Mutex<Vec<(bool, c_uint, c_uint, usize)>> = Mutex::new(Vec::new());
+
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  pixel_format: ActiveFormat<XRGB8888>,
+  frame_buffer: ArrayFrameBuffer<XRGB8888, { 3 * 2 }, 3>,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("dup frame test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let pixel_format = env
+      .set_pixel_format_xrgb8888(pixel_format)
+      .map_err(|_| CoreError::new())?;
+    let frame_buffer = ArrayFrameBuffer::default();
+    Ok(Core { rendering_mode, pixel_format, frame_buffer })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(3, 2))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    if DUPE.load(Ordering::SeqCst) {
+      callbacks.dup_video_frame(&self.rendering_mode);
+    } else {
+      callbacks.upload_video_frame(&self.rendering_mode, &self.pixel_format, &self.frame_buffer);
+    }
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_SET_PIXEL_FORMAT => true,
+    RETRO_ENVIRONMENT_GET_CAN_DUPE => {
+      *(data as *mut bool) = true;
+      true
+    }
+    _ => false,
+  }
+}
+
+unsafe extern "C" fn video_refresh(
+  data: *const c_void,
+  width: c_uint,
+  height: c_uint,
+  pitch: usize,
+) {
+  REFRESHES
+    .lock()
+    .unwrap()
+    .push((data.is_null(), width, height, pitch));
+}
+
+#[test]
+fn duped_frames_have_the_last_frame_geometry() {
+  unsafe {
+    start(environment);
+    retro_set_video_refresh(video_refresh);
+
+    // Without a previous frame, there's no geometry to report.
+    DUPE.store(true, Ordering::SeqCst);
+    retro_run();
+    DUPE.store(false, Ordering::SeqCst);
+    retro_run();
+    DUPE.store(true, Ordering::SeqCst);
+    retro_run();
+    retro_run();
+    assert_eq!(
+      *REFRESHES.lock().unwrap(),
+      [
+        (true, 0, 0, 0),
+        (false, 3, 2, 12),
+        (true, 3, 2, 12),
+        (true, 3, 2, 12)
+      ]
+    );
+
+    stop();
+  }
+}
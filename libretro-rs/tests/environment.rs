@@ -0,0 +1,1245 @@
+//! Tests for the [`env`] traits against a stub `retro_environment_t`.
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use libretro_rs::retro::env::{EnvironmentConfig, Init, Run, SetEnvironment};
+use std::cell::RefCell;
+use std::ptr::NonNull;
+
+type Handler = Box<dyn FnMut(c_uint, *mut c_void) -> bool>;
+
+thread_local! {
+  static HANDLER: RefCell<Option<Handler>> = RefCell::new(None);
+}
+
+unsafe extern "C" fn stub_environment(cmd: c_uint, data: *mut c_void) -> bool {
+  HANDLER.with(|handler| match handler.borrow_mut().as_mut() {
+    Some(handler) => handler(cmd, data),
+    None => false,
+  })
+}
+
+/// Returns an environment callback that forwards every command to `handler`.
+/// Each test runs on its own thread, so handlers don't interfere.
+fn environment(
+  handler: impl FnMut(c_uint, *mut c_void) -> bool + 'static,
+) -> non_null_retro_environment_t {
+  HANDLER.with(|cell| *cell.borrow_mut() = Some(Box::new(handler)));
+  stub_environment
+}
+
+/// Returns an environment that answers [`RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION`]
+/// with `version` (or fails if [`None`]), and records every other command.
+fn disk_control_environment(version: Option<c_uint>) -> (non_null_retro_environment_t, Commands) {
+  let commands = Commands::default();
+  let recorded = commands.clone();
+  let env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION => match version {
+      Some(version) => {
+        unsafe { *(data as *mut c_uint) = version };
+        true
+      }
+      None => false,
+    },
+    cmd => {
+      recorded.0.borrow_mut().push(cmd);
+      true
+    }
+  });
+  (env, commands)
+}
+
+#[derive(Clone, Default)]
+struct Commands(std::rc::Rc<RefCell<Vec<c_uint>>>);
+
+impl Commands {
+  fn get(&self) -> Vec<c_uint> {
+    self.0.borrow().clone()
+  }
+}
+
+#[test]
+fn disk_control_uses_legacy_interface_for_version_0() {
+  let (mut env, commands) = disk_control_environment(Some(0));
+  let callbacks = retro_disk_control_ext_callback::default();
+  assert_eq!(env.set_disk_control_interface(&callbacks), Ok(0));
+  assert_eq!(
+    commands.get(),
+    [RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE]
+  );
+}
+
+#[test]
+fn disk_control_uses_legacy_interface_if_version_is_unknown() {
+  let (mut env, commands) = disk_control_environment(None);
+  let callbacks = retro_disk_control_ext_callback::default();
+  assert_eq!(env.set_disk_control_interface(&callbacks), Ok(0));
+  assert_eq!(
+    commands.get(),
+    [RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE]
+  );
+}
+
+#[test]
+fn disk_control_uses_extended_interface_for_version_1() {
+  let (mut env, commands) = disk_control_environment(Some(1));
+  let callbacks = retro_disk_control_ext_callback::default();
+  assert_eq!(env.set_disk_control_interface(&callbacks), Ok(1));
+  assert_eq!(
+    commands.get(),
+    [RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE]
+  );
+}
+
+/// Returns an environment that answers [`RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE`]
+/// with `rate`, or fails if [`None`].
+fn refresh_rate_environment(rate: Option<f32>) -> non_null_retro_environment_t {
+  environment(move |cmd, data| match (cmd, rate) {
+    (RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE, Some(rate)) => {
+      unsafe { *(data as *mut f32) = rate };
+      true
+    }
+    _ => false,
+  })
+}
+
+#[test]
+fn av_info_matches_target_refresh_rate() {
+  let env = refresh_rate_environment(Some(120.0));
+  let av_info = SystemAVInfo::matching_refresh_rate(&env, GameGeometry::fixed(320, 240), 48_000.0);
+  assert_eq!(av_info.timing().fps(), 120.0);
+  assert_eq!(av_info.timing().sample_rate(), 48_000.0);
+}
+
+#[test]
+fn av_info_falls_back_to_default_refresh_rate() {
+  let default_fps = SystemTiming::default().fps();
+  for rate in [None, Some(0.0), Some(f32::NAN)] {
+    let env = refresh_rate_environment(rate);
+    let av_info =
+      SystemAVInfo::matching_refresh_rate(&env, GameGeometry::fixed(320, 240), 48_000.0);
+    assert_eq!(av_info.timing().fps(), default_fps);
+  }
+}
+
+#[test]
+fn directories_are_queried_from_the_frontend() {
+  let env = environment(|cmd, data| {
+    let path: &'static CStr = match cmd {
+      RETRO_ENVIRONMENT_GET_SYSTEM_DIRECTORY => c_utf8!("/retro/system").as_ref(),
+      RETRO_ENVIRONMENT_GET_SAVE_DIRECTORY => c_utf8!("/retro/saves").as_ref(),
+      _ => return false,
+    };
+    unsafe { *(data as *mut *const c_char) = path.as_ptr() };
+    true
+  });
+  let dirs = env.directories();
+  assert_eq!(dirs.system, Some("/retro/system".into()));
+  assert_eq!(dirs.save, Some("/retro/saves".into()));
+  assert_eq!(dirs.core_assets, None);
+  assert_eq!(dirs.libretro, None);
+  assert_eq!(
+    dirs.system_file("bios.bin"),
+    Some("/retro/system/bios.bin".into())
+  );
+  assert_eq!(dirs.core_assets_file("font.png"), None);
+}
+
+#[test]
+fn hw_render_interface_is_typed_by_backend() {
+  static INTERFACE: retro_hw_render_interface = retro_hw_render_interface {
+    interface_type: retro_hw_render_interface_type::RETRO_HW_RENDER_INTERFACE_VULKAN,
+    interface_version: 5,
+  };
+  let env = environment(|cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_HW_RENDER_INTERFACE => {
+      unsafe { *(data as *mut *const retro_hw_render_interface) = &INTERFACE };
+      true
+    }
+    _ => false,
+  });
+  let interface = env.get_hw_render_interface().unwrap().unwrap();
+  assert!(matches!(interface, HWRenderInterface::Vulkan(_)));
+  assert_eq!(interface.version(), 5);
+}
+
+#[test]
+fn hw_render_interface_handles_null() {
+  let env = environment(|cmd, _| cmd == RETRO_ENVIRONMENT_GET_HW_RENDER_INTERFACE);
+  assert!(matches!(env.get_hw_render_interface(), Ok(None)));
+  let env = environment(|_, _| false);
+  assert!(env.get_hw_render_interface().is_err());
+}
+
+#[test]
+fn game_info_ext_exposes_the_content_name() {
+  let env = environment(|cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_GAME_INFO_EXT => {
+      let info = Box::leak(Box::new(retro_game_info_ext {
+        full_path: c_utf8!("/roms/Super Game.sfc").as_ptr(),
+        archive_path: core::ptr::null(),
+        archive_file: core::ptr::null(),
+        dir: c_utf8!("/roms").as_ptr(),
+        name: c_utf8!("Super Game").as_ptr(),
+        ext: c_utf8!("sfc").as_ptr(),
+        meta: core::ptr::null(),
+        data: core::ptr::null(),
+        size: 0,
+        file_in_archive: false,
+        persistent_data: false,
+      }));
+      unsafe { *(data as *mut *const retro_game_info_ext) = info };
+      true
+    }
+    _ => false,
+  });
+  let info = env.get_game_info_ext().unwrap();
+  assert_eq!(info.name(), Some(c_utf8!("Super Game").as_c_str()));
+  assert_eq!(info.ext(), Some(c_utf8!("sfc").as_c_str()));
+  assert_eq!(info.dir(), Some(c_utf8!("/roms")));
+  assert_eq!(info.data(), None);
+  assert!(!info.file_in_archive());
+
+  let env = environment(|cmd, _| cmd == RETRO_ENVIRONMENT_GET_GAME_INFO_EXT);
+  assert!(env.get_game_info_ext().is_none());
+  let env = environment(|_, _| false);
+  assert!(env.get_game_info_ext().is_none());
+}
+
+#[test]
+fn fastforwarding_override_is_passed_to_the_frontend() {
+  let received = std::rc::Rc::new(RefCell::new(None));
+  let recorded = received.clone();
+  let mut env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_SET_FASTFORWARDING_OVERRIDE => {
+      let data = unsafe { *(data as *const retro_fastforwarding_override) };
+      *recorded.borrow_mut() = Some(data);
+      true
+    }
+    _ => false,
+  });
+  let data = FastForwardingOverride::new(true)
+    .set_ratio(2.5)
+    .set_notification(false)
+    .set_inhibit_toggle(true);
+  assert_eq!(env.set_fastforwarding_override(data), Ok(()));
+
+  let received = received.borrow().expect("command wasn't sent");
+  assert_eq!(received.ratio, 2.5);
+  assert!(received.fastforward);
+  assert!(!received.notification);
+  assert!(received.inhibit_toggle);
+}
+
+#[test]
+fn geometry_is_passed_to_the_frontend() {
+  let received = std::rc::Rc::new(RefCell::new(None));
+  let recorded = received.clone();
+  let mut env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_SET_GEOMETRY => {
+      let data = unsafe { *(data as *const retro_game_geometry) };
+      *recorded.borrow_mut() = Some((data.base_width, data.base_height, data.aspect_ratio));
+      true
+    }
+    _ => false,
+  });
+  let geometry = GameGeometry::new(512..=512, 448..=480, 4.0 / 3.0);
+  assert_eq!(env.set_geometry(&geometry), Ok(()));
+  assert_eq!(*received.borrow(), Some((512, 448, 4.0 / 3.0)));
+
+  let mut env = environment(|_, _| false);
+  assert!(env.set_geometry(&geometry).is_err());
+}
+
+#[test]
+fn system_av_info_is_passed_to_the_frontend() {
+  let received = std::rc::Rc::new(RefCell::new(None));
+  let recorded = received.clone();
+  let mut env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_SET_SYSTEM_AV_INFO => {
+      let data = unsafe { &*(data as *const retro_system_av_info) };
+      *recorded.borrow_mut() = Some((
+        data.geometry.max_height,
+        data.timing.fps,
+        data.timing.sample_rate,
+      ));
+      true
+    }
+    _ => false,
+  });
+  let pal = SystemAVInfo::new(
+    GameGeometry::fixed(256, 288),
+    SystemTiming::new(50.0, 32000.0),
+  );
+  assert_eq!(env.set_system_av_info(&pal), Ok(()));
+  assert_eq!(*received.borrow(), Some((288, 50.0, 32000.0)));
+}
+
+#[test]
+fn disable_cross_session_states_sets_single_session_quirk() {
+  let quirks = std::rc::Rc::new(RefCell::new(None));
+  let recorded = quirks.clone();
+  let mut env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_SET_SERIALIZATION_QUIRKS => {
+      *recorded.borrow_mut() = Some(unsafe { *(data as *const u64) });
+      true
+    }
+    _ => false,
+  });
+  assert_eq!(env.disable_cross_session_states(), Ok(()));
+  assert_eq!(
+    *quirks.borrow(),
+    Some(RETRO_SERIALIZATION_QUIRK_SINGLE_SESSION as u64)
+  );
+}
+
+#[test]
+fn set_serialization_quirks_returns_supported_quirks() {
+  let mut env = environment(|cmd, data| match cmd {
+    RETRO_ENVIRONMENT_SET_SERIALIZATION_QUIRKS => {
+      // Pretend only variable-sized states are supported.
+      let quirks = unsafe { &mut *(data as *mut u64) };
+      *quirks = (*quirks & SerializationQuirks::CORE_VARIABLE_SIZE.into_inner())
+        | SerializationQuirks::FRONT_VARIABLE_SIZE.into_inner();
+      true
+    }
+    _ => false,
+  });
+  let quirks = SerializationQuirks::CORE_VARIABLE_SIZE | SerializationQuirks::SINGLE_SESSION;
+  assert_eq!(
+    env.set_serialization_quirks(quirks),
+    Ok(SerializationQuirks::CORE_VARIABLE_SIZE | SerializationQuirks::FRONT_VARIABLE_SIZE)
+  );
+}
+
+/// Returns an environment that answers `GET_VARIABLE` with `value`, and records
+/// the variables passed to `SET_VARIABLES`.
+fn options_environment(
+  value: Option<&'static CStr>,
+) -> (
+  non_null_retro_environment_t,
+  std::rc::Rc<RefCell<Vec<(String, String)>>>,
+) {
+  let declared = std::rc::Rc::new(RefCell::new(Vec::new()));
+  let recorded = declared.clone();
+  let env = environment(move |cmd, data| unsafe {
+    match cmd {
+      RETRO_ENVIRONMENT_GET_VARIABLE => {
+        let variable = &mut *(data as *mut retro_variable);
+        match value {
+          Some(value) => variable.value = value.as_ptr(),
+          None => return false,
+        }
+        true
+      }
+      RETRO_ENVIRONMENT_SET_VARIABLES => {
+        let mut variable = data as *const retro_variable;
+        while !(*variable).key.is_null() {
+          let key = CStr::from_ptr((*variable).key).to_str().unwrap().to_owned();
+          let value = CStr::from_ptr((*variable).value)
+            .to_str()
+            .unwrap()
+            .to_owned();
+          recorded.borrow_mut().push((key, value));
+          variable = variable.add(1);
+        }
+        true
+      }
+      _ => false,
+    }
+  });
+  (env, declared)
+}
+
+#[test]
+fn int_options_are_declared_as_variables() {
+  let (mut env, declared) = options_environment(None);
+  let options = [
+    IntOption::new("test_frameskip", "Frameskip", 0, 5).default(1),
+    IntOption::new("test_volume", "Volume", 0, 2),
+  ];
+  assert_eq!(env.set_int_options(&options), Ok(()));
+  assert_eq!(
+    *declared.borrow(),
+    [
+      ("test_frameskip".into(), "Frameskip; 1|0|2|3|4|5".into()),
+      ("test_volume".into(), "Volume; 0|1|2".into()),
+    ]
+  );
+}
+
+#[test]
+fn int_option_is_read_from_the_frontend() {
+  let (env, _) = options_environment(Some(c_utf8!("4").as_ref()));
+  assert_eq!(
+    env.get_int_option(&IntOption::new("test_frameskip", "Frameskip", 0, 5).default(1)),
+    4
+  );
+  let (env, _) = options_environment(Some(c_utf8!("12").as_ref()));
+  assert_eq!(
+    env.get_int_option(&IntOption::new("test_frameskip", "Frameskip", 0, 5).default(1)),
+    5
+  );
+  let (env, _) = options_environment(None);
+  assert_eq!(
+    env.get_int_option(&IntOption::new("test_frameskip", "Frameskip", 0, 5).default(1)),
+    1
+  );
+}
+
+#[test]
+fn clear_all_thread_waits_command_id() {
+  assert_eq!(RETRO_ENVIRONMENT_GET_CLEAR_ALL_THREAD_WAITS_CB, 0x800003);
+}
+
+#[test]
+fn clear_all_thread_waits_callback_is_returned() {
+  thread_local! {
+    static CALLS: RefCell<Vec<c_uint>> = const { RefCell::new(Vec::new()) };
+  }
+  unsafe extern "C" fn clear_thread_waits(clear: c_uint, _: *mut c_void) -> bool {
+    CALLS.with(|calls| calls.borrow_mut().push(clear));
+    true
+  }
+
+  let env = environment(|cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_CLEAR_ALL_THREAD_WAITS_CB => {
+      unsafe { *(data as *mut retro_environment_t) = Some(clear_thread_waits) };
+      true
+    }
+    _ => false,
+  });
+  let waits = env.get_clear_all_thread_waits().unwrap();
+  assert!(waits.clear());
+  assert!(waits.restore());
+  assert_eq!(CALLS.with(|calls| calls.borrow().clone()), [1, 0]);
+
+  let env = environment(|_, _| false);
+  assert!(env.get_clear_all_thread_waits().is_none());
+}
+
+thread_local! {
+  /// The number of files closed through [vfs_environment].
+  static VFS_CLOSED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Returns an environment whose VFS interface implements `version`.
+fn vfs_environment(version: u32) -> non_null_retro_environment_t {
+  unsafe extern "C" fn open(_: *const c_char, _: c_uint, _: c_uint) -> *mut retro_vfs_file_handle {
+    core::ptr::NonNull::dangling().as_ptr()
+  }
+  unsafe extern "C" fn read(_: *mut retro_vfs_file_handle, s: *mut c_void, len: u64) -> i64 {
+    let data = b"hello";
+    let len = data.len().min(len as usize);
+    core::ptr::copy_nonoverlapping(data.as_ptr(), s.cast(), len);
+    len as i64
+  }
+  unsafe extern "C" fn close(_: *mut retro_vfs_file_handle) -> c_int {
+    VFS_CLOSED.with(|closed| closed.set(closed.get() + 1));
+    0
+  }
+  unsafe extern "C" fn opendir(_: *const c_char, _: bool) -> *mut retro_vfs_dir_handle {
+    core::ptr::NonNull::dangling().as_ptr()
+  }
+  static INTERFACE: retro_vfs_interface = retro_vfs_interface {
+    get_path: None,
+    open: Some(open),
+    close: Some(close),
+    size: None,
+    tell: None,
+    seek: None,
+    read: Some(read),
+    write: None,
+    flush: None,
+    remove: None,
+    rename: None,
+    truncate: None,
+    stat: None,
+    mkdir: None,
+    opendir: Some(opendir),
+    readdir: None,
+    dirent_get_name: None,
+    dirent_is_dir: None,
+    closedir: None,
+  };
+  environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_VFS_INTERFACE => {
+      let info = unsafe { &mut *(data as *mut retro_vfs_interface_info) };
+      if info.required_interface_version > version {
+        return false;
+      }
+      info.required_interface_version = version;
+      info.iface = &INTERFACE as *const _ as *mut _;
+      true
+    }
+    _ => false,
+  })
+}
+
+#[test]
+fn vfs_interface_is_none_for_unsupported_version() {
+  let env = vfs_environment(2);
+  assert!(env.get_vfs_interface(3).is_none());
+  let env = environment(|_, _| false);
+  assert!(env.get_vfs_interface(1).is_none());
+}
+
+#[test]
+fn vfs_interface_reports_granted_version() {
+  use std::io::Read;
+  let env = vfs_environment(2);
+  let vfs = env.get_vfs_interface(1).unwrap();
+  assert_eq!(vfs.version(), 2);
+
+  let path = c_utf8!("/retro/game.sav").as_ref();
+  let mut file = vfs.open(path, RETRO_VFS_FILE_ACCESS_READ).unwrap();
+  let mut buf = [0; 16];
+  let len = file.read(&mut buf).unwrap();
+  assert_eq!(&buf[..len], b"hello");
+  file.close().unwrap();
+  assert_eq!(VFS_CLOSED.with(std::cell::Cell::get), 1);
+
+  // Directories require version 3, even if the frontend fills them in.
+  let err = vfs.opendir(path, false).unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+}
+
+thread_local! {
+  /// Whether the microphone of [mic_environment] is open and active.
+  static MIC: std::cell::Cell<(bool, bool)> = const { std::cell::Cell::new((false, false)) };
+}
+
+/// Returns an environment with a microphone interface that records a ramp at
+/// the requested rate, or 8000 Hz by default.
+fn mic_environment() -> non_null_retro_environment_t {
+  static RATE: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+  unsafe extern "C" fn open_mic(params: *const retro_microphone_params) -> *mut retro_microphone {
+    let rate = params.as_ref().map_or(8000, |params| params.rate);
+    RATE.store(rate, std::sync::atomic::Ordering::SeqCst);
+    MIC.with(|mic| mic.set((true, false)));
+    core::ptr::NonNull::dangling().as_ptr()
+  }
+  unsafe extern "C" fn close_mic(_: *mut retro_microphone) {
+    MIC.with(|mic| mic.set((false, false)));
+  }
+  unsafe extern "C" fn get_params(
+    _: *const retro_microphone,
+    params: *mut retro_microphone_params,
+  ) -> bool {
+    (*params).rate = RATE.load(std::sync::atomic::Ordering::SeqCst);
+    true
+  }
+  unsafe extern "C" fn set_mic_state(_: *mut retro_microphone, state: bool) -> bool {
+    MIC.with(|mic| mic.set((true, state)));
+    true
+  }
+  unsafe extern "C" fn get_mic_state(_: *const retro_microphone) -> bool {
+    MIC.with(|mic| mic.get().1)
+  }
+  unsafe extern "C" fn read_mic(_: *mut retro_microphone, samples: *mut i16, len: usize) -> c_int {
+    if !get_mic_state(core::ptr::null()) {
+      return -1;
+    }
+    let samples = core::slice::from_raw_parts_mut(samples, len);
+    for (i, sample) in samples.iter_mut().enumerate() {
+      *sample = i as i16;
+    }
+    len as c_int
+  }
+  environment(|cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_MICROPHONE_INTERFACE => {
+      let iface = unsafe { &mut *(data as *mut retro_microphone_interface) };
+      assert_eq!(iface.interface_version, RETRO_MICROPHONE_INTERFACE_VERSION);
+      *iface = retro_microphone_interface {
+        interface_version: RETRO_MICROPHONE_INTERFACE_VERSION,
+        open_mic: Some(open_mic),
+        close_mic: Some(close_mic),
+        get_params: Some(get_params),
+        set_mic_state: Some(set_mic_state),
+        get_mic_state: Some(get_mic_state),
+        read_mic: Some(read_mic),
+      };
+      true
+    }
+    _ => false,
+  })
+}
+
+#[test]
+fn microphone_interface_is_none_without_frontend_support() {
+  let env = environment(|_, _| false);
+  assert!(env.get_microphone_interface().is_none());
+}
+
+#[test]
+fn microphones_are_read_while_active() {
+  let env = mic_environment();
+  let iface = env.get_microphone_interface().unwrap();
+  assert_eq!(iface.version(), RETRO_MICROPHONE_INTERFACE_VERSION);
+
+  let mut mic = iface.open_mic(Some(16000)).unwrap();
+  assert_eq!(mic.rate().unwrap(), 16000);
+  let mut samples = [0; 4];
+  assert!(!mic.is_active());
+  assert!(mic.read(&mut samples).is_err());
+
+  mic.set_active(true).unwrap();
+  assert!(mic.is_active());
+  assert_eq!(mic.read(&mut samples).unwrap(), 4);
+  assert_eq!(samples, [0, 1, 2, 3]);
+
+  mic.close();
+  assert_eq!(MIC.with(std::cell::Cell::get), (false, false));
+  assert_eq!(iface.open_mic(None).unwrap().rate().unwrap(), 8000);
+}
+
+#[test]
+fn frame_stepping_is_reported_as_paused() {
+  let mode = std::rc::Rc::new(std::cell::Cell::new(RETRO_THROTTLE_NONE));
+  let reported = mode.clone();
+  let env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_THROTTLE_STATE => {
+      let state = unsafe { &mut *(data as *mut retro_throttle_state) };
+      state.mode = reported.get();
+      true
+    }
+    _ => false,
+  });
+  assert!(!env.is_paused());
+  mode.set(RETRO_THROTTLE_FRAME_STEPPING);
+  assert!(env.is_paused());
+  assert_eq!(
+    env.get_throttle_state().unwrap().mode,
+    RETRO_THROTTLE_FRAME_STEPPING
+  );
+  mode.set(RETRO_THROTTLE_FAST_FORWARD);
+  assert!(!env.is_paused());
+}
+
+#[test]
+fn frontends_without_throttle_state_are_never_paused() {
+  let env = environment(|_, _| false);
+  assert!(!env.is_paused());
+}
+
+#[test]
+fn fastforwarding_override_support_is_queried_with_null() {
+  let env = environment(|cmd, data| match cmd {
+    RETRO_ENVIRONMENT_SET_FASTFORWARDING_OVERRIDE => data.is_null(),
+    RETRO_ENVIRONMENT_GET_FASTFORWARDING => {
+      unsafe { *(data as *mut bool) = true };
+      true
+    }
+    _ => false,
+  });
+  assert!(env.supports_fastforwarding_override());
+  assert_eq!(env.get_fastforwarding(), Ok(true));
+  // The stub environment doesn't keep track of overrides.
+  assert_eq!(env.fastforwarding_override().map(|data| data.ratio()), None);
+  assert!(!env.is_fastforwarding_overridden());
+  assert!(!env.is_fastforwarding_toggle_inhibited());
+}
+
+#[test]
+fn time_usec_is_read_from_the_perf_interface() {
+  thread_local! {
+    static NOW: std::cell::Cell<retro_time_t> = const { std::cell::Cell::new(1_000) };
+  }
+  unsafe extern "C" fn get_time_usec() -> retro_time_t {
+    NOW.with(|now| now.replace(now.get() + 16_667))
+  }
+  let env = environment(|cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_PERF_INTERFACE => {
+      unsafe { (*(data as *mut retro_perf_callback)).get_time_usec = Some(get_time_usec) };
+      true
+    }
+    _ => false,
+  });
+  let first = env.get_time_usec().unwrap();
+  let second = env.get_time_usec().unwrap();
+  assert_eq!((first, second), (1_000, 17_667));
+}
+
+#[test]
+fn time_usec_is_unavailable_without_a_perf_interface() {
+  let env = environment(|_, _| false);
+  assert_eq!(env.get_time_usec(), None);
+  // Frontends may provide the interface without this callback.
+  let env = environment(|cmd, _| cmd == RETRO_ENVIRONMENT_GET_PERF_INTERFACE);
+  assert_eq!(env.get_time_usec(), None);
+}
+
+#[test]
+fn subsystem_rom_slots_declare_need_fullpath_individually() {
+  let declared = std::rc::Rc::new(RefCell::new(Vec::new()));
+  let recorded = declared.clone();
+  let mut env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO => {
+      let mut info = data as *const retro_subsystem_info;
+      unsafe {
+        while !(*info).ident.is_null() {
+          let roms = std::slice::from_raw_parts((*info).roms, (*info).num_roms as usize);
+          let slots: Vec<_> = roms
+            .iter()
+            .map(|rom| (rom.required, rom.need_fullpath, rom.block_extract))
+            .collect();
+          recorded.borrow_mut().push(((*info).id, slots));
+          info = info.add(1);
+        }
+      }
+      true
+    }
+    _ => false,
+  });
+  // The frontend keeps pointers to the slots, so they have to be 'static.
+  let cd: &'static [_] = Box::leak(Box::new([
+    SubsystemRomInfo::new(c_utf8!("Boot ROM"), ext!["bin"]).with_required(),
+    SubsystemRomInfo::new(c_utf8!("CD image"), ext!["cue"])
+      .with_required()
+      .with_need_full_path()
+      .with_block_extract(),
+  ]));
+  let cart: &'static [_] = Box::leak(Box::new([SubsystemRomInfo::new(
+    c_utf8!("Cartridge"),
+    ext!["rom"],
+  )
+  .with_need_full_path()]));
+  let subsystems = [
+    SubsystemInfo::new(c_utf8!("CD"), c_utf8!("cd"), GameType::new(0x101), cd),
+    SubsystemInfo::new(
+      c_utf8!("Cartridge"),
+      c_utf8!("cart"),
+      GameType::new(0x102),
+      cart,
+    ),
+  ];
+  assert_eq!(env.set_subsystem_info(&subsystems), Ok(()));
+  assert_eq!(
+    *declared.borrow(),
+    [
+      (0x101, vec![(true, false, false), (true, true, true)]),
+      (0x102, vec![(false, true, false)]),
+    ]
+  );
+  assert_eq!(subsystems[0].roms()[1].desc().to_str(), Ok("CD image"));
+  assert_eq!(subsystems[1].game_type(), GameType::new(0x102));
+}
+
+#[test]
+fn disk_control_interface_version_is_queried_with_its_command() {
+  let (env, commands) = disk_control_environment(Some(1));
+  assert_eq!(env.get_disk_control_interface_version(), Ok(1));
+  assert_eq!(env.disk_control_interface_version(), 1);
+  // Every other command would have been recorded.
+  assert!(commands.get().is_empty());
+
+  let (env, _) = disk_control_environment(None);
+  assert_eq!(
+    env.get_disk_control_interface_version(),
+    Err(CommandError::new())
+  );
+  assert_eq!(env.disk_control_interface_version(), 0);
+}
+
+#[test]
+fn supported_commands_include_the_wrapped_commands() {
+  let commands = env::supported_commands();
+  for cmd in [
+    RETRO_ENVIRONMENT_SET_PIXEL_FORMAT,
+    RETRO_ENVIRONMENT_GET_VARIABLE,
+    RETRO_ENVIRONMENT_SET_VARIABLES,
+    RETRO_ENVIRONMENT_SET_KEYBOARD_CALLBACK,
+    RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE,
+    RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE,
+    RETRO_ENVIRONMENT_GET_INPUT_BITMASKS,
+    RETRO_ENVIRONMENT_SET_MESSAGE_EXT,
+  ] {
+    assert!(commands.contains(&cmd), "{cmd} is missing");
+  }
+  let mut deduped = commands.to_vec();
+  deduped.sort();
+  deduped.dedup();
+  assert_eq!(deduped.len(), commands.len());
+
+  assert_eq!(
+    env::command_name(RETRO_ENVIRONMENT_SET_PIXEL_FORMAT),
+    Some("RETRO_ENVIRONMENT_SET_PIXEL_FORMAT")
+  );
+  assert_eq!(
+    env::command_name(RETRO_ENVIRONMENT_GET_SENSOR_INTERFACE),
+    None
+  );
+}
+
+#[test]
+fn probed_commands_report_what_the_frontend_answers() {
+  let env = environment(|cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_CAN_DUPE => {
+      unsafe { *(data as *mut bool) = true };
+      true
+    }
+    RETRO_ENVIRONMENT_GET_INPUT_BITMASKS => true,
+    _ => false,
+  });
+  let probed = env.probe_commands();
+  for (cmd, ok) in &probed {
+    assert!(env::supported_commands().contains(cmd));
+    let expected = [
+      RETRO_ENVIRONMENT_GET_CAN_DUPE,
+      RETRO_ENVIRONMENT_GET_INPUT_BITMASKS,
+    ]
+    .contains(cmd);
+    assert_eq!(*ok, expected, "{:?}", env::command_name(*cmd));
+  }
+
+  struct Recorder(std::rc::Rc<RefCell<Vec<(retro_log_level, String)>>>);
+  impl LogInterface for Recorder {
+    fn log(&mut self, level: retro_log_level, message: &libretro_rs::c_utf8::CUtf8) {
+      self
+        .0
+        .borrow_mut()
+        .push((level, message.as_str().to_owned()));
+    }
+  }
+  let messages = std::rc::Rc::default();
+  let mut logger = FallbackLogger::new(Some(Recorder(std::rc::Rc::clone(&messages))));
+  env.log_capabilities(&mut logger);
+
+  let messages = messages.borrow();
+  assert_eq!(messages.len(), 1 + probed.len());
+  assert_eq!(messages[0].0, retro_log_level::RETRO_LOG_INFO);
+  assert!(messages[0].1.ends_with(&format!(
+    "the frontend answered 2 of {} probed",
+    probed.len()
+  )));
+  assert!(messages[1..]
+    .iter()
+    .any(|(_, message)| message.starts_with("RETRO_ENVIRONMENT_GET_CAN_DUPE (3): supported")));
+}
+
+#[test]
+fn core_options_v2_are_passed_to_the_frontend() {
+  let declared = std::rc::Rc::new(RefCell::new(Vec::new()));
+  let recorded = std::rc::Rc::clone(&declared);
+  let mut env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2 => {
+      let options = unsafe { &*(data as *const retro_core_options_v2) };
+      let mut definition = options.definitions;
+      while !unsafe { (*definition).key.is_null() } {
+        let key = unsafe { std::ffi::CStr::from_ptr((*definition).key) };
+        recorded.borrow_mut().push(key.to_owned());
+        definition = unsafe { definition.add(1) };
+      }
+      true
+    }
+    _ => false,
+  });
+  let options = CoreOptions::new()
+    .option(
+      CoreOption::new("test_speed", "Speed")
+        .value("1x")
+        .value("2x"),
+    )
+    .option(
+      CoreOption::new("test_sound", "Sound")
+        .value("on")
+        .value("off"),
+    );
+  assert!(env.set_core_options_v2(&options));
+  assert_eq!(
+    *declared.borrow(),
+    [
+      c_utf8!("test_speed").as_c_str().to_owned(),
+      c_utf8!("test_sound").as_c_str().to_owned()
+    ]
+  );
+}
+
+#[test]
+fn changed_restart_required_options_are_detected() {
+  use std::rc::Rc;
+  let region = Rc::new(RefCell::new(c_utf8!("ntsc")));
+  let messages = Rc::new(RefCell::new(Vec::new()));
+  let (value, shown) = (Rc::clone(&region), Rc::clone(&messages));
+  let mut env = environment(move |cmd, data| unsafe {
+    match cmd {
+      RETRO_ENVIRONMENT_GET_VARIABLE => {
+        let variable = &mut *(data as *mut retro_variable);
+        variable.value = value.borrow().as_ptr();
+        true
+      }
+      RETRO_ENVIRONMENT_SET_MESSAGE => {
+        let message = &*(data as *const retro_message);
+        let text = CStr::from_ptr(message.msg).to_str().unwrap().to_owned();
+        shown.borrow_mut().push(text);
+        true
+      }
+      _ => false,
+    }
+  });
+  let options = CoreOptions::new()
+    .option(
+      CoreOption::new("test_region", "Region")
+        .value("ntsc")
+        .value("pal")
+        .restart_required(),
+    )
+    .option(CoreOption::new("test_speed", "Speed").value("1x"));
+  assert_eq!(
+    options.restart_required_keys().collect::<Vec<_>>(),
+    [c_utf8!("test_region").as_c_str()]
+  );
+
+  let mut tracker = RestartTracker::new(&options, &env);
+  assert!(tracker.changed(&env).is_empty());
+  assert!(!tracker.notify(&mut env));
+
+  *region.borrow_mut() = c_utf8!("pal");
+  assert_eq!(tracker.changed(&env), [c_utf8!("test_region").as_c_str()]);
+  assert!(tracker.notify(&mut env));
+  // The same change is only reported once.
+  assert!(!tracker.notify(&mut env));
+  assert_eq!(*messages.borrow(), ["Restart the game to apply: Region"]);
+
+  // Going back to the loaded value doesn't require a restart.
+  *region.borrow_mut() = c_utf8!("ntsc");
+  assert!(tracker.changed(&env).is_empty());
+  assert!(!tracker.notify(&mut env));
+  assert_eq!(messages.borrow().len(), 1);
+}
+
+#[test]
+fn variable_update_is_queried_from_the_frontend() {
+  let env = environment(|cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE => {
+      unsafe { *(data as *mut bool) = true };
+      true
+    }
+    RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION => {
+      unsafe { *(data as *mut c_uint) = 2 };
+      true
+    }
+    _ => false,
+  });
+  assert!(env.variable_update());
+  assert_eq!(env.get_variable_update(), Ok(true));
+  assert_eq!(env.get_core_options_version(), Ok(2));
+
+  let env = environment(|_, _| false);
+  assert!(!env.variable_update());
+  assert_eq!(env.get_core_options_version(), Err(CommandError::new()));
+}
+
+#[test]
+fn variables_need_update_forwards_every_query_to_the_frontend() {
+  let queries = std::rc::Rc::new(RefCell::new(0));
+  let counted = std::rc::Rc::clone(&queries);
+  // Answers true to the first query only, like a frontend clearing the flag
+  // once it's been read.
+  let mut env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE => {
+      *counted.borrow_mut() += 1;
+      unsafe { *(data as *mut bool) = *counted.borrow() == 1 };
+      true
+    }
+    _ => false,
+  });
+  assert!(env.variables_need_update());
+  assert!(!env.variables_need_update());
+  assert!(!env.variables_need_update());
+  assert_eq!(*queries.borrow(), 3);
+
+  let mut env = environment(|_, _| false);
+  assert!(!env.variables_need_update());
+}
+
+#[test]
+fn memory_maps_describe_the_core_memory() {
+  let received = std::rc::Rc::new(RefCell::new(Vec::new()));
+  let frontend = received.clone();
+  let mut env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_SET_MEMORY_MAPS => {
+      let map = unsafe { &*(data as *const retro_memory_map) };
+      let descriptors =
+        unsafe { std::slice::from_raw_parts(map.descriptors, map.num_descriptors as usize) };
+      *frontend.borrow_mut() = descriptors.to_vec();
+      true
+    }
+    _ => false,
+  });
+
+  let mut wram = vec![0u8; 0x2000];
+  let mut rom = vec![0u8; 0x8000];
+  let (wram_ptr, rom_ptr) = (wram.as_mut_ptr(), rom.as_mut_ptr());
+  let descriptors = [
+    MemoryDescriptor::new(NonNull::new(wram_ptr).unwrap(), wram.len(), 0xC000)
+      .with_flags(MemoryDescriptorFlags::SYSTEM_RAM)
+      .with_select(0xE000)
+      .with_addrspace(c_utf8!("WRAM")),
+    MemoryDescriptor::new(NonNull::new(rom_ptr).unwrap(), rom.len(), 0x4000)
+      .with_flags(MemoryDescriptorFlags::CONST)
+      .with_offset(0x4000),
+    MemoryDescriptor::unmapped(0xFF00, 0x80),
+  ];
+  assert_eq!(descriptors[1].len(), 0x4000);
+  unsafe { env.set_memory_maps(&descriptors) }.unwrap();
+
+  let received = received.borrow();
+  assert_eq!(received.len(), 3);
+  let wram = &received[0];
+  assert_eq!(wram.ptr as *mut u8, wram_ptr);
+  assert_eq!(
+    (wram.start, wram.select, wram.len),
+    (0xC000, 0xE000, 0x2000)
+  );
+  assert_eq!(wram.flags, RETRO_MEMDESC_SYSTEM_RAM as u64);
+  assert_eq!(
+    unsafe { CStr::from_ptr(wram.addrspace) },
+    c_utf8!("WRAM").as_c_str()
+  );
+  let rom = &received[1];
+  assert_eq!(rom.ptr as *mut u8, rom_ptr);
+  assert_eq!((rom.start, rom.offset, rom.len), (0x4000, 0x4000, 0x4000));
+  assert!(rom.addrspace.is_null());
+  let io = &received[2];
+  assert!(io.ptr.is_null());
+  assert_eq!((io.start, io.len, io.flags), (0xFF00, 0x80, 0));
+}
+
+#[test]
+fn memory_maps_can_share_one_buffer() {
+  let received = std::rc::Rc::new(RefCell::new(Vec::new()));
+  let frontend = received.clone();
+  let mut env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_SET_MEMORY_MAPS => {
+      let map = unsafe { &*(data as *const retro_memory_map) };
+      let descriptors =
+        unsafe { std::slice::from_raw_parts(map.descriptors, map.num_descriptors as usize) };
+      *frontend.borrow_mut() = descriptors.to_vec();
+      true
+    }
+    _ => false,
+  });
+
+  // Two 16 KiB banks of one ROM, the second one mirrored at 0xC000.
+  let mut rom = vec![0u8; 0x8000];
+  let rom_ptr = NonNull::new(rom.as_mut_ptr()).unwrap();
+  let descriptors = [
+    MemoryDescriptor::new(rom_ptr, 0x4000, 0x0000).with_flags(MemoryDescriptorFlags::CONST),
+    MemoryDescriptor::new(rom_ptr, rom.len(), 0x4000)
+      .with_flags(MemoryDescriptorFlags::CONST)
+      .with_offset(0x4000)
+      .with_select(0x4000),
+  ];
+  unsafe { env.set_memory_maps(&descriptors) }.unwrap();
+
+  let received = received.borrow();
+  assert_eq!(received.len(), 2);
+  assert!(received
+    .iter()
+    .all(|d| d.ptr as *mut u8 == rom_ptr.as_ptr()));
+  let (bank0, bank1) = (&received[0], &received[1]);
+  assert_eq!((bank0.start, bank0.offset, bank0.len), (0x0000, 0, 0x4000));
+  assert_eq!(
+    (bank1.start, bank1.offset, bank1.len, bank1.select),
+    (0x4000, 0x4000, 0x4000, 0x4000)
+  );
+}
+
+#[test]
+fn environment_config_issues_every_command() {
+  let (mut env, commands) = disk_control_environment(None);
+  let roms: &'static [_] = Box::leak(Box::new([SubsystemRomInfo::new(
+    c_utf8!("Cartridge"),
+    ext!["rom"],
+  )]));
+  let subsystems = [SubsystemInfo::new(
+    c_utf8!("Cartridge"),
+    c_utf8!("cart"),
+    GameType::new(0x101),
+    roms,
+  )];
+  let options = [IntOption::new("test_frameskip", "Frameskip", 0, 5)];
+  let inputs = [jump()];
+  let config = EnvironmentConfig::new()
+    .support_no_game(true)
+    .subsystems(&subsystems)
+    .int_options(&options)
+    .input_descriptors(&inputs);
+  assert_eq!(config.configure(&mut env), Ok(()));
+  assert_eq!(
+    commands.get(),
+    [
+      RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME,
+      RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO,
+      RETRO_ENVIRONMENT_SET_VARIABLES,
+      RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS,
+    ]
+  );
+}
+
+fn jump() -> InputDescriptor<'static> {
+  InputDescriptor::new(
+    DevicePort::new(1),
+    DeviceType::Joypad,
+    0,
+    JoypadButton::A.into(),
+    c_utf8!("Jump"),
+  )
+}
+
+#[test]
+fn input_descriptors_are_terminated() {
+  let declared = std::rc::Rc::new(RefCell::new(Vec::new()));
+  let recorded = declared.clone();
+  let mut env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS => {
+      let mut descriptor = data as *const retro_input_descriptor;
+      while let Some(d) = unsafe { descriptor.as_ref() }.filter(|d| !d.description.is_null()) {
+        let description = unsafe { CStr::from_ptr(d.description) }.to_owned();
+        recorded
+          .borrow_mut()
+          .push((d.port, d.device, d.index, d.id, description));
+        descriptor = unsafe { descriptor.add(1) };
+      }
+      true
+    }
+    _ => false,
+  });
+  let steer = InputDescriptor::new(
+    DevicePort::new(0),
+    DeviceType::Analog,
+    RETRO_DEVICE_INDEX_ANALOG_LEFT,
+    RETRO_DEVICE_ID_ANALOG_X,
+    c_utf8!("Steer"),
+  );
+  assert_eq!(env.set_input_descriptors(&[jump(), steer]), Ok(()));
+  assert_eq!(
+    *declared.borrow(),
+    [
+      (
+        1,
+        RETRO_DEVICE_JOYPAD,
+        0,
+        RETRO_DEVICE_ID_JOYPAD_A,
+        c"Jump".to_owned()
+      ),
+      (
+        0,
+        RETRO_DEVICE_ANALOG,
+        RETRO_DEVICE_INDEX_ANALOG_LEFT,
+        RETRO_DEVICE_ID_ANALOG_X,
+        c"Steer".to_owned()
+      ),
+    ]
+  );
+}
+
+#[test]
+fn environment_config_reports_every_failed_command() {
+  let commands = Commands::default();
+  let recorded = commands.clone();
+  let mut env = environment(move |cmd, _data| {
+    recorded.0.borrow_mut().push(cmd);
+    cmd == RETRO_ENVIRONMENT_SET_VARIABLES
+  });
+  let options = [IntOption::new("test_frameskip", "Frameskip", 0, 5)];
+  let err = EnvironmentConfig::new()
+    .support_no_game(true)
+    .subsystems(&[])
+    .int_options(&options)
+    .input_descriptors(&[jump()])
+    .configure(&mut env)
+    .unwrap_err();
+  assert_eq!(
+    err.failed_commands(),
+    [
+      RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME,
+      RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO,
+      RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS,
+    ]
+  );
+  assert_eq!(
+    err.to_string(),
+    "the frontend rejected RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME, RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO, RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS"
+  );
+  // Later settings are still applied after a failure.
+  assert!(commands.get().contains(&RETRO_ENVIRONMENT_SET_VARIABLES));
+}
+
+/// Returns an environment that supports core options `version`, answers
+/// `RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2` with `categories`, and only knows
+/// the options declared if `accepted`. Every set command is recorded.
+fn core_options_environment(
+  version: c_uint,
+  categories: bool,
+  accepted: bool,
+) -> (non_null_retro_environment_t, Commands) {
+  let commands = Commands::default();
+  let recorded = commands.clone();
+  let env = environment(move |cmd, data| match cmd {
+    RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION => {
+      unsafe { *(data as *mut c_uint) = version };
+      true
+    }
+    RETRO_ENVIRONMENT_GET_VARIABLE if accepted => {
+      let variable = unsafe { &mut *(data as *mut retro_variable) };
+      variable.value = c"1x".as_ptr();
+      true
+    }
+    RETRO_ENVIRONMENT_GET_VARIABLE => false,
+    RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2 => {
+      recorded.0.borrow_mut().push(cmd);
+      categories
+    }
+    cmd => {
+      recorded.0.borrow_mut().push(cmd);
+      true
+    }
+  });
+  (env, commands)
+}
+
+#[test]
+fn environment_config_reports_core_options_that_werent_declared() {
+  let options = CoreOptions::new().option(CoreOption::new("test_speed", "Speed").value("1x"));
+  let int_options = [IntOption::new("test_frameskip", "Frameskip", 0, 5)];
+  let config = EnvironmentConfig::new().core_options(&options);
+  let with_fallback = config.int_options(&int_options);
+
+  // Accepted, whether or not the frontend shows categories.
+  for categories in [true, false] {
+    let (mut env, commands) = core_options_environment(2, categories, true);
+    assert_eq!(with_fallback.configure(&mut env), Ok(()));
+    assert_eq!(commands.get(), [RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2]);
+  }
+
+  // Rejected, so the int options are declared instead if there are any.
+  let (mut env, commands) = core_options_environment(2, false, false);
+  assert_eq!(with_fallback.configure(&mut env), Ok(()));
+  assert_eq!(
+    commands.get(),
+    [
+      RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2,
+      RETRO_ENVIRONMENT_SET_VARIABLES
+    ]
+  );
+  let (mut env, _) = core_options_environment(2, false, false);
+  let err = config.configure(&mut env).unwrap_err();
+  assert_eq!(
+    err.failed_commands(),
+    [RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2]
+  );
+
+  // Not supported by the frontend.
+  let (mut env, commands) = core_options_environment(1, true, true);
+  assert_eq!(with_fallback.configure(&mut env), Ok(()));
+  assert_eq!(commands.get(), [RETRO_ENVIRONMENT_SET_VARIABLES]);
+  let (mut env, commands) = core_options_environment(1, true, true);
+  let err = config.configure(&mut env).unwrap_err();
+  assert_eq!(
+    err.failed_commands(),
+    [RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2]
+  );
+  assert!(commands.get().is_empty());
+}
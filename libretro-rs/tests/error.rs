@@ -0,0 +1,51 @@
+//! Tests for the source chain of [`CoreError`].
+
+use libretro_rs::prelude::*;
+use std::error::Error;
+use std::io;
+
+fn source_of(error: &CoreError) -> String {
+  error.source().expect("missing source").to_string()
+}
+
+#[test]
+fn conversions_keep_the_underlying_error_as_source() {
+  let error = CoreError::from(CommandError::new());
+  assert_eq!(source_of(&error), CommandError::new().to_string());
+
+  let error =
+    CoreError::from(SliceFrameBuffer::with_width(vec![XRGB8888::DEFAULT; 3], 2).unwrap_err());
+  assert!(error.source().unwrap().is::<FrameBufferError>());
+
+  let error = CoreError::from(io::Error::new(io::ErrorKind::NotFound, "missing.rom"));
+  assert_eq!(source_of(&error), "missing.rom");
+  assert_eq!(
+    error
+      .source()
+      .unwrap()
+      .downcast_ref::<io::Error>()
+      .unwrap()
+      .kind(),
+    io::ErrorKind::NotFound
+  );
+}
+
+#[test]
+fn question_mark_converts_to_core_error() {
+  fn load() -> Result<(), CoreError> {
+    Err(io::Error::other("bad header"))?;
+    Ok(())
+  }
+  let error = load().unwrap_err();
+  assert_eq!(
+    error.to_string(),
+    "a libretro API function call did not succeed"
+  );
+  assert_eq!(source_of(&error), "bad header");
+}
+
+#[test]
+fn errors_without_a_cause_have_no_source() {
+  assert!(CoreError::new().source().is_none());
+  assert!(CoreError::with_source("custom").source().is_some());
+}
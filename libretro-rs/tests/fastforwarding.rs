@@ -0,0 +1,117 @@
+//! Overrides fast-forwarding from a core defined in this test, and reads the
+//! override back through the environment passed to `run`.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Whether the frontend accepts overrides, and whether it's fast-forwarding.
+static SUPPORTED: AtomicBool = AtomicBool::new(true);
+static FASTFORWARDING: AtomicBool = AtomicBool::new(false);
+
+/// The override the core sets on its next run, if any.
+static NEXT_OVERRIDE: Mutex<Option<FastForwardingOverride>> = Mutex::new(None);
+
+/// What the core read back on each run, as `(fastforward, overridden, inhibited)`.
+static STATES: Mutex<Vec<(Option<bool>, bool, bool)>> = Mutex::new(Vec::new());
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("fast-forwarding test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    if let Some(data) = NEXT_OVERRIDE.lock().unwrap().take() {
+      env.set_fastforwarding_override(data).ok();
+    }
+    STATES.lock().unwrap().push((
+      env.fastforwarding_override().map(|data| data.fastforward()),
+      env.is_fastforwarding_overridden(),
+      env.is_fastforwarding_toggle_inhibited(),
+    ));
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_SET_FASTFORWARDING_OVERRIDE => {
+      let supported = SUPPORTED.load(Ordering::SeqCst);
+      if supported && !data.is_null() {
+        let data = &*(data as *const retro_fastforwarding_override);
+        FASTFORWARDING.store(data.fastforward, Ordering::SeqCst);
+      }
+      supported
+    }
+    RETRO_ENVIRONMENT_GET_FASTFORWARDING => {
+      *(data as *mut bool) = FASTFORWARDING.load(Ordering::SeqCst);
+      true
+    }
+    _ => false,
+  }
+}
+
+unsafe fn run_with(data: Option<FastForwardingOverride>) -> (Option<bool>, bool, bool) {
+  *NEXT_OVERRIDE.lock().unwrap() = data;
+  retro_run();
+  STATES.lock().unwrap().pop().unwrap()
+}
+
+#[test]
+fn override_is_readable_after_being_set() {
+  unsafe {
+    start(environment);
+    assert_eq!(run_with(None), (None, false, false));
+
+    let inhibited = FastForwardingOverride::new(true).set_inhibit_toggle(true);
+    assert_eq!(run_with(Some(inhibited)), (Some(true), true, true));
+    assert_eq!(run_with(None), (Some(true), true, true));
+
+    // The user can't toggle it, but the frontend may stop fast-forwarding anyway.
+    FASTFORWARDING.store(false, Ordering::SeqCst);
+    assert_eq!(run_with(None), (Some(true), false, true));
+
+    assert_eq!(
+      run_with(Some(FastForwardingOverride::new(false))),
+      (Some(false), true, false)
+    );
+
+    // A rejected override leaves the previous one in place.
+    SUPPORTED.store(false, Ordering::SeqCst);
+    assert_eq!(run_with(Some(inhibited)), (Some(false), true, false));
+    stop();
+
+    // Overrides don't outlive the core.
+    retro_init();
+    assert!(retro_load_game(core::ptr::null()));
+    assert_eq!(run_with(None), (None, false, false));
+    stop();
+  }
+}
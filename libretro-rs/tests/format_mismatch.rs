@@ -0,0 +1,87 @@
+//! Uploads a frame whose pixels don't match the negotiated pixel format from
+//! a core defined in this test, and checks that debug builds catch it.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// The message of the last panic.
+static PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+/// The number of frames received by the frontend.
+static FRAMES: AtomicUsize = AtomicUsize::new(0);
+
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  pixel_format: ActiveFormat<RGB565>,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("format mismatch test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let pixel_format = env
+      .set_pixel_format_rgb565(pixel_format)
+      .map_err(|_| CoreError::new())?;
+    Ok(Core { rendering_mode, pixel_format })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(2, 2))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let polled = callbacks.poll_inputs();
+    // Built for the wrong format.
+    let frame = ArrayFrameBuffer::<XRGB8888, 4, 2>::default();
+    callbacks.upload_video_frame(&self.rendering_mode, &self.pixel_format, &frame);
+    polled
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+extern "C" fn video_refresh(_data: *const c_void, _width: c_uint, _height: c_uint, _pitch: usize) {
+  FRAMES.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn frames_of_another_format_are_rejected_in_debug_builds() {
+  std::panic::set_hook(Box::new(|info| {
+    *PANIC.lock().unwrap() = info.payload().downcast_ref::<String>().cloned();
+  }));
+  unsafe {
+    start(common::environment);
+    retro_set_video_refresh(video_refresh);
+    retro_run();
+    stop();
+  }
+  let _ = std::panic::take_hook();
+
+  assert_eq!(FRAMES.load(Ordering::SeqCst), 0);
+  let panic = PANIC
+    .lock()
+    .unwrap()
+    .take()
+    .expect("the upload didn't panic");
+  assert!(panic.contains("negotiated pixel format"), "{panic}");
+}
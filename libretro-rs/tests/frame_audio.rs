@@ -0,0 +1,79 @@
+//! Checks that audio pushed to [`Callbacks::frame_audio`] is uploaded by the
+//! `retro_run` symbol exported by [`libretro_core`], with a core defined in
+//! this test.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// Every batch uploaded by the core, in order.
+static BATCHES: Mutex<Vec<Vec<i16>>> = Mutex::new(Vec::new());
+
+struct Core {
+  frame: i16,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("frame audio test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core { frame: 0 })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    // Push in small chunks; odd frames don't produce any audio.
+    if self.frame % 2 == 0 {
+      for i in 0..3 {
+        callbacks.frame_audio().push_frame(self.frame, i);
+      }
+      callbacks.frame_audio().push_samples(&[-1, -2]);
+    }
+    self.frame += 1;
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn audio_sample_batch(data: *const i16, frames: usize) -> usize {
+  let samples = core::slice::from_raw_parts(data, frames * 2);
+  BATCHES.lock().unwrap().push(samples.to_vec());
+  frames
+}
+
+#[test]
+fn frame_audio_is_flushed_once_per_run() {
+  unsafe {
+    start(env::null_environment);
+    retro_set_audio_sample_batch(audio_sample_batch);
+    for _ in 0..3 {
+      retro_run();
+    }
+    stop();
+  }
+  assert_eq!(
+    *BATCHES.lock().unwrap(),
+    [
+      vec![0, 0, 0, 1, 0, 2, -1, -2],
+      vec![2, 0, 2, 1, 2, 2, -1, -2]
+    ]
+  );
+}
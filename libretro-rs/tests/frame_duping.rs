@@ -0,0 +1,48 @@
+//! Checks that the `software-rendering` example only uploads frames that
+//! changed, and dupes the rest. This runs in its own test binary (and thus
+//! process) since the stub frontend's state is global.
+
+mod common;
+
+use common::*;
+use libloading::{Library, Symbol};
+use libretro_rs::ffi::*;
+use std::sync::atomic::Ordering;
+
+#[test]
+fn software_rendering_core_dupes_unchanged_frames() {
+  let path = build_example("software-rendering");
+  CAN_DUPE.store(true, Ordering::SeqCst);
+  unsafe {
+    let lib = Library::new(&path).expect("failed to load the example core");
+    start_example(&lib);
+    let run: Symbol<unsafe extern "C" fn()> = lib.get(b"retro_run").unwrap();
+
+    // The first frame is always uploaded.
+    run();
+    assert_eq!(DUPED_FRAMES.load(Ordering::SeqCst), 0);
+    let first = last_frame();
+
+    // Nothing changed, so the frame is duped.
+    run();
+    assert_eq!(FRAMES.load(Ordering::SeqCst), 2);
+    assert_eq!(DUPED_FRAMES.load(Ordering::SeqCst), 1);
+
+    // Holding A rotates the pixels, so a new frame is uploaded.
+    JOYPAD_BUTTONS.store(1 << RETRO_DEVICE_ID_JOYPAD_A, Ordering::SeqCst);
+    run();
+    assert_eq!(DUPED_FRAMES.load(Ordering::SeqCst), 1);
+    assert_eq!(
+      last_frame().xrgb8888(),
+      [0x00FFFFFF, 0x000000FF, 0x0000FF00, 0x00FF0000]
+    );
+    assert_ne!(last_frame(), first);
+
+    JOYPAD_BUTTONS.store(0, Ordering::SeqCst);
+    run();
+    assert_eq!(FRAMES.load(Ordering::SeqCst), 4);
+    assert_eq!(DUPED_FRAMES.load(Ordering::SeqCst), 2);
+
+    stop_example(&lib);
+  }
+}
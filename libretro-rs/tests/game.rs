@@ -0,0 +1,59 @@
+//! Tests for [`GameInfo`] that need real files.
+
+use c_utf8::CUtf8;
+use libretro_rs::prelude::*;
+use std::ffi::CString;
+
+const CONTENT: &[u8] = b"The quick brown fox jumps over the lazy dog";
+const CONTENT_CRC32: u32 = 0x414FA339;
+const CONTENT_SHA1: [u8; 20] = [
+  0x2F, 0xD4, 0xE1, 0xC6, 0x7A, 0x2D, 0x28, 0xFC, 0xED, 0x84, 0x9E, 0xE1, 0xBB, 0x76, 0xE7, 0x39,
+  0x1B, 0x93, 0xEB, 0x12,
+];
+
+#[test]
+fn content_hash_of_data() {
+  let info = GameInfo::from_data(GameData::new(CONTENT, None, None));
+  assert_eq!(info.content_crc32().unwrap(), CONTENT_CRC32);
+  assert_eq!(info.content_sha1().unwrap(), CONTENT_SHA1);
+}
+
+#[test]
+fn content_hash_of_path() {
+  let file = std::env::temp_dir().join(format!("libretro-rs-content-{}", std::process::id()));
+  std::fs::write(&file, CONTENT).unwrap();
+  let path = CString::new(file.to_str().unwrap()).unwrap();
+  let path = CUtf8::from_c_str(&path).unwrap();
+  let info = GameInfo::from_path(GamePath::new(path, None));
+  let hashes = (info.content_crc32(), info.content_sha1());
+  std::fs::remove_file(&file).unwrap();
+  assert_eq!(hashes.0.unwrap(), CONTENT_CRC32);
+  assert_eq!(hashes.1.unwrap(), CONTENT_SHA1);
+}
+
+#[test]
+fn content_hash_of_missing_path() {
+  let info = GameInfo::from_path(GamePath::new(c_utf8!("/nonexistent/game.rom"), None));
+  assert!(info.content_crc32().is_err());
+}
+
+#[test]
+fn cursor_reads_the_header_of_data() {
+  use std::io::Read;
+  let rom = [0x4E, 0x45, 0x53, 0x1A, 0x02, 0x01];
+  let info = GameInfo::from_data(GameData::new(&rom, None, None));
+  let mut cursor = info.cursor().unwrap();
+  let mut magic = [0; 4];
+  cursor.read_exact(&mut magic).unwrap();
+  assert_eq!(u32::from_le_bytes(magic), 0x1A53454E);
+  assert_eq!(cursor.position(), 4);
+}
+
+#[test]
+fn cursor_is_none_for_paths() {
+  assert!(
+    GameInfo::from_path(GamePath::new(c_utf8!("/tmp/game.rom"), None))
+      .cursor()
+      .is_none()
+  );
+}
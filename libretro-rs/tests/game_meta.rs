@@ -0,0 +1,66 @@
+//! Loads content with metadata through the `retro_load_game` symbol exported
+//! by [`libretro_core`], with a core defined in this test.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// The metadata seen by the core while loading.
+static META: Mutex<Option<Option<String>>> = Mutex::new(None);
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("meta test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_game<E: env::LoadGame>(
+    game: &GameInfo,
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let meta = game.meta().map(|meta| meta.to_str().unwrap().to_owned());
+    *META.lock().unwrap() = Some(meta);
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+#[test]
+fn meta_is_accessible_during_load() {
+  let content = [1, 2, 3];
+  let meta = c_utf8!("extracted from game.zip").as_ref();
+  let with_meta = retro_game_info::from(GameData::new(&content, None, Some(meta)));
+  let without_meta = retro_game_info::from(GameData::new(&content, None, None));
+  unsafe {
+    init(env::null_environment);
+    assert!(retro_load_game(&with_meta));
+    assert_eq!(
+      META.lock().unwrap().take(),
+      Some(Some("extracted from game.zip".to_owned()))
+    );
+    retro_unload_game();
+    assert!(retro_load_game(&without_meta));
+    assert_eq!(META.lock().unwrap().take(), Some(None));
+    stop();
+  }
+}
@@ -0,0 +1,100 @@
+//! Reads the buttons and sticks of the analog device from the `run` of a core
+//! defined in this test, through the symbols exported by [`libretro_core`].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// The buttons the frontend reports as pressed: Start and R1.
+const BUTTONS: u16 = 1 << RETRO_DEVICE_ID_JOYPAD_START | 1 << RETRO_DEVICE_ID_JOYPAD_R;
+
+/// Every `(port, device)` queried by the core, in order.
+static DEVICES: Mutex<Vec<(c_uint, c_uint)>> = Mutex::new(Vec::new());
+
+static GAMEPADS: Mutex<Vec<Gamepad>> = Mutex::new(Vec::new());
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("gamepad test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let inputs_polled = callbacks.poll_inputs();
+    let gamepad = callbacks.read_gamepad(DevicePort::new(2));
+    GAMEPADS.lock().unwrap().push(gamepad);
+    inputs_polled
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn input_state(port: c_uint, device: c_uint, index: c_uint, id: c_uint) -> i16 {
+  DEVICES.lock().unwrap().push((port, device));
+  match device {
+    RETRO_DEVICE_JOYPAD => (BUTTONS >> id & 1) as i16,
+    RETRO_DEVICE_ANALOG => match (index, id) {
+      (RETRO_DEVICE_INDEX_ANALOG_LEFT, RETRO_DEVICE_ID_ANALOG_X) => 1000,
+      (RETRO_DEVICE_INDEX_ANALOG_LEFT, RETRO_DEVICE_ID_ANALOG_Y) => -2000,
+      (RETRO_DEVICE_INDEX_ANALOG_RIGHT, RETRO_DEVICE_ID_ANALOG_X) => i16::MIN,
+      (RETRO_DEVICE_INDEX_ANALOG_RIGHT, RETRO_DEVICE_ID_ANALOG_Y) => i16::MAX,
+      _ => 0,
+    },
+    _ => 0,
+  }
+}
+
+#[test]
+fn buttons_and_sticks_are_read_from_the_same_port() {
+  unsafe {
+    start(env::null_environment);
+    retro_set_input_state(input_state);
+    retro_run();
+    stop();
+  }
+  let gamepad = GAMEPADS.lock().unwrap()[0];
+  assert_eq!(
+    gamepad,
+    Gamepad {
+      buttons: BUTTONS,
+      left_stick: (1000, -2000),
+      right_stick: (i16::MIN, i16::MAX),
+    }
+  );
+  assert!(gamepad.is_pressed(JoypadButton::Start));
+  assert!(gamepad.is_pressed(JoypadButton::R1));
+  assert!(!gamepad.is_pressed(JoypadButton::A));
+
+  let devices = DEVICES.lock().unwrap();
+  assert!(devices.iter().all(|&(port, _)| port == 2));
+  let joypad = devices
+    .iter()
+    .filter(|&&(_, device)| device == RETRO_DEVICE_JOYPAD);
+  let analog = devices
+    .iter()
+    .filter(|&&(_, device)| device == RETRO_DEVICE_ANALOG);
+  assert_eq!((joypad.count(), analog.count()), (16, 4));
+}
@@ -0,0 +1,51 @@
+//! Known-answer tests for [`Sha1`], covering the padding of messages that
+//! spill into a second block and the chaining of multi-block messages.
+
+use libretro_rs::retro::hash::{Hasher, Sha1};
+
+/// Parses a hex digest.
+fn digest(hex: &str) -> [u8; 20] {
+  let mut digest = [0; 20];
+  for (byte, pair) in digest.iter_mut().zip(hex.as_bytes().chunks(2)) {
+    *byte = u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap();
+  }
+  digest
+}
+
+#[test]
+fn sha1_of_messages_around_the_block_size() {
+  let cases = [
+    // The length still fits in the first block.
+    (55, "c1c8bbdc22796e28c0e15163d20899b65621d65a"),
+    // The padding spills into a second block.
+    (56, "c2db330f6083854c99d4b5bfb6e8f29f201be699"),
+    (63, "03f09f5b158a7a8cdad920bddc29b81c18a551f5"),
+    // Multi-block messages.
+    (64, "0098ba824b5c16427bd7a1122a5a442a25ec644d"),
+    (65, "11655326c708d70319be2610e8a57d9a5b959d3b"),
+    (1000, "291e9a6c66994949b57ba5e650361e98fc36b1ba"),
+  ];
+  for (len, hex) in cases {
+    assert_eq!(Sha1::hash(&vec![b'a'; len]), digest(hex), "{len} bytes");
+  }
+  assert_eq!(
+    Sha1::hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+    digest("84983e441c3bd26ebaae4aa1f95129e5e54670f1")
+  );
+}
+
+#[test]
+fn sha1_is_independent_of_how_the_data_is_split() {
+  let data = vec![b'a'; 1000];
+  for chunk_size in [1, 7, 55, 56, 63, 64, 65, 999] {
+    let mut hasher = Sha1::default();
+    for chunk in data.chunks(chunk_size) {
+      hasher.update(chunk);
+    }
+    assert_eq!(
+      hasher.finish(),
+      digest("291e9a6c66994949b57ba5e650361e98fc36b1ba"),
+      "chunks of {chunk_size} bytes"
+    );
+  }
+}
@@ -0,0 +1,72 @@
+//! Tests for the safe wrappers around the hardware rendering callbacks.
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+
+unsafe extern "C" fn get_current_framebuffer() -> usize {
+  42
+}
+
+unsafe extern "C" fn get_proc_address(_sym: *const c_char) -> retro_proc_address_t {
+  None
+}
+
+#[test]
+fn current_framebuffer_returns_the_frontend_fbo() {
+  let callbacks = GLContextCallbacks {
+    get_proc_address_cb: get_proc_address,
+    get_current_framebuffer_cb: get_current_framebuffer,
+  };
+  assert_eq!(callbacks.current_framebuffer(), 42);
+}
+
+extern "C" fn gl_clear() {}
+
+unsafe extern "C" fn get_known_proc_address(sym: *const c_char) -> retro_proc_address_t {
+  match std::ffi::CStr::from_ptr(sym).to_bytes() {
+    b"glClear" => Some(gl_clear),
+    _ => None,
+  }
+}
+
+#[test]
+fn get_proc_address_resolves_known_functions() {
+  let callbacks = GLContextCallbacks {
+    get_proc_address_cb: get_known_proc_address,
+    get_current_framebuffer_cb: get_current_framebuffer,
+  };
+  let gl_clear = gl_clear as extern "C" fn() as *mut c_void;
+  assert_eq!(
+    callbacks.get_proc_address(c_utf8!("glClear").as_c_str()),
+    core::ptr::NonNull::new(gl_clear)
+  );
+  assert_eq!(
+    callbacks.get_proc_address_str("glClear"),
+    core::ptr::NonNull::new(gl_clear)
+  );
+  assert_eq!(callbacks.get_proc_address_str("glUnknown"), None);
+  assert_eq!(callbacks.get_proc_address_str("gl\0Clear"), None);
+}
+
+#[test]
+fn negotiated_context_type_is_read_back_from_the_callback() {
+  let options = GLOptions::new(GLContextType::OpenGLCore4_5);
+  assert_eq!(options.get_negotiated(), Some(GLContextType::OpenGLCore4_5));
+
+  let mut callback: retro_hw_render_callback = options.into();
+  callback.version_major = 3;
+  callback.version_minor = 3;
+  assert_eq!(
+    GLOptions::from(callback).get_negotiated(),
+    Some(GLContextType::OpenGLCore3_3)
+  );
+
+  callback.version_minor = 1;
+  assert_eq!(GLOptions::from(callback).get_negotiated(), None);
+
+  let callback: retro_hw_render_callback = GLOptions::new(GLContextType::OpenGLES3).into();
+  assert_eq!(
+    GLOptions::from(callback).get_negotiated(),
+    Some(GLContextType::OpenGLES3)
+  );
+}
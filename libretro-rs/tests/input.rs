@@ -0,0 +1,146 @@
+//! Tests for [`InputMap`] and [`InputSnapshot`].
+
+use libretro_rs::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+  Jump,
+  Fire,
+  Pause,
+}
+
+fn input_map() -> InputMap<Action> {
+  let mut map = InputMap::new();
+  map.bind(Action::Jump, DevicePort::new(0), JoypadButton::B);
+  map.bind(Action::Jump, DevicePort::new(0), JoypadButton::Up);
+  map.bind(Action::Fire, DevicePort::new(0), JoypadButton::A);
+  map.bind(Action::Pause, DevicePort::new(1), JoypadButton::Start);
+  map
+}
+
+fn snapshot(pressed: &[(DevicePort, JoypadButton)]) -> InputSnapshot {
+  let mut snapshot = InputSnapshot::new();
+  for &(port, button) in pressed {
+    snapshot.set_pressed(port, button, true);
+  }
+  snapshot
+}
+
+#[test]
+fn action_is_active_if_any_binding_is_pressed() {
+  let map = input_map();
+  for button in [JoypadButton::B, JoypadButton::Up] {
+    let snapshot = snapshot(&[(DevicePort::new(0), button)]);
+    assert!(map.is_active(Action::Jump, &snapshot));
+    assert!(!map.is_active(Action::Fire, &snapshot));
+  }
+  assert!(!map.is_active(Action::Jump, &InputSnapshot::new()));
+}
+
+#[test]
+fn bindings_are_per_port() {
+  let map = input_map();
+  let snapshot = snapshot(&[
+    (DevicePort::new(1), JoypadButton::B),
+    (DevicePort::new(0), JoypadButton::Start),
+  ]);
+  assert!(!map.is_active(Action::Jump, &snapshot));
+  assert!(!map.is_active(Action::Pause, &snapshot));
+  let snapshot = self::snapshot(&[(DevicePort::new(1), JoypadButton::Start)]);
+  assert!(map.is_active(Action::Pause, &snapshot));
+}
+
+#[test]
+fn rebinding_replaces_previous_bindings() {
+  let mut map = input_map();
+  map.rebind(Action::Jump, DevicePort::new(0), JoypadButton::X);
+  assert_eq!(
+    map.bindings(&Action::Jump).collect::<Vec<_>>(),
+    [(DevicePort::new(0), JoypadButton::X)]
+  );
+  assert!(!map.is_active(
+    Action::Jump,
+    &snapshot(&[(DevicePort::new(0), JoypadButton::B)])
+  ));
+  assert!(map.is_active(
+    Action::Jump,
+    &snapshot(&[(DevicePort::new(0), JoypadButton::X)])
+  ));
+
+  map.unbind(&Action::Jump);
+  assert_eq!(map.bindings(&Action::Jump).count(), 0);
+  assert!(map.is_active(
+    Action::Fire,
+    &snapshot(&[(DevicePort::new(0), JoypadButton::A)])
+  ));
+}
+
+#[test]
+fn released_buttons_are_cleared() {
+  let mut snapshot = snapshot(&[(DevicePort::new(0), JoypadButton::R3)]);
+  assert!(snapshot.is_pressed(DevicePort::new(0), JoypadButton::R3));
+  snapshot.set_pressed(DevicePort::new(0), JoypadButton::R3, false);
+  assert!(!snapshot.is_pressed(DevicePort::new(0), JoypadButton::R3));
+}
+
+#[test]
+fn center_dead_zone_is_ignored_and_rest_is_rescaled() {
+  let dead_zone = DeadZone::from_percent(20);
+  assert_eq!(dead_zone.size(), 0.2);
+  for value in [0, 1, -1, 6000, -6553] {
+    assert_eq!(dead_zone.apply(value), 0, "{value}");
+  }
+  // Halfway between the dead zone and the edge.
+  assert_eq!(dead_zone.apply(19660), 16383);
+  assert_eq!(dead_zone.apply(-19660), -16383);
+  assert_eq!(dead_zone.apply_radial(3000, -3000), (0, 0));
+
+  // Curves bend the response but keep both ends.
+  let curved = DeadZone::new(0.0).curve(2.0);
+  assert_eq!(curved.apply(16384), 8192);
+  assert_eq!(curved.apply(i16::MAX), i16::MAX);
+}
+
+#[test]
+fn edges_are_clamped() {
+  let dead_zone = DeadZone::new(0.1);
+  assert_eq!(dead_zone.apply(i16::MAX), i16::MAX);
+  assert_eq!(dead_zone.apply(i16::MIN), -i16::MAX);
+  // A corner is outside of the unit circle, and gets pulled back onto it.
+  let (x, y) = dead_zone.apply_radial(i16::MAX, i16::MIN);
+  assert_eq!((x, y), (23170, -23170));
+  assert_eq!(dead_zone.apply_radial(0, i16::MIN), (0, -i16::MAX));
+  assert_eq!(DeadZone::new(2.0).apply(i16::MAX), 0);
+  assert_eq!(DeadZone::from_percent(-5), DeadZone::default());
+}
+
+/// Returns the pulses of a held turbo button over `frames` frames.
+fn pulses(turbo: TurboButton, frames: u64) -> String {
+  (0..frames)
+    .map(|frame| if turbo.is_pressed(true, frame) { '#' } else { '.' })
+    .collect()
+}
+
+#[test]
+fn turbo_pulses_with_the_period_and_duty_cycle() {
+  assert_eq!(pulses(TurboButton::new(2), 6), "#.#.#.");
+  assert_eq!(pulses(TurboButton::new(3), 6), "##.##.");
+  assert_eq!(pulses(TurboButton::with_duty_cycle(4, 1), 8), "#...#...");
+  assert_eq!(pulses(TurboButton::with_duty_cycle(4, 3), 8), "###.###.");
+  // Degenerate periods and duty cycles.
+  assert_eq!(pulses(TurboButton::new(0), 3), "###");
+  assert_eq!(pulses(TurboButton::with_duty_cycle(3, 0), 3), "...");
+  assert_eq!(TurboButton::with_duty_cycle(3, 5).pressed_frames(), 3);
+}
+
+#[test]
+fn turbo_reads_held_buttons_from_snapshot() {
+  let port = DevicePort::new(0);
+  let turbo = TurboButton::new(2);
+  let mut snapshot = InputSnapshot::new();
+  assert!(!turbo.is_pressed_in(&snapshot, port, JoypadButton::A, 0));
+  snapshot.set_pressed(port, JoypadButton::A, true);
+  assert!(turbo.is_pressed_in(&snapshot, port, JoypadButton::A, 0));
+  assert!(!turbo.is_pressed_in(&snapshot, port, JoypadButton::A, 1));
+  assert!(turbo.is_pressed_in(&snapshot, port, JoypadButton::A, u64::MAX - 1));
+}
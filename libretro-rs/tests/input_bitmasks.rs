@@ -0,0 +1,122 @@
+//! Checks that the symbols exported by [`libretro_core`] query input bitmask
+//! support once, and read joypads with a single query when it's supported,
+//! with a core defined in this test.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+const RUNS: usize = 10;
+
+/// The buttons reported as pressed by the frontend: A and R3.
+const PRESSED: u16 = 1 << RETRO_DEVICE_ID_JOYPAD_A | 1 << RETRO_DEVICE_ID_JOYPAD_R3;
+
+static BITMASK_QUERIES: AtomicUsize = AtomicUsize::new(0);
+
+/// The ids passed to the input state callback since the last check.
+static INPUT_IDS: Mutex<Vec<c_uint>> = Mutex::new(Vec::new());
+
+/// The joypad state read by the core on each run.
+static SNAPSHOTS: Mutex<Vec<(bool, u16, bool)>> = Mutex::new(Vec::new());
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("input bitmasks test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let inputs_polled = callbacks.poll_inputs();
+    let snapshot = InputSnapshot::capture(callbacks, 1);
+    SNAPSHOTS.lock().unwrap().push((
+      callbacks.supports_input_bitmasks(),
+      callbacks.joypad_buttons(DevicePort::new(0)),
+      snapshot.is_pressed(DevicePort::new(0), JoypadButton::R3),
+    ));
+    inputs_polled
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, _data: *mut c_void) -> bool {
+  if cmd == RETRO_ENVIRONMENT_GET_INPUT_BITMASKS {
+    BITMASK_QUERIES.fetch_add(1, Ordering::SeqCst);
+    return true;
+  }
+  false
+}
+
+unsafe extern "C" fn environment_without_bitmasks(cmd: c_uint, _data: *mut c_void) -> bool {
+  if cmd == RETRO_ENVIRONMENT_GET_INPUT_BITMASKS {
+    BITMASK_QUERIES.fetch_add(1, Ordering::SeqCst);
+  }
+  false
+}
+
+unsafe extern "C" fn input_state(_port: c_uint, device: c_uint, _index: c_uint, id: c_uint) -> i16 {
+  assert_eq!(device, RETRO_DEVICE_JOYPAD);
+  INPUT_IDS.lock().unwrap().push(id);
+  match id {
+    RETRO_DEVICE_ID_JOYPAD_MASK => PRESSED as i16,
+    _ => (PRESSED >> id & 1) as i16,
+  }
+}
+
+/// Loads the core with `environment`, runs it [RUNS] times and unloads it.
+unsafe fn run_game(environment: non_null_retro_environment_t) {
+  start(environment);
+  retro_set_input_state(input_state);
+  for _ in 0..RUNS {
+    retro_run();
+  }
+  stop();
+}
+
+#[test]
+fn bitmask_support_is_queried_once() {
+  unsafe {
+    run_game(environment);
+  }
+  assert_eq!(BITMASK_QUERIES.load(Ordering::SeqCst), 1);
+  let ids = core::mem::take(&mut *INPUT_IDS.lock().unwrap());
+  assert_eq!(ids, [RETRO_DEVICE_ID_JOYPAD_MASK; RUNS * 2]);
+  let snapshots = core::mem::take(&mut *SNAPSHOTS.lock().unwrap());
+  assert_eq!(snapshots, [(true, PRESSED, true); RUNS]);
+
+  // Setting the environment again queries the new frontend, which reads each
+  // button separately.
+  unsafe {
+    run_game(environment_without_bitmasks);
+  }
+  assert_eq!(BITMASK_QUERIES.load(Ordering::SeqCst), 2);
+  let ids = core::mem::take(&mut *INPUT_IDS.lock().unwrap());
+  assert_eq!(ids.len(), RUNS * 2 * 16);
+  assert!(!ids.contains(&RETRO_DEVICE_ID_JOYPAD_MASK));
+  let snapshots = core::mem::take(&mut *SNAPSHOTS.lock().unwrap());
+  assert_eq!(snapshots, [(false, PRESSED, true); RUNS]);
+}
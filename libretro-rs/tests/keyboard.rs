@@ -0,0 +1,70 @@
+//! Polls the keyboard from the `run` of a core defined in this test, through
+//! the symbols exported by [`libretro_core`].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// The keys the frontend reports as pressed.
+const PRESSED: [Key; 2] = [Key::Q, Key::LShift];
+
+/// The keys the core found pressed, in order.
+static KEYS: Mutex<Vec<Key>> = Mutex::new(Vec::new());
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("keyboard test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let inputs_polled = callbacks.poll_inputs();
+    let port = DevicePort::new(0);
+    let pressed = [Key::Q, Key::W, Key::LShift, Key::RShift, Key::Kp0]
+      .into_iter()
+      .filter(|&key| callbacks.key_pressed(port, key));
+    KEYS.lock().unwrap().extend(pressed);
+    inputs_polled
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn input_state(port: c_uint, device: c_uint, index: c_uint, id: c_uint) -> i16 {
+  assert_eq!((port, device, index), (0, RETRO_DEVICE_KEYBOARD, 0));
+  PRESSED.iter().any(|&key| c_uint::from(key) == id) as i16
+}
+
+#[test]
+fn pressed_keys_are_read_from_the_keyboard() {
+  unsafe {
+    start(env::null_environment);
+    retro_set_input_state(input_state);
+    retro_run();
+    stop();
+  }
+  assert_eq!(*KEYS.lock().unwrap(), PRESSED);
+}
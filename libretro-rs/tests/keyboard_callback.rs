@@ -0,0 +1,153 @@
+//! Forwards keyboard events from the frontend to a core defined in this test,
+//! through the callback registered by [`libretro_core`].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// The callback registered by the core.
+static CALLBACK: Mutex<retro_keyboard_event_t> = Mutex::new(None);
+
+/// The events received by the core, in order.
+static EVENTS: Mutex<Vec<(bool, Key, u32, KeyModifiers)>> = Mutex::new(Vec::new());
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+  const KEYBOARD_EVENTS: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("keyboard callback test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn keyboard_event(&mut self, down: bool, key: Key, character: u32, modifiers: KeyModifiers) {
+    EVENTS
+      .lock()
+      .unwrap()
+      .push((down, key, character, modifiers));
+  }
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_SET_KEYBOARD_CALLBACK => {
+      let callback = &*(data as *const retro_keyboard_callback);
+      *CALLBACK.lock().unwrap() = callback.callback;
+      true
+    }
+    _ => false,
+  }
+}
+
+#[test]
+fn keyboard_events_are_forwarded_while_a_game_is_loaded() {
+  use retro_key::*;
+  use retro_mod::*;
+  unsafe {
+    init(environment);
+    let callback = CALLBACK
+      .lock()
+      .unwrap()
+      .expect("keyboard callback registered");
+
+    // Events before a game is loaded are dropped.
+    callback(true, RETROK_b as c_uint, 0, 0);
+    assert!(retro_load_game(core::ptr::null()));
+
+    let modifiers = (RETROKMOD_SHIFT as u16) | (RETROKMOD_CAPSLOCK as u16);
+    callback(true, RETROK_a as c_uint, 'A' as u32, modifiers);
+    callback(false, RETROK_a as c_uint, 0, modifiers);
+    // Events are queued on any thread, and delivered on the next run.
+    std::thread::spawn(move || callback(true, 0xFFFF, 'é' as u32, 0))
+      .join()
+      .unwrap();
+    assert!(EVENTS.lock().unwrap().is_empty());
+    retro_run();
+    // Events that weren't delivered before unloading are dropped.
+    callback(true, RETROK_c as c_uint, 0, 0);
+    retro_unload_game();
+    callback(false, RETROK_b as c_uint, 0, 0);
+    retro_deinit();
+  }
+
+  let shift_caps = KeyModifiers::SHIFT | KeyModifiers::CAPSLOCK;
+  assert_eq!(
+    *EVENTS.lock().unwrap(),
+    [
+      (true, Key::A, 'A' as u32, shift_caps),
+      (false, Key::A, 0, shift_caps),
+      (true, Key::Unknown, 'é' as u32, KeyModifiers::NONE),
+    ]
+  );
+}
+
+#[test]
+fn modifiers_are_decoded_from_retrokmod_bits() {
+  use retro_mod::*;
+  let decode = |mods: &[retro_mod]| {
+    let bits = mods.iter().fold(0, |bits, &m| bits | m as u16);
+    let modifiers = KeyModifiers::new(bits);
+    [
+      modifiers.shift(),
+      modifiers.ctrl(),
+      modifiers.alt(),
+      modifiers.meta(),
+      modifiers.numlock(),
+      modifiers.capslock(),
+      modifiers.scrolllock(),
+    ]
+  };
+  assert_eq!(decode(&[RETROKMOD_NONE]), [false; 7]);
+  assert_eq!(
+    decode(&[RETROKMOD_CTRL, RETROKMOD_ALT]),
+    [false, true, true, false, false, false, false]
+  );
+  assert_eq!(
+    decode(&[
+      RETROKMOD_SHIFT,
+      RETROKMOD_META,
+      RETROKMOD_NUMLOCK,
+      RETROKMOD_SCROLLOCK
+    ]),
+    [true, false, false, true, true, false, true]
+  );
+  assert_eq!(
+    decode(&[
+      RETROKMOD_SHIFT,
+      RETROKMOD_CTRL,
+      RETROKMOD_ALT,
+      RETROKMOD_META,
+      RETROKMOD_NUMLOCK,
+      RETROKMOD_CAPSLOCK,
+      RETROKMOD_SCROLLOCK,
+    ]),
+    [true; 7]
+  );
+}
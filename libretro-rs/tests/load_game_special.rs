@@ -0,0 +1,85 @@
+//! Loads a two-slot subsystem through the `retro_load_game_special` symbol
+//! exported by [`libretro_core`], with a core defined in this test.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use libretro_rs::retro::env::LoadGameSpecial;
+use std::sync::Mutex;
+
+const SUBSYSTEM: c_uint = 0x101;
+
+/// The data of each slot seen by the core, in order.
+static SLOTS: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("subsystem test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+impl<'a> SpecialGameCore<'a> for Core {
+  fn load_game<'b>(
+    _env: &mut impl LoadGameSpecial,
+    _init_state: &'a mut Self::Init,
+    game_type: GameType,
+    games: &[SpecialGameInfo],
+  ) -> Result<Self, LoadGameError<Self::Init>> {
+    if game_type != GameType::new(SUBSYSTEM) || games.len() != 2 {
+      return Err(LoadGameError::new(()));
+    }
+    let mut slots = SLOTS.lock().unwrap();
+    slots.extend(games.iter().map(|game| match game.as_data() {
+      Some(data) => data.data().to_vec(),
+      None => Vec::new(),
+    }));
+    Ok(Core)
+  }
+}
+
+test_core!();
+
+#[test]
+fn load_game_special_passes_every_slot_to_the_core() {
+  let bios = [1, 2, 3];
+  let cartridge = [4, 5];
+  let games = [
+    retro_game_info::from(GameData::new(&bios, None, None)),
+    retro_game_info::from(GameData::new(&cartridge, None, None)),
+  ];
+  unsafe {
+    init(env::null_environment);
+    assert!(!retro_load_game_special(
+      SUBSYSTEM + 1,
+      games.as_ptr(),
+      games.len()
+    ));
+    assert!(!retro_load_game_special(SUBSYSTEM, core::ptr::null(), 0));
+    assert!(retro_load_game_special(
+      SUBSYSTEM,
+      games.as_ptr(),
+      games.len()
+    ));
+    assert_eq!(*SLOTS.lock().unwrap(), [vec![1, 2, 3], vec![4, 5]]);
+    stop();
+  }
+}
@@ -0,0 +1,100 @@
+//! Tests for the level filtering of [`FallbackLogger`].
+
+use libretro_rs::c_utf8::CUtf8;
+use libretro_rs::ffi::retro_log_level::{self, *};
+use libretro_rs::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+/// Records every message it receives.
+struct Recorder<'a>(&'a RefCell<Vec<(retro_log_level, String)>>);
+
+impl LogInterface for Recorder<'_> {
+  fn log(&mut self, level: retro_log_level, message: &CUtf8) {
+    self
+      .0
+      .borrow_mut()
+      .push((level, message.as_str().to_owned()));
+  }
+}
+
+/// Counts how many times it's formatted.
+struct Counted<'a>(&'a Cell<usize>, &'static str);
+
+impl fmt::Display for Counted<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.0.set(self.0.get() + 1);
+    f.write_str(self.1)
+  }
+}
+
+#[test]
+fn log_levels_are_ordered_by_severity() {
+  use RetroLogLevel::*;
+  assert!(Debug < Info && Info < Warn && Warn < Error);
+  assert_eq!(retro_log_level::from(Warn), RETRO_LOG_WARN);
+}
+
+#[test]
+fn debug_messages_are_dropped_at_info_threshold() {
+  let messages = RefCell::new(Vec::new());
+  let formatted = Cell::new(0);
+  let mut logger =
+    FallbackLogger::new(Some(Recorder(&messages))).with_min_level(RetroLogLevel::Info);
+
+  logger.log_display(RetroLogLevel::Debug, Counted(&formatted, "dropped"));
+  logger.debug(c_utf8!("dropped"));
+  assert_eq!(formatted.get(), 0);
+  assert!(messages.borrow().is_empty());
+
+  logger.log_display(RetroLogLevel::Info, Counted(&formatted, "kept"));
+  logger.log_display(RetroLogLevel::Error, format_args!("frame {}", 42));
+  logger.warn(c_utf8!("also kept"));
+  assert_eq!(formatted.get(), 1);
+  assert_eq!(
+    *messages.borrow(),
+    [
+      (RETRO_LOG_INFO, "kept".to_owned()),
+      (RETRO_LOG_ERROR, "frame 42".to_owned()),
+      (RETRO_LOG_WARN, "also kept".to_owned()),
+    ]
+  );
+}
+
+#[test]
+fn everything_is_logged_by_default() {
+  let messages = RefCell::new(Vec::new());
+  let mut logger = FallbackLogger::new(Some(Recorder(&messages)));
+  assert_eq!(logger.min_level(), RetroLogLevel::Debug);
+  logger.log_display(RetroLogLevel::Debug, "debug");
+  assert_eq!(*messages.borrow(), [(RETRO_LOG_DEBUG, "debug".to_owned())]);
+
+  logger.set_min_level(RetroLogLevel::Error);
+  assert!(!logger.is_enabled(RetroLogLevel::Warn));
+  assert!(logger.is_enabled(RetroLogLevel::Error));
+}
+
+#[test]
+fn log_macros_format_through_the_logger() {
+  use libretro_rs::{debug, error, info, warn};
+
+  let messages = RefCell::new(Vec::new());
+  let formatted = Cell::new(0);
+  let mut logger =
+    FallbackLogger::new(Some(Recorder(&messages))).with_min_level(RetroLogLevel::Info);
+
+  debug!(logger, "{}", Counted(&formatted, "dropped"));
+  info!(logger, "frame {}", 42);
+  // Loggers can also be passed by reference.
+  let by_ref = &mut logger;
+  warn!(by_ref, "{}", Counted(&formatted, "kept"));
+  error!(Recorder(&messages), "nul\0dropped");
+  assert_eq!(formatted.get(), 1);
+  assert_eq!(
+    *messages.borrow(),
+    [
+      (RETRO_LOG_INFO, "frame 42".to_owned()),
+      (RETRO_LOG_WARN, "kept".to_owned()),
+    ]
+  );
+}
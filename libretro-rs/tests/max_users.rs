@@ -0,0 +1,90 @@
+//! Sizes the per-port input of a core defined in this test by the number of
+//! users the frontend supports, through the symbols exported by
+//! [`libretro_core`].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+/// The number of users the frontend reports.
+const MAX_USERS: c_uint = 4;
+
+/// Every port queried by the core.
+static PORTS: Mutex<BTreeSet<c_uint>> = Mutex::new(BTreeSet::new());
+
+struct Core {
+  gamepads: Vec<Gamepad>,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("max users test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let max_users = args.env.get_input_max_users()?;
+    Ok(Core {
+      gamepads: vec![Gamepad::default(); max_users as usize],
+    })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let polled = callbacks.poll_inputs();
+    for (port, gamepad) in (0..).zip(&mut self.gamepads) {
+      *gamepad = callbacks.read_gamepad(DevicePort::new(port));
+    }
+    polled
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_GET_INPUT_MAX_USERS => {
+      *(data as *mut c_uint) = MAX_USERS;
+      true
+    }
+    _ => false,
+  }
+}
+
+unsafe extern "C" fn input_state(
+  port: c_uint,
+  _device: c_uint,
+  _index: c_uint,
+  _id: c_uint,
+) -> i16 {
+  PORTS.lock().unwrap().insert(port);
+  0
+}
+
+#[test]
+fn every_user_reported_by_the_frontend_is_read() {
+  unsafe {
+    start(environment);
+    retro_set_input_state(input_state);
+    retro_run();
+    stop();
+  }
+  assert_eq!(*PORTS.lock().unwrap(), BTreeSet::from([0, 1, 2, 3]));
+}
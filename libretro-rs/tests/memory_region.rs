@@ -0,0 +1,83 @@
+//! Exposes the memory of a core defined in this test through the
+//! `retro_get_memory_*` symbols exported by [`libretro_core`].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+
+struct Core {
+  save_ram: Vec<u8>,
+  system_ram: Vec<u8>,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("memory region test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core { save_ram: vec![0; 8], system_ram: Vec::new() })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    self.save_ram[0] += 1;
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn get_memory_region(&mut self, id: MemoryType) -> Option<&mut [u8]> {
+    match StandardMemoryType::try_from(id) {
+      Ok(StandardMemoryType::SaveRam) => Some(&mut self.save_ram),
+      Ok(StandardMemoryType::SystemRam) => Some(&mut self.system_ram),
+      _ => None,
+    }
+  }
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+#[test]
+fn memory_regions_are_passed_as_pointer_and_size() {
+  unsafe {
+    init(env::null_environment);
+    // There's no core to ask before a game is loaded.
+    assert_eq!(retro_get_memory_size(RETRO_MEMORY_SAVE_RAM), 0);
+    assert!(retro_get_memory_data(RETRO_MEMORY_SAVE_RAM).is_null());
+    assert!(retro_load_game(core::ptr::null()));
+
+    // The frontend writes save RAM in place, and sees the core's changes.
+    assert_eq!(retro_get_memory_size(RETRO_MEMORY_SAVE_RAM), 8);
+    let save_ram = retro_get_memory_data(RETRO_MEMORY_SAVE_RAM) as *mut u8;
+    assert!(!save_ram.is_null());
+    *save_ram = 41;
+    retro_run();
+    assert_eq!(*save_ram, 42);
+
+    // Empty and missing regions are reported as null with a size of 0.
+    assert_eq!(retro_get_memory_size(RETRO_MEMORY_SYSTEM_RAM), 0);
+    assert!(retro_get_memory_data(RETRO_MEMORY_SYSTEM_RAM).is_null());
+    assert_eq!(retro_get_memory_size(RETRO_MEMORY_VIDEO_RAM), 0);
+    assert!(retro_get_memory_data(RETRO_MEMORY_VIDEO_RAM).is_null());
+
+    retro_unload_game();
+    assert_eq!(retro_get_memory_size(RETRO_MEMORY_SAVE_RAM), 0);
+    assert!(retro_get_memory_data(RETRO_MEMORY_SAVE_RAM).is_null());
+    retro_deinit();
+  }
+}
@@ -0,0 +1,57 @@
+//! Tests for [`MemoryWatch`].
+
+use libretro_rs::prelude::*;
+
+fn watch() -> MemoryWatch {
+  MemoryWatch::new(StandardMemoryType::SystemRam.into())
+}
+
+#[test]
+fn first_update_is_dirty() {
+  let mut watch = watch();
+  assert!(watch.is_dirty());
+  assert!(watch.update(&[0; 64]));
+  assert!(watch.is_dirty());
+}
+
+#[test]
+fn unchanged_region_is_clean() {
+  let ram = [0x55; 64];
+  let mut watch = watch();
+  watch.update(&ram);
+  for _ in 0..3 {
+    assert!(!watch.update(&ram));
+    assert!(!watch.is_dirty());
+  }
+}
+
+#[test]
+fn writes_mark_region_dirty() {
+  let mut ram = vec![0u8; 2048];
+  let mut watch = watch();
+  watch.update(&ram);
+  for index in [0, 1, 1023, 2047] {
+    ram[index] ^= 0x80;
+    assert!(watch.update(&ram), "write at {index} wasn't detected");
+    assert!(!watch.update(&ram));
+  }
+}
+
+#[test]
+fn resizing_marks_region_dirty() {
+  let mut watch = watch();
+  watch.update(&[0; 16]);
+  assert!(watch.update(&[0; 32]));
+  assert!(watch.update(&[]));
+}
+
+#[test]
+fn reset_marks_region_dirty() {
+  let ram = [1, 2, 3];
+  let mut watch = watch();
+  watch.update(&ram);
+  watch.reset();
+  assert!(watch.is_dirty());
+  assert!(watch.update(&ram));
+  assert_eq!(watch.id(), StandardMemoryType::SystemRam.into());
+}
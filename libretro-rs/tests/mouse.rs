@@ -0,0 +1,108 @@
+//! Reads the mouse from the `run` of a core defined in this test, through the
+//! symbols exported by [`libretro_core`].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// The ids the frontend reports as pressed.
+static PRESSED: Mutex<Vec<c_uint>> = Mutex::new(Vec::new());
+
+/// The mouse states read by the core, in order.
+static STATES: Mutex<Vec<MouseState>> = Mutex::new(Vec::new());
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("mouse test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let inputs_polled = callbacks.poll_inputs();
+    let state = callbacks.mouse_state(DevicePort::new(1));
+    STATES.lock().unwrap().push(state);
+    inputs_polled
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn input_state(port: c_uint, device: c_uint, _index: c_uint, id: c_uint) -> i16 {
+  assert_eq!((port, device), (1, RETRO_DEVICE_MOUSE));
+  match id {
+    RETRO_DEVICE_ID_MOUSE_X => -5,
+    RETRO_DEVICE_ID_MOUSE_Y => 300,
+    id => PRESSED.lock().unwrap().contains(&id) as i16,
+  }
+}
+
+#[test]
+fn deltas_and_buttons_are_read() {
+  let buttons = [
+    (
+      RETRO_DEVICE_ID_MOUSE_LEFT,
+      MouseState { left: true, ..Default::default() },
+    ),
+    (
+      RETRO_DEVICE_ID_MOUSE_RIGHT,
+      MouseState { right: true, ..Default::default() },
+    ),
+    (
+      RETRO_DEVICE_ID_MOUSE_MIDDLE,
+      MouseState { middle: true, ..Default::default() },
+    ),
+    (
+      RETRO_DEVICE_ID_MOUSE_BUTTON_4,
+      MouseState { button_4: true, ..Default::default() },
+    ),
+    (
+      RETRO_DEVICE_ID_MOUSE_BUTTON_5,
+      MouseState { button_5: true, ..Default::default() },
+    ),
+    (
+      RETRO_DEVICE_ID_MOUSE_WHEELUP,
+      MouseState { wheel_up: true, ..Default::default() },
+    ),
+    (
+      RETRO_DEVICE_ID_MOUSE_WHEELDOWN,
+      MouseState { wheel_down: true, ..Default::default() },
+    ),
+  ];
+  unsafe {
+    start(env::null_environment);
+    retro_set_input_state(input_state);
+    for (id, _) in buttons {
+      *PRESSED.lock().unwrap() = vec![id];
+      retro_run();
+    }
+    stop();
+  }
+  let expected: Vec<_> = buttons
+    .iter()
+    .map(|&(_, state)| MouseState { x: -5, y: 300, ..state })
+    .collect();
+  assert_eq!(*STATES.lock().unwrap(), expected);
+}
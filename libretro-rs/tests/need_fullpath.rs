@@ -0,0 +1,72 @@
+//! Loads content by path into a core defined in this test that sets
+//! `need_fullpath`, through the symbols exported by [`libretro_core`].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// The path of the content the core was given, if it was passed by path.
+static LOADED_PATH: Mutex<Option<String>> = Mutex::new(None);
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("need fullpath test"), c_utf8!("1.0"), ext!["iso"])
+      .with_need_full_path()
+      .with_block_extract()
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_game<E: env::LoadGame>(
+    game: &GameInfo,
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let path = game.as_path().ok_or_else(CoreError::new)?;
+    *LOADED_PATH.lock().unwrap() = Some(path.path().as_str().to_owned());
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+#[test]
+fn content_is_passed_by_path_when_data_is_null() {
+  let mut info = retro_system_info::default();
+  unsafe { retro_get_system_info(&mut info) };
+  assert!(info.need_fullpath);
+  assert!(info.block_extract);
+
+  let game = retro_game_info {
+    path: c_utf8!("/roms/Big Game.iso").as_ptr(),
+    data: core::ptr::null(),
+    size: 0,
+    meta: core::ptr::null(),
+  };
+  unsafe {
+    init(env::null_environment);
+    assert!(retro_load_game(&game));
+    stop();
+  }
+  assert_eq!(
+    LOADED_PATH.lock().unwrap().as_deref(),
+    Some("/roms/Big Game.iso")
+  );
+}
@@ -0,0 +1,66 @@
+//! Starts a core defined in this test without content, relying on
+//! [`Core::SUPPORTS_NO_GAME`] instead of calling `set_support_no_game`.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static SUPPORT_NO_GAME: AtomicBool = AtomicBool::new(false);
+static LOADED_WITHOUT_CONTENT: AtomicBool = AtomicBool::new(false);
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("no game test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    LOADED_WITHOUT_CONTENT.store(true, Ordering::SeqCst);
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME => {
+      SUPPORT_NO_GAME.store(*(data as *const bool), Ordering::SeqCst);
+      true
+    }
+    _ => false,
+  }
+}
+
+#[test]
+fn core_without_content_is_advertised_and_loaded() {
+  unsafe {
+    start(environment);
+    assert!(SUPPORT_NO_GAME.load(Ordering::SeqCst));
+    assert!(LOADED_WITHOUT_CONTENT.load(Ordering::SeqCst));
+    stop();
+  }
+}
@@ -0,0 +1,125 @@
+//! Requests an OpenGL context from a core defined in this test, through the
+//! symbols exported by [`libretro_core`], and drives its context callbacks.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// The framebuffers returned by the callbacks the core got on each context
+/// reset, and `None` for each context destroy.
+static EVENTS: Mutex<Vec<Option<u32>>> = Mutex::new(Vec::new());
+
+/// The hardware render callback the core sent to the frontend.
+static HW_RENDER: Mutex<Option<retro_hw_render_callback>> = Mutex::new(None);
+
+/// The context type the core was told the frontend agreed to.
+static NEGOTIATED: Mutex<Option<GLContextType>> = Mutex::new(None);
+
+struct Core {
+  _rendering_mode: GLRenderEnabled,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("opengl test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let options = GLOptions::new(GLContextType::OpenGLCore3_3);
+    let rendering_mode = args
+      .env
+      .set_hw_render_gl(options)
+      .map_err(|_| CoreError::new())?;
+    *NEGOTIATED.lock().unwrap() = rendering_mode.negotiated();
+    Ok(Core { _rendering_mode: rendering_mode })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+unsafe impl<'a> retro::OpenGLCore<'a> for Core {
+  fn context_reset(&mut self, _env: &mut impl env::Environment, callbacks: GLContextCallbacks) {
+    EVENTS
+      .lock()
+      .unwrap()
+      .push(Some(callbacks.current_framebuffer()));
+  }
+
+  fn context_destroy(&mut self, _env: &mut impl env::Environment) {
+    EVENTS.lock().unwrap().push(None);
+  }
+}
+
+test_core!();
+
+unsafe extern "C" fn get_current_framebuffer() -> usize {
+  7
+}
+
+unsafe extern "C" fn get_proc_address(_sym: *const c_char) -> retro_proc_address_t {
+  None
+}
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_SET_HW_RENDER => {
+      let data = &mut *(data as *mut retro_hw_render_callback);
+      data.get_current_framebuffer = Some(get_current_framebuffer);
+      data.get_proc_address = Some(get_proc_address);
+      // Only 3.2 is available.
+      data.version_minor = 2;
+      *HW_RENDER.lock().unwrap() = Some(*data);
+      true
+    }
+    _ => false,
+  }
+}
+
+#[test]
+fn gl_context_callbacks_reach_the_core() {
+  unsafe {
+    start(environment);
+  }
+
+  let hw_render = HW_RENDER.lock().unwrap().take().unwrap();
+  assert_eq!(
+    hw_render.context_type,
+    retro_hw_context_type::RETRO_HW_CONTEXT_OPENGL_CORE
+  );
+  assert_eq!(hw_render.version_major, 3);
+  assert_eq!(
+    *NEGOTIATED.lock().unwrap(),
+    Some(GLContextType::OpenGLCore3_2)
+  );
+
+  unsafe {
+    hw_render.context_reset.unwrap()();
+    hw_render.context_destroy.unwrap()();
+    hw_render.context_reset.unwrap()();
+  }
+  assert_eq!(*EVENTS.lock().unwrap(), [Some(7), None, Some(7)]);
+
+  unsafe {
+    stop();
+  }
+}
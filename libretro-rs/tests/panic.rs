@@ -0,0 +1,163 @@
+//! Checks that the symbols exported by [`libretro_core`] catch panics in the
+//! `run` of a core defined in this test, and report them to the frontend.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// The number of calls to `run` that reached the core.
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// Every message set by the core, as `(msg, level, duration)`.
+static MESSAGES: Mutex<Vec<(String, retro_log_level, c_uint)>> = Mutex::new(Vec::new());
+
+/// The number of times the core asked for the log interface.
+static LOG_QUERIES: AtomicUsize = AtomicUsize::new(0);
+
+/// Every message logged by the core.
+static LOGGED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// The number of calls to `reset` and `unload_game` that reached the core.
+static RESETS: AtomicUsize = AtomicUsize::new(0);
+static UNLOADS: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of calls to `get_system_av_info` and `get_memory_region` that
+/// reached the core.
+static AV_INFOS: AtomicUsize = AtomicUsize::new(0);
+static MEMORY_REGIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// The line of the `panic!` in `run`.
+static PANIC_LINE: AtomicUsize = AtomicUsize::new(0);
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("panic test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    AV_INFOS.fetch_add(1, Ordering::SeqCst);
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    if RUNS.fetch_add(1, Ordering::SeqCst) == 1 {
+      PANIC_LINE.store(line!() as usize + 1, Ordering::SeqCst);
+      panic!("boom");
+    }
+    callbacks.poll_inputs()
+  }
+
+  fn get_memory_region(&mut self, _id: MemoryType) -> Option<&mut [u8]> {
+    MEMORY_REGIONS.fetch_add(1, Ordering::SeqCst);
+    None
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {
+    RESETS.fetch_add(1, Ordering::SeqCst);
+  }
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {
+    UNLOADS.fetch_add(1, Ordering::SeqCst);
+  }
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_GET_LOG_INTERFACE => {
+      LOG_QUERIES.fetch_add(1, Ordering::SeqCst);
+      // `log` isn't variadic, but reads the `%s` argument from the same
+      // register a variadic function would (except on Apple arm64, where
+      // variadic arguments are passed on the stack).
+      let log: unsafe extern "C" fn(retro_log_level, *const c_char, *const c_char) = log;
+      (*(data as *mut retro_log_callback)).log = Some(std::mem::transmute(log));
+      true
+    }
+    RETRO_ENVIRONMENT_SET_MESSAGE_EXT => {
+      let message = &*(data as *const retro_message_ext);
+      let msg = CStr::from_ptr(message.msg).to_str().unwrap().to_owned();
+      MESSAGES
+        .lock()
+        .unwrap()
+        .push((msg, message.level, message.duration));
+      true
+    }
+    _ => false,
+  }
+}
+
+unsafe extern "C" fn log(_level: retro_log_level, _fmt: *const c_char, message: *const c_char) {
+  let message = CStr::from_ptr(message).to_str().unwrap().to_owned();
+  LOGGED.lock().unwrap().push(message);
+}
+
+#[test]
+fn panics_in_run_are_reported_and_stop_the_core() {
+  unsafe {
+    start(environment);
+    retro_run();
+    assert!(MESSAGES.lock().unwrap().is_empty());
+
+    // The second run panics, and the core isn't run anymore afterwards.
+    retro_run();
+    retro_run();
+    retro_run();
+    assert_eq!(RUNS.load(Ordering::SeqCst), 2);
+    assert_eq!(LOG_QUERIES.load(Ordering::SeqCst), 1);
+    let logged = LOGGED.lock().unwrap().clone();
+    let location = format!("tests/panic.rs:{}", PANIC_LINE.load(Ordering::SeqCst));
+    assert_eq!(logged.len(), 1);
+    assert!(logged[0].contains("boom"), "{logged:?}");
+    assert!(logged[0].contains(&location), "{logged:?}");
+    assert_eq!(
+      *MESSAGES.lock().unwrap(),
+      [(
+        "Core encountered an internal error".to_owned(),
+        retro_log_level::RETRO_LOG_ERROR,
+        5000
+      )]
+    );
+
+    // The crashed core isn't called into anymore: it isn't reset, asked for
+    // its timings or memory, nor asked to unload the game.
+    retro_reset();
+    let mut av_info = std::mem::zeroed();
+    retro_get_system_av_info(&mut av_info);
+    assert!(retro_get_memory_data(RETRO_MEMORY_SAVE_RAM).is_null());
+    assert_eq!(retro_get_memory_size(RETRO_MEMORY_SAVE_RAM), 0);
+    retro_unload_game();
+    assert_eq!(RESETS.load(Ordering::SeqCst), 0);
+    assert_eq!(AV_INFOS.load(Ordering::SeqCst), 0);
+    assert_eq!(MEMORY_REGIONS.load(Ordering::SeqCst), 0);
+    assert_eq!(UNLOADS.load(Ordering::SeqCst), 0);
+
+    // Loading a game again resumes running the core.
+    assert!(retro_load_game(core::ptr::null()));
+    retro_run();
+    assert_eq!(RUNS.load(Ordering::SeqCst), 3);
+    retro_reset();
+    retro_unload_game();
+    assert_eq!(RESETS.load(Ordering::SeqCst), 1);
+    assert_eq!(UNLOADS.load(Ordering::SeqCst), 1);
+    retro_deinit();
+  }
+}
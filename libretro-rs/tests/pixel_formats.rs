@@ -0,0 +1,100 @@
+//! Negotiates a pixel format from a list of preferences, through the symbols
+//! exported by [`libretro_core`] for a core defined in this test.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use libretro_rs::retro::env::EnvironmentConfig;
+use std::sync::Mutex;
+
+/// Every format offered to the frontend, in order.
+static OFFERED: Mutex<Vec<i32>> = Mutex::new(Vec::new());
+
+/// The format negotiated by the core, if any.
+static NEGOTIATED: Mutex<Option<Option<AnyFormat>>> = Mutex::new(None);
+
+/// The result of offering only XRGB8888 through an [`EnvironmentConfig`].
+static CONFIGURED: Mutex<Option<Result<(), ConfigureError>>> = Mutex::new(None);
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("pixel formats test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    use retro_pixel_format::*;
+    let LoadGameExtraArgs { env, pixel_format, .. } = args;
+    let (format, result) = EnvironmentConfig::new()
+      .pixel_formats(&[RETRO_PIXEL_FORMAT_XRGB8888])
+      .configure_pixel_format(env, pixel_format);
+    *CONFIGURED.lock().unwrap() = Some(result);
+    // The frontend rejected it, so the default format is still active.
+    let AnyFormat::ORGB1555(pixel_format) = format else {
+      return Err(CoreError::new());
+    };
+    let formats = [RETRO_PIXEL_FORMAT_XRGB8888, RETRO_PIXEL_FORMAT_RGB565];
+    *NEGOTIATED.lock().unwrap() = Some(env.set_pixel_formats(pixel_format, &formats).ok());
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+/// Only accepts RGB565.
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_SET_PIXEL_FORMAT => {
+      let format = *(data as *const i32);
+      OFFERED.lock().unwrap().push(format);
+      format == retro_pixel_format::RETRO_PIXEL_FORMAT_RGB565 as i32
+    }
+    _ => false,
+  }
+}
+
+#[test]
+fn first_accepted_pixel_format_is_returned() {
+  use retro_pixel_format::*;
+  unsafe {
+    start(environment);
+    stop();
+  }
+  assert_eq!(
+    *OFFERED.lock().unwrap(),
+    [
+      RETRO_PIXEL_FORMAT_XRGB8888 as i32,
+      RETRO_PIXEL_FORMAT_XRGB8888 as i32,
+      RETRO_PIXEL_FORMAT_RGB565 as i32
+    ]
+  );
+  let configured = CONFIGURED.lock().unwrap().take().unwrap();
+  assert_eq!(
+    configured.unwrap_err().failed_commands(),
+    [RETRO_ENVIRONMENT_SET_PIXEL_FORMAT]
+  );
+  let negotiated = NEGOTIATED.lock().unwrap().take().unwrap();
+  assert!(matches!(negotiated, Some(AnyFormat::RGB565(_))));
+}
@@ -0,0 +1,87 @@
+//! Reads the touches of the pointer device from the `run` of a core defined in
+//! this test, through the symbols exported by [`libretro_core`].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// The touches reported by the frontend, as `(x, y)`; all of them are pressed.
+const TOUCHES: [(i16, i16); 2] = [(-0x7FFF, 0x7FFF), (100, -200)];
+
+/// The touches read by the core, in order.
+static STATES: Mutex<Vec<PointerState>> = Mutex::new(Vec::new());
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("pointer test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let inputs_polled = callbacks.poll_inputs();
+    let port = DevicePort::new(0);
+    // Read one touch past the last one, which the frontend reports as released.
+    for index in 0..=callbacks.pointer_count(port) {
+      let state = callbacks.pointer_state(port, index);
+      STATES.lock().unwrap().push(state);
+    }
+    inputs_polled
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn input_state(port: c_uint, device: c_uint, index: c_uint, id: c_uint) -> i16 {
+  assert_eq!((port, device), (0, RETRO_DEVICE_POINTER));
+  let Some(&(x, y)) = TOUCHES.get(index as usize) else {
+    return 0;
+  };
+  match id {
+    RETRO_DEVICE_ID_POINTER_X => x,
+    RETRO_DEVICE_ID_POINTER_Y => y,
+    RETRO_DEVICE_ID_POINTER_PRESSED => 1,
+    RETRO_DEVICE_ID_POINTER_COUNT => TOUCHES.len() as i16,
+    _ => 0,
+  }
+}
+
+#[test]
+fn touches_are_read_by_index() {
+  unsafe {
+    start(env::null_environment);
+    retro_set_input_state(input_state);
+    retro_run();
+    stop();
+  }
+  assert_eq!(
+    *STATES.lock().unwrap(),
+    [
+      PointerState { x: -0x7FFF, y: 0x7FFF, pressed: true },
+      PointerState { x: 100, y: -200, pressed: true },
+      PointerState::default(),
+    ]
+  );
+}
@@ -0,0 +1,125 @@
+//! Resets a core defined in this test through the symbols exported by
+//! [`libretro_core`], checking that only its emulation state is reset.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// Every environment command the frontend received, in order.
+static COMMANDS: Mutex<Vec<c_uint>> = Mutex::new(Vec::new());
+
+/// What the core saw on each run, as `(frame, frameskip)`.
+static RUNS: Mutex<Vec<(u32, i64)>> = Mutex::new(Vec::new());
+
+/// The number of frames uploaded with the negotiated pixel format.
+static FRAMES: Mutex<usize> = Mutex::new(0);
+
+fn frameskip() -> IntOption {
+  IntOption::new("reset_frameskip", "Frameskip", 0, 5)
+}
+
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  pixel_format: ActiveFormat<XRGB8888>,
+  frame_buffer: ArrayFrameBuffer<XRGB8888, 1, 1>,
+  frame: u32,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("reset test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn set_environment(env: &mut impl env::SetEnvironment) {
+    env.set_int_options(&[frameskip()]).unwrap();
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let pixel_format = env
+      .set_pixel_format_xrgb8888(pixel_format)
+      .map_err(|_| CoreError::new())?;
+    let frame_buffer = ArrayFrameBuffer::default();
+    Ok(Core {
+      rendering_mode,
+      pixel_format,
+      frame_buffer,
+      frame: 0,
+    })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let frameskip = match env.get_variable(&frameskip().key()) {
+      Ok(Some(value)) => frameskip().parse(value),
+      _ => frameskip().default_value(),
+    };
+    RUNS.lock().unwrap().push((self.frame, frameskip));
+    self.frame += 1;
+    callbacks.upload_video_frame(&self.rendering_mode, &self.pixel_format, &self.frame_buffer);
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {
+    self.frame = 0;
+  }
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  COMMANDS.lock().unwrap().push(cmd);
+  match cmd {
+    RETRO_ENVIRONMENT_SET_VARIABLES | RETRO_ENVIRONMENT_SET_PIXEL_FORMAT => true,
+    RETRO_ENVIRONMENT_GET_VARIABLE => {
+      (*(data as *mut retro_variable)).value = b"3\0".as_ptr().cast();
+      true
+    }
+    _ => false,
+  }
+}
+
+unsafe extern "C" fn video_refresh(
+  _data: *const c_void,
+  _width: c_uint,
+  _height: c_uint,
+  _pitch: usize,
+) {
+  *FRAMES.lock().unwrap() += 1;
+}
+
+#[test]
+fn reset_keeps_options_and_pixel_format() {
+  unsafe {
+    start(environment);
+    retro_set_video_refresh(video_refresh);
+    retro_run();
+    retro_run();
+    assert_eq!(*RUNS.lock().unwrap(), [(0, 3), (1, 3)]);
+
+    COMMANDS.lock().unwrap().clear();
+    retro_reset();
+    assert!(COMMANDS.lock().unwrap().is_empty());
+
+    retro_run();
+    assert_eq!(RUNS.lock().unwrap().last(), Some(&(0, 3)));
+    assert_eq!(*COMMANDS.lock().unwrap(), [RETRO_ENVIRONMENT_GET_VARIABLE]);
+    assert_eq!(*FRAMES.lock().unwrap(), 3);
+    stop();
+  }
+}
@@ -0,0 +1,39 @@
+//! Renders into an [`image::RgbaImage`] through a frame buffer, when the
+//! `image` feature is enabled.
+#![cfg(feature = "image")]
+
+use image::{Rgba, RgbaImage};
+use libretro_rs::prelude::*;
+
+const RED: Rgba<u8> = Rgba([0xFF, 0, 0, 0xFF]);
+const BLUE: Rgba<u8> = Rgba([0, 0, 0xFF, 0xFF]);
+
+#[test]
+fn frame_buffer_renders_into_image() {
+  let mut image = RgbaImage::new(4, 2);
+  image.put_pixel(3, 1, BLUE);
+
+  let mut buffer = SliceFrameBuffer::<Vec<XRGB8888>>::from_rgba_image(&image).unwrap();
+  assert_eq!((buffer.width(), buffer.height()), (4, 2));
+  assert_eq!(buffer[7].raw_value(), 0x000000FF);
+  buffer.fill_rect(0, 0, 2, 2, XRGB8888::new_with_raw_value(0x00FF0000));
+
+  let rendered = buffer.to_rgba_image();
+  assert_eq!(rendered.dimensions(), (4, 2));
+  assert_eq!(*rendered.get_pixel(1, 1), RED);
+  assert_eq!(*rendered.get_pixel(3, 1), BLUE);
+  // The transparent pixels of the original image become opaque black.
+  assert_eq!(*rendered.get_pixel(2, 0), Rgba([0, 0, 0, 0xFF]));
+
+  let path = std::env::temp_dir().join(format!("libretro-rs-image-{}.png", std::process::id()));
+  rendered.save(&path).unwrap();
+  let png = std::fs::read(&path).unwrap();
+  std::fs::remove_file(&path).ok();
+  assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+}
+
+#[test]
+fn empty_images_are_rejected() {
+  let image = RgbaImage::new(0, 0);
+  assert!(SliceFrameBuffer::<Vec<RGB565>>::from_rgba_image(&image).is_err());
+}
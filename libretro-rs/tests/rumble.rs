@@ -0,0 +1,113 @@
+//! Drives the frontend's rumble motors from the `run` of a core defined in this
+//! test, through the symbols exported by [`libretro_core`].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Whether the frontend provides a rumble interface.
+static SUPPORTS_RUMBLE: AtomicBool = AtomicBool::new(false);
+
+/// The rumble states set by the core, as `(port, effect, strength)`.
+static RUMBLE: Mutex<Vec<(c_uint, retro_rumble_effect, u16)>> = Mutex::new(Vec::new());
+
+/// The results of [RumbleInterface::set_rumble] seen by the core, or [None] if
+/// it didn't get a rumble interface.
+static RESULTS: Mutex<Vec<Option<bool>>> = Mutex::new(Vec::new());
+
+struct Core {
+  rumble: Option<RumbleInterface>,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("rumble test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let rumble = args.env.get_rumble_interface();
+    Ok(Core { rumble })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let port = DevicePort::new(1);
+    let mut results = RESULTS.lock().unwrap();
+    results.push(
+      self
+        .rumble
+        .map(|rumble| rumble.set_rumble(port, RumbleEffect::Strong, 0x8000)),
+    );
+    results.push(
+      self
+        .rumble
+        .map(|rumble| rumble.set_rumble(port, RumbleEffect::Weak, 0)),
+    );
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE if SUPPORTS_RUMBLE.load(Ordering::SeqCst) => {
+      let iface = &mut *(data as *mut retro_rumble_interface);
+      iface.set_rumble_state = Some(set_rumble_state);
+      true
+    }
+    _ => false,
+  }
+}
+
+/// Accepts the strong motor only, like a joypad with a single motor.
+unsafe extern "C" fn set_rumble_state(
+  port: c_uint,
+  effect: retro_rumble_effect,
+  strength: u16,
+) -> bool {
+  RUMBLE.lock().unwrap().push((port, effect, strength));
+  effect == retro_rumble_effect::RETRO_RUMBLE_STRONG
+}
+
+#[test]
+fn rumble_is_set_only_when_the_frontend_supports_it() {
+  use retro_rumble_effect::*;
+  unsafe {
+    init(environment);
+    for supported in [false, true] {
+      SUPPORTS_RUMBLE.store(supported, Ordering::SeqCst);
+      assert!(retro_load_game(core::ptr::null()));
+      retro_run();
+      retro_unload_game();
+    }
+    retro_deinit();
+  }
+
+  assert_eq!(
+    *RESULTS.lock().unwrap(),
+    [None, None, Some(true), Some(false)]
+  );
+  assert_eq!(
+    *RUMBLE.lock().unwrap(),
+    [(1, RETRO_RUMBLE_STRONG, 0x8000), (1, RETRO_RUMBLE_WEAK, 0)]
+  );
+}
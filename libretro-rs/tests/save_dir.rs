@@ -0,0 +1,154 @@
+//! Tests for [`Environment::create_core_save_dir`] against a stub
+//! `retro_environment_t`.
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::path::{Path, PathBuf};
+
+struct Core;
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("Save Dir Test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    Ok(Core)
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+/// The stubbed environment, which is per thread since each test runs on its
+/// own thread.
+struct Frontend {
+  save_directory: Option<CString>,
+  vfs: bool,
+  created: Vec<PathBuf>,
+}
+
+thread_local! {
+  static FRONTEND: RefCell<Frontend> =
+    RefCell::new(Frontend { save_directory: None, vfs: false, created: Vec::new() });
+}
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  FRONTEND.with(|frontend| {
+    let frontend = frontend.borrow();
+    match cmd {
+      RETRO_ENVIRONMENT_GET_SAVE_DIRECTORY => match &frontend.save_directory {
+        Some(dir) => {
+          *(data as *mut *const c_char) = dir.as_ptr();
+          true
+        }
+        None => false,
+      },
+      RETRO_ENVIRONMENT_GET_VFS_INTERFACE if frontend.vfs => {
+        let info = &mut *(data as *mut retro_vfs_interface_info);
+        info.required_interface_version = 3;
+        info.iface = &VFS as *const _ as *mut _;
+        true
+      }
+      _ => false,
+    }
+  })
+}
+
+static VFS: retro_vfs_interface = retro_vfs_interface {
+  get_path: None,
+  open: None,
+  close: None,
+  size: None,
+  tell: None,
+  seek: None,
+  read: None,
+  write: None,
+  flush: None,
+  remove: None,
+  rename: None,
+  truncate: None,
+  stat: None,
+  mkdir: Some(mkdir),
+  opendir: None,
+  readdir: None,
+  dirent_get_name: None,
+  dirent_is_dir: None,
+  closedir: None,
+};
+
+unsafe extern "C" fn mkdir(dir: *const c_char) -> c_int {
+  let dir = PathBuf::from(CStr::from_ptr(dir).to_str().unwrap());
+  FRONTEND.with(|frontend| {
+    let created = &mut frontend.borrow_mut().created;
+    match created.contains(&dir) {
+      true => -2,
+      false => {
+        created.push(dir);
+        0
+      }
+    }
+  })
+}
+
+fn set_frontend(save_directory: Option<&Path>, vfs: bool) -> non_null_retro_environment_t {
+  let save_directory = save_directory.map(|dir| CString::new(dir.to_str().unwrap()).unwrap());
+  FRONTEND.with(|frontend| {
+    *frontend.borrow_mut() = Frontend { save_directory, vfs, created: Vec::new() }
+  });
+  environment
+}
+
+#[test]
+fn save_dir_is_created_with_std_without_vfs() {
+  let save = std::env::temp_dir().join(format!("libretro-rs-save-dir-{}", std::process::id()));
+  std::fs::create_dir_all(&save).unwrap();
+  let env = set_frontend(Some(&save), false);
+
+  let dir = env.create_core_save_dir::<Core>("logs/today").unwrap();
+  assert_eq!(dir, save.join("Save Dir Test").join("logs/today"));
+  assert!(dir.is_dir());
+  // Creating it again is fine.
+  assert_eq!(env.create_core_save_dir::<Core>("logs/today").unwrap(), dir);
+  std::fs::remove_dir_all(&save).unwrap();
+}
+
+#[test]
+fn save_dir_is_created_through_vfs() {
+  let env = set_frontend(Some(Path::new("/retro/saves")), true);
+  let dir = env.create_core_save_dir::<Core>("config").unwrap();
+  assert_eq!(dir, Path::new("/retro/saves/Save Dir Test/config"));
+  let expected = [
+    PathBuf::from("/retro/saves/Save Dir Test"),
+    PathBuf::from("/retro/saves/Save Dir Test/config"),
+  ];
+  FRONTEND.with(|frontend| assert_eq!(frontend.borrow().created, expected));
+
+  // Existing directories aren't an error.
+  env.create_core_save_dir::<Core>("config").unwrap();
+  FRONTEND.with(|frontend| assert_eq!(frontend.borrow().created, expected));
+}
+
+#[test]
+fn save_dir_requires_a_save_directory() {
+  let env = set_frontend(None, true);
+  let err = env.create_core_save_dir::<Core>("logs").unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
@@ -0,0 +1,146 @@
+//! Saves and loads a large state through the symbols exported by
+//! [`libretro_core`], for a core defined in this test that writes its state
+//! with [StateWriter].
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::num::NonZeroUsize;
+
+const RAM_SIZE: usize = 16 * 1024 * 1024;
+
+/// Counts the bytes allocated by the current thread, so allocations made by
+/// the test harness on other threads don't interfere.
+struct CountingAllocator;
+
+thread_local! {
+  static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    ALLOCATED.with(|allocated| allocated.set(allocated.get() + layout.size()));
+    System.alloc(layout)
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout)
+  }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocated() -> usize {
+  ALLOCATED.with(Cell::get)
+}
+
+struct Core {
+  frame: u32,
+  ram: Vec<u8>,
+}
+
+impl Core {
+  fn save(&self, state: &mut StateWriter) -> Result<(), CoreError> {
+    state.write_u32(self.frame)?;
+    state.write_bytes(&self.ram)
+  }
+
+  fn load(&mut self, state: &mut StateReader) -> Result<(), CoreError> {
+    self.frame = state.read_u32()?;
+    state.read_bytes(&mut self.ram)
+  }
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("save state test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    _args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let ram = (0..RAM_SIZE).map(|i| i as u8).collect();
+    Ok(Core { frame: 0, ram })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    self.frame += 1;
+    self.ram[0] = self.ram[0].wrapping_add(1);
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+impl<'a> retro::SaveStateCore<'a> for Core {
+  fn serialize_size(&self, _env: &mut impl env::SerializeSize) -> NonZeroUsize {
+    let size = StateWriter::measure(|state| self.save(state)).unwrap();
+    NonZeroUsize::new(size).unwrap()
+  }
+
+  fn serialize(&self, _env: &mut impl env::Serialize, data: &mut [u8]) -> Result<(), CoreError> {
+    let mut state = StateWriter::new(data);
+    self.save(&mut state)?;
+    state.finish();
+    Ok(())
+  }
+
+  fn unserialize(
+    &mut self,
+    _env: &mut impl env::Unserialize,
+    data: &[u8],
+  ) -> Result<(), CoreError> {
+    self.load(&mut StateReader::new(data))
+  }
+}
+
+test_core!();
+
+#[test]
+fn large_states_are_written_without_intermediate_allocations() {
+  unsafe {
+    start(env::null_environment);
+    retro_run();
+
+    let before = allocated();
+    let size = retro_serialize_size();
+    assert_eq!(size, 4 + RAM_SIZE);
+    assert_eq!(allocated(), before);
+
+    // The core only sees the first `size` bytes of a larger buffer.
+    let mut state = vec![0xAA; size + 16];
+    let before = allocated();
+    assert!(retro_serialize(state.as_mut_ptr() as *mut (), state.len()));
+    assert_eq!(allocated(), before);
+    assert_eq!(state[..4], 1u32.to_le_bytes());
+    assert_eq!(state[4..8], [1, 1, 2, 3]);
+    assert!(state[size..].iter().all(|&byte| byte == 0xAA));
+
+    // A buffer that's too small is rejected.
+    assert!(!retro_serialize(state.as_mut_ptr() as *mut (), size - 1));
+
+    retro_run();
+    assert!(retro_unserialize(state.as_ptr() as *const (), size));
+    let mut reloaded = vec![0; size];
+    assert!(retro_serialize(reloaded.as_mut_ptr() as *mut (), size));
+    assert_eq!(reloaded, state[..size]);
+
+    stop();
+  }
+}
@@ -0,0 +1,101 @@
+//! Takes a screenshot through the `get_proc_address` interface of the
+//! `screenshot` feature, registered by a core defined in this test.
+#![cfg(feature = "screenshot")]
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use libretro_rs::retro::screenshot::{ScreenshotFn, PROC_ADDRESS_INTERFACE, SCREENSHOT_SYMBOL};
+use std::ffi::CString;
+use std::sync::Mutex;
+
+static PROC_ADDRESS: Mutex<retro_get_proc_address_t> = Mutex::new(None);
+
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  format: ActiveFormat<XRGB8888>,
+  frame_buffer: ArrayFrameBuffer<XRGB8888, 4, 2>,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("screenshot test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn set_environment(env: &mut impl env::SetEnvironment) {
+    env.set_proc_address_callback(&PROC_ADDRESS_INTERFACE).ok();
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let format = env.set_pixel_format_xrgb8888(pixel_format)?;
+    let frame_buffer = ArrayFrameBuffer::new([
+      XRGB8888::new_with_raw_value(0x00FF0000),
+      XRGB8888::new_with_raw_value(0x0000FF00),
+      XRGB8888::new_with_raw_value(0x000000FF),
+      XRGB8888::new_with_raw_value(0x00FFFFFF),
+    ]);
+    Ok(Core { rendering_mode, format, frame_buffer })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(2, 2))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.upload_video_frame(&self.rendering_mode, &self.format, &self.frame_buffer);
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_SET_PROC_ADDRESS_CALLBACK => {
+      let interface = &*(data as *const retro_get_proc_address_interface);
+      *PROC_ADDRESS.lock().unwrap() = interface.get_proc_address;
+      true
+    }
+    RETRO_ENVIRONMENT_SET_PIXEL_FORMAT => true,
+    _ => false,
+  }
+}
+
+#[test]
+fn screenshot_writes_last_frame_as_png() {
+  let path =
+    std::env::temp_dir().join(format!("libretro-rs-screenshot-{}.png", std::process::id()));
+  let c_path = CString::new(path.to_str().unwrap()).unwrap();
+  unsafe {
+    start(environment);
+
+    let get_proc_address = PROC_ADDRESS
+      .lock()
+      .unwrap()
+      .expect("interface wasn't registered");
+    let symbol = CString::new(SCREENSHOT_SYMBOL).unwrap();
+    assert!(get_proc_address(c_utf8!("unknown").as_ptr()).is_none());
+    let screenshot = get_proc_address(symbol.as_ptr()).expect("screenshot function not found");
+    let screenshot: ScreenshotFn = std::mem::transmute(screenshot);
+
+    retro_run();
+    assert!(screenshot(c_path.as_ptr()));
+
+    stop();
+  }
+  let png = std::fs::read(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(png[..8], *b"\x89PNG\r\n\x1a\n");
+}
@@ -0,0 +1,54 @@
+//! End-to-end test of the symbols exported by [`libretro_rs::libretro_core`].
+//!
+//! The `software-rendering` example is built as a `cdylib`, loaded at runtime,
+//! and driven through a minimal stub frontend, the same way a real frontend
+//! would. This catches ABI regressions that the type checker can't.
+
+mod common;
+
+use common::*;
+use libloading::{Library, Symbol};
+use libretro_rs::ffi::*;
+use std::sync::atomic::Ordering;
+
+#[test]
+fn software_rendering_core_emits_video_frame() {
+  let path = build_example("software-rendering");
+  unsafe {
+    let lib = Library::new(&path).expect("failed to load the example core");
+
+    let api_version: Symbol<unsafe extern "C" fn() -> c_uint> =
+      lib.get(b"retro_api_version").unwrap();
+    assert_eq!(api_version(), RETRO_API_VERSION);
+
+    let get_system_info: Symbol<unsafe extern "C" fn(*mut retro_system_info)> =
+      lib.get(b"retro_get_system_info").unwrap();
+    let mut info = retro_system_info::default();
+    get_system_info(&mut info);
+    assert!(!info.library_name.is_null());
+    assert!(!info.library_version.is_null());
+
+    start_example(&lib);
+
+    let get_system_av_info: Symbol<unsafe extern "C" fn(*mut retro_system_av_info)> =
+      lib.get(b"retro_get_system_av_info").unwrap();
+    let mut av_info = retro_system_av_info::default();
+    get_system_av_info(&mut av_info);
+    assert_eq!(av_info.geometry.base_width, 2);
+    assert_eq!(av_info.geometry.base_height, 2);
+
+    let run: Symbol<unsafe extern "C" fn()> = lib.get(b"retro_run").unwrap();
+    run();
+    assert_eq!(FRAMES.load(Ordering::SeqCst), 1);
+    assert_eq!(INPUT_POLLS.load(Ordering::SeqCst), 1);
+
+    let frame = last_frame();
+    assert_eq!((frame.width, frame.height, frame.pitch), (2, 2, 8));
+    assert_eq!(
+      frame.xrgb8888(),
+      [0x000000FF, 0x0000FF00, 0x00FF0000, 0x00FFFFFF]
+    );
+
+    stop_example(&lib);
+  }
+}
@@ -0,0 +1,105 @@
+//! Uploads solid frames from a core defined in this test, through the symbols
+//! exported by [`libretro_core`], and checks the frames the frontend receives.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// A solid frame for the core to upload on its next run, as
+/// `(width, height, color)`.
+static UPLOADS: Mutex<VecDeque<(u16, u16, u32)>> = Mutex::new(VecDeque::new());
+
+/// Every frame received by the frontend, as `(pixels, width, height, pitch)`.
+static FRAMES: Mutex<Vec<(Vec<u32>, c_uint, c_uint, usize)>> = Mutex::new(Vec::new());
+
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  pixel_format: ActiveFormat<XRGB8888>,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("solid frame test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let pixel_format = env
+      .set_pixel_format_xrgb8888(pixel_format)
+      .map_err(|_| CoreError::new())?;
+    Ok(Core { rendering_mode, pixel_format })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(4, 3))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let (width, height, color) = UPLOADS.lock().unwrap().pop_front().unwrap();
+    callbacks.upload_solid_frame(
+      &self.rendering_mode,
+      &self.pixel_format,
+      width,
+      height,
+      XRGB8888::new_with_raw_value(color),
+    );
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn video_refresh(
+  data: *const c_void,
+  width: c_uint,
+  height: c_uint,
+  pitch: usize,
+) {
+  let data = core::slice::from_raw_parts(data as *const u8, pitch * height as usize);
+  let pixels = data
+    .chunks(pitch)
+    .flat_map(|row| row[..width as usize * 4].chunks_exact(4))
+    .map(|pixel| u32::from_ne_bytes(pixel.try_into().unwrap()))
+    .collect();
+  FRAMES.lock().unwrap().push((pixels, width, height, pitch));
+}
+
+/// Makes the core upload a solid frame, and returns the frame the frontend got.
+fn upload(width: u16, height: u16, color: u32) -> (Vec<u32>, c_uint, c_uint, usize) {
+  UPLOADS.lock().unwrap().push_back((width, height, color));
+  unsafe { retro_run() };
+  FRAMES.lock().unwrap().pop().unwrap()
+}
+
+#[test]
+fn solid_frames_are_uniformly_colored() {
+  unsafe {
+    start(common::environment);
+    retro_set_video_refresh(video_refresh);
+  }
+
+  assert_eq!(upload(4, 3, 0x00FF8040), (vec![0x00FF8040; 12], 4, 3, 16));
+  // The same buffer is filled with another color.
+  assert_eq!(upload(4, 3, 0x00000000), (vec![0; 12], 4, 3, 16));
+  // The buffer is resized to the new size.
+  assert_eq!(upload(2, 5, 0x0000FF00), (vec![0x0000FF00; 10], 2, 5, 8));
+
+  unsafe {
+    stop();
+  }
+}
@@ -0,0 +1,41 @@
+//! Tests for [`StackCUtf8`].
+
+use libretro_rs::prelude::*;
+use std::fmt::Write;
+
+#[test]
+fn short_messages_stay_inline() {
+  let message = StackCUtf8::<16>::from_fmt(format_args!("{}x{}", 320, 240));
+  assert!(!message.spilled());
+  assert_eq!(message.as_str(), "320x240");
+  assert_eq!(message.as_bytes_with_nul(), b"320x240\0");
+}
+
+#[test]
+fn messages_that_fill_the_buffer_spill_to_the_heap() {
+  // 7 bytes and a nul fit exactly in 8 bytes; one more doesn't.
+  let mut message = StackCUtf8::<8>::new();
+  write!(message, "1234567").unwrap();
+  assert!(!message.spilled());
+  write!(message, "8").unwrap();
+  assert!(message.spilled());
+  write!(message, "9").unwrap();
+  assert_eq!(message.as_bytes_with_nul(), b"123456789\0");
+}
+
+#[test]
+fn empty_buffers_always_spill() {
+  assert_eq!(StackCUtf8::<0>::new().as_str(), "");
+  let message = StackCUtf8::<0>::from_fmt(format_args!("é"));
+  assert!(message.spilled());
+  assert_eq!(message.as_str(), "é");
+}
+
+#[test]
+fn messages_are_truncated_at_interior_nuls() {
+  let message = StackCUtf8::<16>::from_fmt(format_args!("{}{}", "ab\0cd", "ef"));
+  assert_eq!(message.as_bytes_with_nul(), b"ab\0");
+  let message = StackCUtf8::<2>::from_fmt(format_args!("{}{}", "abc", "d\0e"));
+  assert!(message.spilled());
+  assert_eq!(message.as_bytes_with_nul(), b"abcd\0");
+}
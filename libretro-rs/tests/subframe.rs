@@ -0,0 +1,194 @@
+//! Uploads subframes from a core defined in this test, through the symbols
+//! exported by [`libretro_core`], and checks the frames the frontend receives.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// A frame buffer of `XRGB8888` pixels whose rows are padded by a pixel, so
+/// its pitch differs from the composited frame's.
+struct Padded {
+  data: Vec<u8>,
+  width: u16,
+  height: u16,
+}
+
+impl Padded {
+  fn new(pixels: &[u32], width: u16) -> Self {
+    let data = pixels
+      .chunks(width as usize)
+      .flat_map(|row| row.iter().chain([&0xDEAD]))
+      .flat_map(|pixel| pixel.to_ne_bytes())
+      .collect();
+    let height = (pixels.len() / width as usize) as u16;
+    Self { data, width, height }
+  }
+}
+
+unsafe impl FrameBuffer for Padded {
+  type Pixel = XRGB8888;
+
+  fn data(&self) -> &[u8] {
+    &self.data
+  }
+
+  fn width(&self) -> u16 {
+    self.width
+  }
+
+  fn height(&self) -> u16 {
+    self.height
+  }
+
+  fn pitch(&self) -> usize {
+    (self.width as usize + 1) * 4
+  }
+}
+
+/// An upload for the core to make on its next run: a whole frame if there's
+/// no origin, or a subframe otherwise.
+type Upload = (Vec<u32>, u16, Option<(u16, u16)>);
+
+static UPLOADS: Mutex<VecDeque<Upload>> = Mutex::new(VecDeque::new());
+
+/// Every frame received by the frontend, as `(pixels, width, height, pitch)`.
+static FRAMES: Mutex<Vec<(Vec<u32>, c_uint, c_uint, usize)>> = Mutex::new(Vec::new());
+
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  pixel_format: ActiveFormat<XRGB8888>,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("subframe test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let pixel_format = env
+      .set_pixel_format_xrgb8888(pixel_format)
+      .map_err(|_| CoreError::new())?;
+    Ok(Core { rendering_mode, pixel_format })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(3, 3))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    let (pixels, width, origin) = UPLOADS.lock().unwrap().pop_front().unwrap();
+    let framebuffer = Padded::new(&pixels, width);
+    match origin {
+      Some(origin) => callbacks.upload_video_subframe(
+        &self.rendering_mode,
+        &self.pixel_format,
+        &framebuffer,
+        origin,
+      ),
+      None => callbacks.upload_video_frame(&self.rendering_mode, &self.pixel_format, &framebuffer),
+    }
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn video_refresh(
+  data: *const c_void,
+  width: c_uint,
+  height: c_uint,
+  pitch: usize,
+) {
+  let data = core::slice::from_raw_parts(data as *const u8, pitch * height as usize);
+  let pixels = data
+    .chunks(pitch)
+    .flat_map(|row| row[..width as usize * 4].chunks_exact(4))
+    .map(|pixel| u32::from_ne_bytes(pixel.try_into().unwrap()))
+    .collect();
+  FRAMES.lock().unwrap().push((pixels, width, height, pitch));
+}
+
+/// Makes the core upload `pixels`, and returns the frame the frontend got.
+fn upload(pixels: &[u32], width: u16, origin: Option<(u16, u16)>) -> Vec<u32> {
+  UPLOADS
+    .lock()
+    .unwrap()
+    .push_back((pixels.to_vec(), width, origin));
+  unsafe { retro_run() };
+  let (pixels, width, height, pitch) = FRAMES.lock().unwrap().pop().unwrap();
+  // Whole frames are uploaded as is, but the composited frame is always
+  // tightly packed.
+  let expected_pitch = if origin.is_some() { 12 } else { 16 };
+  assert_eq!((width, height, pitch), (3, 3, expected_pitch));
+  pixels
+}
+
+#[test]
+fn subframes_are_composited_into_the_last_frame() {
+  unsafe {
+    start(common::environment);
+    retro_set_video_refresh(video_refresh);
+  }
+
+  let all: Vec<u32> = (1..=9).collect();
+  assert_eq!(upload(&all, 3, None), all);
+
+  // No copy is kept until a subframe is uploaded, so the first one is
+  // composited into a black frame of the last frame's size.
+  assert_eq!(upload(&[10], 1, Some((1, 1))), [0, 0, 0, 0, 10, 0, 0, 0, 0]);
+  assert_eq!(upload(&all, 3, Some((0, 0))), all);
+
+  // Top and left edges.
+  assert_eq!(
+    upload(&[11, 12], 2, Some((0, 0))),
+    [11, 12, 3, 4, 5, 6, 7, 8, 9]
+  );
+  // Right edge, clipped at the bottom.
+  assert_eq!(
+    upload(&[13, 14, 15, 16], 1, Some((2, 0))),
+    [11, 12, 13, 4, 5, 14, 7, 8, 15]
+  );
+  // Bottom and right edges, clipped on both.
+  assert_eq!(
+    upload(&[17, 18, 19, 20], 2, Some((2, 2))),
+    [11, 12, 13, 4, 5, 14, 7, 8, 17]
+  );
+  // Bottom and left edges.
+  assert_eq!(
+    upload(&[21, 22], 2, Some((0, 2))),
+    [11, 12, 13, 4, 5, 14, 21, 22, 17]
+  );
+  // Entirely outside of the frame.
+  assert_eq!(
+    upload(&[23], 1, Some((3, 0))),
+    [11, 12, 13, 4, 5, 14, 21, 22, 17]
+  );
+
+  // Once subframes are used, whole frames replace the composited frame, and
+  // its untouched pixels are kept.
+  assert_eq!(upload(&[0x20; 9], 3, None), [0x20; 9]);
+  assert_eq!(
+    upload(&[24], 1, Some((1, 1))),
+    [0x20, 0x20, 0x20, 0x20, 24, 0x20, 0x20, 0x20, 0x20]
+  );
+
+  unsafe {
+    stop();
+  }
+}
@@ -0,0 +1,88 @@
+//! Checks that the symbols exported by [`libretro_core`] don't reach the
+//! frontend's audio and video callbacks once the game has been unloaded, with
+//! a core defined in this test.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static VIDEO_FRAMES: AtomicUsize = AtomicUsize::new(0);
+static AUDIO_BATCHES: AtomicUsize = AtomicUsize::new(0);
+
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  pixel_format: ActiveFormat<XRGB8888>,
+  frame_buffer: ArrayFrameBuffer<XRGB8888, 1, 1>,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("teardown test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let pixel_format = env
+      .set_pixel_format_xrgb8888(pixel_format)
+      .map_err(|_| CoreError::new())?;
+    let frame_buffer = ArrayFrameBuffer::default();
+    Ok(Core { rendering_mode, pixel_format, frame_buffer })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.upload_video_frame(&self.rendering_mode, &self.pixel_format, &self.frame_buffer);
+    callbacks.upload_audio_frame(&[0, 0]);
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn video_refresh(
+  _data: *const c_void,
+  _width: c_uint,
+  _height: c_uint,
+  _pitch: usize,
+) {
+  VIDEO_FRAMES.fetch_add(1, Ordering::SeqCst);
+}
+
+unsafe extern "C" fn audio_sample_batch(_data: *const i16, frames: usize) -> usize {
+  AUDIO_BATCHES.fetch_add(1, Ordering::SeqCst);
+  frames
+}
+
+#[test]
+fn callbacks_are_not_called_after_unload() {
+  unsafe {
+    start(common::environment);
+    retro_set_video_refresh(video_refresh);
+    retro_set_audio_sample_batch(audio_sample_batch);
+    retro_run();
+    assert_eq!(VIDEO_FRAMES.load(Ordering::SeqCst), 1);
+    assert_eq!(AUDIO_BATCHES.load(Ordering::SeqCst), 1);
+
+    // A misbehaving frontend keeps running the core after unloading the game.
+    retro_unload_game();
+    retro_run();
+    assert_eq!(VIDEO_FRAMES.load(Ordering::SeqCst), 1);
+    assert_eq!(AUDIO_BATCHES.load(Ordering::SeqCst), 1);
+    retro_deinit();
+  }
+}
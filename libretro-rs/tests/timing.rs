@@ -0,0 +1,111 @@
+//! Long-running checks for [`FrameClock`], and tests for [`RunTracker`] and
+//! [`AudioJitter`].
+
+use libretro_rs::prelude::*;
+use libretro_rs::retro::timing::{AudioJitter, AudioJitterStats, FrameClock, RunTracker};
+use std::time::{Duration, Instant};
+
+fn assert_average(fps: f64, sample_rate: f64) {
+  let mut clock = FrameClock::new(fps, sample_rate);
+  let frames = 100_000;
+  let total: usize = (0..frames).map(|_| clock.next_frame()).sum();
+  let expected = frames as f64 * sample_rate / fps;
+  assert!(
+    (total as f64 - expected).abs() <= 0.5,
+    "{fps}fps at {sample_rate}Hz: emitted {total} samples, expected {expected}"
+  );
+  assert_eq!(clock.samples(), total as u64);
+}
+
+#[test]
+fn frame_clock_averages_to_sample_rate_over_fps() {
+  assert_average(60.0, 44_100.0);
+  assert_average(60.0, 48_000.0);
+  assert_average(59.94, 44_100.0);
+  assert_average(60.0988, 32_040.5);
+  assert_average(50.0070, 44_100.0);
+}
+
+#[test]
+fn frame_clock_jitters_by_at_most_one_sample() {
+  let mut clock = FrameClock::from_timing(&SystemTiming::new(59.94, 44_100.0));
+  for _ in 0..10_000 {
+    assert!(matches!(clock.next_frame(), 735 | 736));
+  }
+}
+
+#[test]
+fn frame_clock_reset_starts_over() {
+  let mut clock = FrameClock::new(3.0, 10.0);
+  let first: Vec<_> = (0..4).map(|_| clock.next_frame()).collect();
+  clock.reset();
+  assert_eq!((clock.frames(), clock.samples()), (0, 0));
+  let second: Vec<_> = (0..4).map(|_| clock.next_frame()).collect();
+  assert_eq!(first, second);
+}
+
+#[test]
+fn run_tracker_detects_gaps_between_runs() {
+  let start = Instant::now();
+  let second = Duration::from_secs(1);
+  let mut tracker = RunTracker::new();
+  assert_eq!(tracker.since_last_run_at(start), None);
+  assert!(!tracker.is_idle_at(start + 10 * second, second));
+
+  tracker.record_run_at(start);
+  assert!(!tracker.is_idle_at(start + second / 60, second));
+  assert!(tracker.is_idle_at(start + 5 * second, second));
+  assert_eq!(
+    tracker.since_last_run_at(start + 5 * second),
+    Some(5 * second)
+  );
+
+  tracker.record_run_at(start + 5 * second);
+  assert!(!tracker.is_idle_at(start + 5 * second, second));
+}
+
+#[test]
+fn audio_jitter_summarizes_each_period() {
+  let mut jitter = AudioJitter::new(Duration::from_millis(100));
+  // Uploads roughly every 16.7 ms, with one late frame.
+  let intervals = [16_700, 16_600, 16_700, 25_000, 8_400, 16_700, 16_700];
+  let mut time = 1_000_000;
+  assert_eq!(jitter.record_upload_at(time), None);
+  let mut reports = Vec::new();
+  for interval in intervals {
+    time += interval;
+    reports.extend(jitter.record_upload_at(time));
+  }
+  let stats = AudioJitterStats {
+    count: 6,
+    min_usec: 8_400,
+    max_usec: 25_000,
+    total_usec: 100_100,
+  };
+  assert_eq!(reports, [stats]);
+  assert_eq!(stats.avg_usec(), 16_683);
+  assert_eq!(stats.jitter_usec(), 16_600);
+
+  // The next period starts with the interval after the report.
+  let current = jitter.current().unwrap();
+  assert_eq!(
+    (current.count, current.min_usec, current.max_usec),
+    (1, 16_700, 16_700)
+  );
+}
+
+#[test]
+fn audio_jitter_ignores_gaps_after_reset() {
+  let mut jitter = AudioJitter::new(Duration::from_secs(1));
+  jitter.record_upload_at(0);
+  jitter.record_upload_at(16_000);
+  jitter.reset();
+  assert_eq!(jitter.current(), None);
+  jitter.record_upload_at(5_000_000);
+  jitter.record_upload_at(5_017_000);
+  let current = jitter.current().unwrap();
+  assert_eq!((current.count, current.max_usec), (1, 17_000));
+  // Time going backwards isn't measured.
+  assert_eq!(jitter.record_upload_at(4_000_000), None);
+  assert_eq!(jitter.current().unwrap().count, 1);
+}
@@ -0,0 +1,112 @@
+//! Runs several internal frames per `run` in a core defined in this test, and
+//! checks that [`TurboFrameAccumulator`] uploads them through the symbols
+//! exported by [`libretro_core`] as a single frame.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use libretro_rs::retro::timing::TurboFrameAccumulator;
+use std::sync::Mutex;
+
+const SUB_FRAMES: usize = 3;
+
+type Frame = ArrayFrameBuffer<XRGB8888, 1, 1>;
+
+/// Every audio batch received by the frontend, in order.
+static BATCHES: Mutex<Vec<Vec<i16>>> = Mutex::new(Vec::new());
+
+/// The pixel of every video frame received by the frontend, in order.
+static FRAMES: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+struct Core {
+  rendering_mode: SoftwareRenderEnabled,
+  pixel_format: ActiveFormat<XRGB8888>,
+  turbo: TurboFrameAccumulator<Frame>,
+  frame: i16,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("turbo test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let LoadGameExtraArgs { env, rendering_mode, pixel_format, .. } = args;
+    let pixel_format = env
+      .set_pixel_format_xrgb8888(pixel_format)
+      .map_err(|_| CoreError::new())?;
+    let turbo = TurboFrameAccumulator::new(SUB_FRAMES);
+    Ok(Core { rendering_mode, pixel_format, turbo, frame: 0 })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    loop {
+      // Each internal frame generates one stereo frame of audio and a frame
+      // filled with its number.
+      self.frame += 1;
+      self.turbo.push_audio(&[self.frame, -self.frame]);
+      let pixel = XRGB8888::new_with_raw_value(self.frame as u32);
+      self.turbo.push_video(Frame::new([pixel]));
+      if self.turbo.finish_sub_frame() {
+        break;
+      }
+    }
+    self
+      .turbo
+      .flush(callbacks, &self.rendering_mode, &self.pixel_format);
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+test_core!();
+
+unsafe extern "C" fn video_refresh(
+  data: *const c_void,
+  _width: c_uint,
+  _height: c_uint,
+  _pitch: usize,
+) {
+  FRAMES.lock().unwrap().push(*(data as *const u32));
+}
+
+unsafe extern "C" fn audio_sample_batch(data: *const i16, frames: usize) -> usize {
+  let samples = core::slice::from_raw_parts(data, frames * 2);
+  BATCHES.lock().unwrap().push(samples.to_vec());
+  frames
+}
+
+#[test]
+fn sub_frames_are_uploaded_once_per_run() {
+  unsafe {
+    start(common::environment);
+    retro_set_video_refresh(video_refresh);
+    retro_set_audio_sample_batch(audio_sample_batch);
+    retro_run();
+    retro_run();
+    stop();
+  }
+  // Only the last of each run's sub-frames is presented...
+  assert_eq!(*FRAMES.lock().unwrap(), [3, 6]);
+  // ...but none of their audio is lost.
+  assert_eq!(
+    *BATCHES.lock().unwrap(),
+    [vec![1, -1, 2, -2, 3, -3], vec![4, -4, 5, -5, 6, -6]]
+  );
+}
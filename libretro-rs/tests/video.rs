@@ -0,0 +1,364 @@
+//! Tests for the frame buffer types.
+
+use libretro_rs::prelude::*;
+
+fn pixels() -> [XRGB8888; 6] {
+  [1, 2, 3, 4, 5, 6].map(XRGB8888::new_with_raw_value)
+}
+
+#[test]
+fn upload_rows_honors_origin() {
+  let pixels = pixels();
+  let buffer = SliceFrameBuffer::with_width(&pixels[..], 3).unwrap();
+  let top_left: Vec<_> = buffer.upload_rows(false).collect();
+  let bottom_left: Vec<_> = buffer.upload_rows(true).collect();
+  assert_eq!(top_left, [&pixels[..3], &pixels[3..]]);
+  assert_eq!(bottom_left, [&pixels[3..], &pixels[..3]]);
+}
+
+#[test]
+fn rows_have_the_width_of_the_buffer() {
+  let mut buffer = SliceFrameBuffer::with_width(pixels().to_vec(), 2).unwrap();
+  assert_eq!(buffer.rows().len(), 3);
+  assert!(buffer.rows().all(|row| row.len() == 2));
+  for row in buffer.rows_mut() {
+    row[0] = XRGB8888::new_with_raw_value(0);
+  }
+  let raw: Vec<_> = buffer
+    .pixels()
+    .iter()
+    .map(|pixel| pixel.raw_value())
+    .collect();
+  assert_eq!(raw, [0, 2, 0, 4, 0, 6]);
+}
+
+const RED: u32 = 0x00FF0000;
+
+/// Draws a line on a 4x4 buffer, returning the coordinates of the red pixels.
+fn line(from: (i32, i32), to: (i32, i32)) -> Vec<(usize, usize)> {
+  let mut buffer = ArrayFrameBuffer::<XRGB8888, 16, 4>::default();
+  buffer.draw_line(from, to, XRGB8888::new_with_raw_value(RED));
+  (0..16)
+    .filter(|&i| buffer[i].raw_value() == RED)
+    .map(|i| (i % 4, i / 4))
+    .collect()
+}
+
+#[test]
+fn horizontal_lines_are_drawn_in_either_direction() {
+  assert_eq!(line((0, 1), (3, 1)), [(0, 1), (1, 1), (2, 1), (3, 1)]);
+  assert_eq!(line((2, 1), (1, 1)), [(1, 1), (2, 1)]);
+  assert_eq!(line((2, 2), (2, 2)), [(2, 2)]);
+}
+
+#[test]
+fn vertical_lines_are_drawn_in_either_direction() {
+  assert_eq!(line((3, 0), (3, 3)), [(3, 0), (3, 1), (3, 2), (3, 3)]);
+  assert_eq!(line((0, 2), (0, 1)), [(0, 1), (0, 2)]);
+}
+
+#[test]
+fn diagonal_lines_include_both_ends() {
+  assert_eq!(line((0, 0), (3, 3)), [(0, 0), (1, 1), (2, 2), (3, 3)]);
+  assert_eq!(line((3, 0), (0, 3)), [(3, 0), (2, 1), (1, 2), (0, 3)]);
+  // A shallow line steps down once, halfway along.
+  assert_eq!(line((0, 0), (3, 1)), [(0, 0), (1, 0), (2, 1), (3, 1)]);
+  // A steep line steps right once, halfway along.
+  assert_eq!(line((0, 0), (1, 3)), [(0, 0), (0, 1), (1, 2), (1, 3)]);
+}
+
+#[test]
+fn lines_are_clipped_to_the_buffer() {
+  assert_eq!(line((-10, 2), (10, 2)), [(0, 2), (1, 2), (2, 2), (3, 2)]);
+  assert_eq!(line((-1, 1), (2, -2)), [(0, 0)]);
+  assert_eq!(line((2, -5), (2, 0)), [(2, 0)]);
+  assert_eq!(
+    line((i32::MIN, i32::MIN), (i32::MAX, i32::MAX)),
+    [(0, 0), (1, 1), (2, 2), (3, 3)]
+  );
+  assert!(line((4, 0), (8, 3)).is_empty());
+  assert!(line((-5, -1), (5, -1)).is_empty());
+}
+
+/// Draws a circle on a 7x7 buffer, returning a string with a row per line,
+/// where `#` is a red pixel.
+fn circle(center: (i32, i32), radius: u16, fill: bool) -> String {
+  let mut buffer = ArrayFrameBuffer::<XRGB8888, 49, 7>::default();
+  let red = XRGB8888::new_with_raw_value(RED);
+  match fill {
+    true => buffer.fill_circle(center, radius, red),
+    false => buffer.draw_circle(center, radius, red),
+  }
+  buffer
+    .rows()
+    .map(|row| {
+      let row: String = row
+        .iter()
+        .map(|pixel| if pixel.raw_value() == RED { '#' } else { '.' })
+        .collect();
+      row + "\n"
+    })
+    .collect()
+}
+
+#[test]
+fn circles_are_drawn_with_the_midpoint_algorithm() {
+  let outline = "\
+    .......\n\
+    ..###..\n\
+    .#...#.\n\
+    .#...#.\n\
+    .#...#.\n\
+    ..###..\n\
+    .......\n";
+  assert_eq!(circle((3, 3), 2, false), outline);
+  let filled = "\
+    .......\n\
+    ..###..\n\
+    .#####.\n\
+    .#####.\n\
+    .#####.\n\
+    ..###..\n\
+    .......\n";
+  assert_eq!(circle((3, 3), 2, true), filled);
+}
+
+#[test]
+fn circles_of_radius_zero_are_a_single_pixel() {
+  let expected = "\
+    .......\n\
+    .......\n\
+    .......\n\
+    ...#...\n\
+    .......\n\
+    .......\n\
+    .......\n";
+  assert_eq!(circle((3, 3), 0, false), expected);
+  assert_eq!(circle((3, 3), 0, true), expected);
+}
+
+#[test]
+fn circles_are_clipped_to_the_buffer() {
+  let expected = "\
+    ##.....\n\
+    #......\n\
+    .......\n\
+    .......\n\
+    .......\n\
+    .......\n\
+    .......\n";
+  assert_eq!(circle((-1, -1), 2, true), expected);
+  let empty = ".......\n".repeat(7);
+  assert_eq!(circle((-3, 3), 2, false), empty);
+  assert_eq!(circle((3, i32::MAX), u16::MAX, true), empty);
+  assert_eq!(circle((i32::MIN, i32::MIN), 5, true), empty);
+}
+
+#[test]
+fn frames_are_written_as_binary_ppm() {
+  let buffer = ArrayFrameBuffer::<XRGB8888, 4, 2>::new(
+    [0x00FF0000, 0x0000FF00, 0x000000FF, 0x00102030].map(XRGB8888::new_with_raw_value),
+  );
+  let mut ppm = Vec::new();
+  buffer.write_ppm(&mut ppm).unwrap();
+  let header = b"P6\n2 2\n255\n";
+  assert_eq!(ppm.len(), header.len() + 2 * 2 * 3);
+  assert_eq!(&ppm[..header.len()], header);
+  assert_eq!(
+    &ppm[header.len()..],
+    [255, 0, 0, 0, 255, 0, 0, 0, 255, 0x10, 0x20, 0x30]
+  );
+}
+
+#[test]
+fn rgb888_frames_are_tightly_packed() {
+  let pixels = [0x00FF0000, 0x0000FF00, 0x000000FF].map(RGB888::new_with_raw_value);
+  let buffer = SliceFrameBuffer::with_width(&pixels[..], 3).unwrap();
+  assert_eq!(buffer.pitch(), 9);
+  assert_eq!(buffer.data(), [255, 0, 0, 0, 255, 0, 0, 0, 255]);
+  assert_eq!(pixels[1].g(), 255);
+  assert_eq!(pixels[2].raw_value(), 0x000000FF);
+  assert_eq!(
+    RGB888::new_with_raw_value(0xAA102030).raw_value(),
+    0x00102030
+  );
+
+  let mut ppm = Vec::new();
+  buffer.write_ppm(&mut ppm).unwrap();
+  assert_eq!(&ppm[b"P6\n3 1\n255\n".len()..], buffer.data());
+}
+
+#[test]
+fn narrow_channels_are_expanded_by_replicating_high_bits() {
+  let rgb565 = RGB565::new_with_raw_value(0b10000_100000_10000);
+  let xrgb: XRGB8888 = rgb565.convert();
+  assert_eq!(xrgb.raw_value(), 0x00848284);
+  let orgb: ORGB1555 = RGB565::new_with_raw_value(0xFFFF).convert();
+  assert_eq!(orgb.raw_value(), 0x7FFF);
+  let rgb: RGB888 = ORGB1555::new_with_raw_value(0x7FFF).convert();
+  assert_eq!(rgb.raw_value(), 0x00FFFFFF);
+}
+
+#[test]
+fn converting_to_a_wider_format_and_back_is_lossless() {
+  for raw in 0..=u16::MAX {
+    let rgb565 = RGB565::new_with_raw_value(raw);
+    assert_eq!(ConvertPixel::<RGB565>::convert(rgb565), rgb565);
+    let xrgb: XRGB8888 = rgb565.convert();
+    assert_eq!(ConvertPixel::<RGB565>::convert(xrgb), rgb565);
+    let orgb = ORGB1555::new_with_raw_value(raw & 0x7FFF);
+    let rgb: RGB888 = orgb.convert();
+    assert_eq!(ConvertPixel::<ORGB1555>::convert(rgb), orgb);
+  }
+}
+
+#[test]
+fn frames_are_converted_to_another_format() {
+  let pixels = [0xF800, 0x07E0, 0x001F, 0x0000].map(RGB565::new_with_raw_value);
+  let buffer = SliceFrameBuffer::with_width(&pixels[..], 2).unwrap();
+  let converted = buffer.convert_to::<XRGB8888>();
+  assert_eq!((converted.width(), converted.height()), (2, 2));
+  assert_eq!(
+    converted
+      .iter()
+      .map(|pixel| pixel.raw_value())
+      .collect::<Vec<_>>(),
+    [0x00FF0000, 0x0000FF00, 0x000000FF, 0x00000000]
+  );
+}
+
+fn blit(src_width: u16, src_height: u16) -> (u16, [u32; 9]) {
+  let src: Vec<_> = (1..=u32::from(src_width * src_height))
+    .map(XRGB8888::new_with_raw_value)
+    .collect();
+  let mut buffer = ArrayFrameBuffer::<XRGB8888, 9, 3>::default();
+  let rows = buffer.blit_from(&src, src_width);
+  (rows, buffer.map(|pixel| pixel.raw_value()))
+}
+
+#[test]
+fn blits_of_the_same_size_copy_everything() {
+  assert_eq!(blit(3, 3), (3, [1, 2, 3, 4, 5, 6, 7, 8, 9]));
+}
+
+#[test]
+fn blits_are_clipped_to_the_buffer() {
+  assert_eq!(blit(4, 4), (3, [1, 2, 3, 5, 6, 7, 9, 10, 11]));
+}
+
+#[test]
+fn smaller_blits_only_write_the_overlap() {
+  assert_eq!(blit(2, 1), (1, [1, 2, 0, 0, 0, 0, 0, 0, 0]));
+  assert_eq!(blit(0, 0), (0, [0; 9]));
+}
+
+#[test]
+fn partial_source_rows_are_skipped() {
+  let src = [1, 2, 3, 4, 5].map(XRGB8888::new_with_raw_value);
+  let mut buffer = ArrayFrameBuffer::<XRGB8888, 4, 2>::default();
+  assert_eq!(buffer.blit_from(&src, 2), 2);
+  assert_eq!(buffer.map(|pixel| pixel.raw_value()), [1, 2, 3, 4]);
+  assert_eq!(buffer.blit_from(&src[..1], 2), 0);
+}
+
+/// Blits the `src_rect` of a 3x3 sprite, whose center pixel is the color key,
+/// to `dst` on a 3x3 buffer filled with `0xAA`.
+fn blit_keyed(src_rect: (i32, i32, u16, u16), dst: (i32, i32)) -> [u32; 9] {
+  let sprite = ArrayFrameBuffer::<XRGB8888, 9, 3>::new(
+    [1, 2, 3, 4, 0, 6, 7, 8, 9].map(XRGB8888::new_with_raw_value),
+  );
+  let mut buffer = ArrayFrameBuffer::<XRGB8888, 9, 3>::default();
+  buffer.fill(XRGB8888::new_with_raw_value(0xAA));
+  buffer.blit_keyed(&sprite, src_rect, dst, XRGB8888::DEFAULT);
+  buffer.map(|pixel| pixel.raw_value())
+}
+
+#[test]
+fn keyed_pixels_are_skipped() {
+  assert_eq!(
+    blit_keyed((0, 0, 3, 3), (0, 0)),
+    [1, 2, 3, 4, 0xAA, 6, 7, 8, 9]
+  );
+  assert_eq!(
+    blit_keyed((1, 1, 2, 2), (0, 1)),
+    [0xAA, 0xAA, 0xAA, 0xAA, 6, 0xAA, 8, 9, 0xAA]
+  );
+}
+
+#[test]
+fn keyed_blits_are_clipped_to_both_buffers() {
+  // Clipped by the destination.
+  assert_eq!(
+    blit_keyed((0, 0, 3, 3), (-1, 2)),
+    [0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 2, 3, 0xAA]
+  );
+  // Clipped by the source.
+  assert_eq!(
+    blit_keyed((-1, 2, 9, 9), (0, 0)),
+    [0xAA, 7, 8, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA]
+  );
+  // Entirely outside of either.
+  let untouched = [0xAA; 9];
+  assert_eq!(blit_keyed((3, 0, 3, 3), (0, 0)), untouched);
+  assert_eq!(blit_keyed((0, 0, 3, 3), (i32::MIN, i32::MAX)), untouched);
+  assert_eq!(blit_keyed((i32::MAX, 0, u16::MAX, 1), (0, 0)), untouched);
+}
+
+#[test]
+fn gamma_of_one_is_a_no_op() {
+  let original = [0x00000000, 0x12345678, 0xFF808080, 0x00FFFFFF].map(XRGB8888::new_with_raw_value);
+  let mut buffer = SliceFrameBuffer::with_width(original.to_vec(), 2).unwrap();
+  buffer.apply_gamma(1.0);
+  assert_eq!(buffer.pixels(), original);
+
+  let original = [0x0000, 0x1234, 0xFFFF].map(RGB565::new_with_raw_value);
+  let mut buffer = SliceFrameBuffer::with_width(original.to_vec(), 3).unwrap();
+  buffer.apply_gamma(1.0);
+  assert_eq!(buffer.pixels(), original);
+}
+
+#[test]
+fn gamma_keeps_black_and_white() {
+  let original = [0x00000000, 0x00FFFFFF].map(XRGB8888::new_with_raw_value);
+  for gamma in [0.5, 2.2] {
+    let mut buffer = SliceFrameBuffer::with_width(original.to_vec(), 2).unwrap();
+    buffer.apply_gamma(gamma);
+    assert_eq!(buffer.pixels(), original);
+  }
+}
+
+#[test]
+#[should_panic]
+fn gamma_must_be_positive() {
+  let mut buffer = SliceFrameBuffer::with_width(pixels().to_vec(), 2).unwrap();
+  buffer.apply_gamma(0.0);
+}
+
+#[test]
+fn brightness_clamps_channels() {
+  let pixels = [0x00F01008, 0x00FFFFFF].map(XRGB8888::new_with_raw_value);
+  let mut buffer = SliceFrameBuffer::with_width(pixels.to_vec(), 2).unwrap();
+  buffer.apply_brightness(0x20);
+  let raw: Vec<_> = buffer
+    .pixels()
+    .iter()
+    .map(|pixel| pixel.raw_value())
+    .collect();
+  assert_eq!(raw, [0x00FF3028, 0x00FFFFFF]);
+
+  buffer.apply_brightness(-0x30);
+  let raw: Vec<_> = buffer
+    .pixels()
+    .iter()
+    .map(|pixel| pixel.raw_value())
+    .collect();
+  assert_eq!(raw, [0x00CF0000, 0x00CFCFCF]);
+
+  // 5-bit channels saturate too.
+  let mut buffer =
+    SliceFrameBuffer::with_width(vec![RGB565::new_with_raw_value(0xF81F)], 1).unwrap();
+  buffer.apply_brightness(i16::MAX);
+  assert_eq!(buffer[0].raw_value(), 0xFFFF);
+  buffer.apply_brightness(i16::MIN);
+  assert_eq!(buffer[0].raw_value(), 0x0000);
+}
@@ -0,0 +1,113 @@
+//! Requests a Vulkan context from a core defined in this test, through the
+//! symbols exported by [`libretro_core`], and drives its context callbacks.
+
+mod common;
+
+use libretro_rs::ffi::*;
+use libretro_rs::prelude::*;
+use std::sync::Mutex;
+
+/// The context callbacks the core was notified of, in order.
+static EVENTS: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+/// The hardware render callback the core sent to the frontend.
+static HW_RENDER: Mutex<Option<retro_hw_render_callback>> = Mutex::new(None);
+
+static NEGOTIATION_INTERFACE: retro_hw_render_context_negotiation_interface =
+  retro_hw_render_context_negotiation_interface {
+    interface_type:
+      retro_hw_render_context_negotiation_interface_type::RETRO_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE_VULKAN,
+    interface_version: 1,
+  };
+
+struct Core {
+  _rendering_mode: VulkanRenderEnabled,
+}
+
+impl<'a> retro::Core<'a> for Core {
+  type Init = ();
+
+  const SUPPORTS_NO_GAME: bool = true;
+
+  fn get_system_info() -> SystemInfo {
+    SystemInfo::new(c_utf8!("vulkan test"), c_utf8!("1.0"), ext![])
+  }
+
+  fn init(_env: &mut impl env::Init) -> Self::Init {}
+
+  fn load_without_content<E: env::LoadGame>(
+    args: LoadGameExtraArgs<'a, '_, E, Self::Init>,
+  ) -> Result<Self, CoreError> {
+    let env = args.env;
+    let options = VulkanOptions::new(1, 1, 0).set_debug_context(true);
+    let rendering_mode = env
+      .set_hw_render_vulkan(options)
+      .map_err(|_| CoreError::new())?;
+    unsafe { env.set_hw_render_context_negotiation_interface(&NEGOTIATION_INTERFACE) }
+      .map_err(|_| CoreError::new())?;
+    Ok(Core { _rendering_mode: rendering_mode })
+  }
+
+  fn get_system_av_info(&self, _env: &mut impl env::GetAvInfo) -> SystemAVInfo {
+    SystemAVInfo::default_timings(GameGeometry::fixed(1, 1))
+  }
+
+  fn run(&mut self, _env: &mut impl env::Run, callbacks: &mut impl Callbacks) -> InputsPolled {
+    callbacks.poll_inputs()
+  }
+
+  fn reset(&mut self, _env: &mut impl env::Reset) {}
+
+  fn unload_game(self, _env: &mut impl env::UnloadGame) -> Self::Init {}
+}
+
+impl<'a> retro::VulkanCore<'a> for Core {
+  fn context_reset(&mut self, _env: &mut impl env::Environment) {
+    EVENTS.lock().unwrap().push("reset");
+  }
+
+  fn context_destroy(&mut self, _env: &mut impl env::Environment) {
+    EVENTS.lock().unwrap().push("destroy");
+  }
+}
+
+test_core!();
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+  match cmd {
+    RETRO_ENVIRONMENT_SET_HW_RENDER => {
+      *HW_RENDER.lock().unwrap() = Some(*(data as *const retro_hw_render_callback));
+      true
+    }
+    RETRO_ENVIRONMENT_SET_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE => {
+      core::ptr::eq(data, &NEGOTIATION_INTERFACE as *const _ as *mut c_void)
+    }
+    _ => false,
+  }
+}
+
+#[test]
+fn vulkan_context_callbacks_reach_the_core() {
+  unsafe {
+    start(environment);
+  }
+
+  let hw_render = HW_RENDER.lock().unwrap().take().unwrap();
+  assert_eq!(
+    hw_render.context_type,
+    retro_hw_context_type::RETRO_HW_CONTEXT_VULKAN
+  );
+  assert_eq!(hw_render.version_major, (1 << 22) | (1 << 12));
+  assert!(hw_render.debug_context);
+
+  unsafe {
+    hw_render.context_reset.unwrap()();
+    hw_render.context_destroy.unwrap()();
+    hw_render.context_reset.unwrap()();
+  }
+  assert_eq!(*EVENTS.lock().unwrap(), ["reset", "destroy", "reset"]);
+
+  unsafe {
+    stop();
+  }
+}